@@ -1,8 +1,13 @@
+use std::collections::BTreeMap;
+
+use crate::key::KeySignature;
+
 #[allow(dead_code)]
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Default)]
 pub enum Accidental {
     DoubleSharp,
     Sharp,
+    #[default]
     None,
     Natural,
     Flat,
@@ -32,12 +37,102 @@ impl Accidental {
             _ => Err("Invalid accidental".into()),
         }
     }
+
+    /// Alteration in semitones, None if no accidental is given
+    pub fn get_alter(&self) -> Option<i8> {
+        match self {
+            Accidental::DoubleSharp => Some(2),
+            Accidental::Sharp => Some(1),
+            Accidental::None => None,
+            Accidental::Natural => Some(0),
+            Accidental::Flat => Some(-1),
+            Accidental::DoubleFlat => Some(-2),
+        }
+    }
+
+    pub fn from_alter(alter: i8) -> Self {
+        match alter {
+            2 => Accidental::DoubleSharp,
+            1 => Accidental::Sharp,
+            0 => Accidental::Natural,
+            -1 => Accidental::Flat,
+            -2 => Accidental::DoubleFlat,
+            _ => Accidental::None,
+        }
+    }
 }
 
-impl Default for Accidental {
-    fn default() -> Self {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AccidentalMode {
+    /// Accidentals are read as written: they last until the next barline, heads without accidental follow the bar and the key signature
+    #[default]
+    Written,
+    /// Heads give sounding pitch, no accidental means natural. What gets printed is decided by the engraver.
+    Sounding,
+}
+
+/// Accidental state of one staff, bar by bar
+#[derive(Debug)]
+pub struct AccidentalState {
+    key: KeySignature,
+    bar: BTreeMap<i8, i8>,
+    previous_bar: BTreeMap<i8, i8>,
+    last: BTreeMap<i8, i8>,
+}
+
+impl AccidentalState {
+    pub fn new(key: KeySignature) -> Self {
+        AccidentalState {
+            key,
+            bar: BTreeMap::new(),
+            previous_bar: BTreeMap::new(),
+            last: BTreeMap::new(),
+        }
+    }
+
+    pub fn set_key(&mut self, key: KeySignature) {
+        self.key = key;
+    }
+
+    pub fn barline(&mut self) {
+        self.previous_bar = std::mem::take(&mut self.bar);
+    }
+
+    /// Alteration of a head on a diatonic step. Tied heads without accidental keep the alteration of the head they are tied from.
+    pub fn get_alter(&self, step: i8, accidental: &Accidental, tied: bool, mode: AccidentalMode) -> i8 {
+        if let Some(alter) = accidental.get_alter() {
+            return alter;
+        }
+        if tied && let Some(alter) = self.last.get(&step) {
+            return *alter;
+        }
+        match mode {
+            AccidentalMode::Written => self.get_current(step),
+            AccidentalMode::Sounding => 0,
+        }
+    }
+
+    /// Accidental to print for a head, updating the state. Tied heads print nothing, explicit ones are printed even if redundant.
+    /// A head returning to the key signature after an alteration in the previous bar gets a cautionary accidental.
+    pub fn get_printed(&mut self, step: i8, alter: i8, tied: bool, explicit: bool) -> Accidental {
+        self.last.insert(step, alter);
+        if tied {
+            return Accidental::None;
+        }
+        if explicit || alter != self.get_current(step) {
+            self.bar.insert(step, alter);
+            return Accidental::from_alter(alter);
+        }
+        if !self.bar.contains_key(&step) && self.previous_bar.get(&step).is_some_and(|previous| *previous != alter) {
+            self.bar.insert(step, alter);
+            return Accidental::from_alter(alter);
+        }
         Accidental::None
     }
+
+    fn get_current(&self, step: i8) -> i8 {
+        self.bar.get(&step).copied().unwrap_or(self.key.get_alter(step))
+    }
 }
 
 #[cfg(test)]
@@ -50,4 +145,31 @@ mod tests {
         let a: Accidental = Accidental::parse("b").unwrap();
         assert!(a == Accidental::Flat);
     }
+
+    #[test]
+    fn test_state() {
+        // F4 is step 31
+        let mut state = AccidentalState::new(KeySignature::Neutral);
+        let alter = state.get_alter(31, &Accidental::Sharp, false, AccidentalMode::Written);
+        assert_eq!(state.get_printed(31, alter, false, false), Accidental::Sharp);
+        let alter = state.get_alter(31, &Accidental::None, false, AccidentalMode::Written);
+        assert_eq!((alter, state.get_printed(31, alter, false, false)), (1, Accidental::None));
+
+        // tied over the barline, then back to F natural with a cautionary natural
+        state.barline();
+        let alter = state.get_alter(31, &Accidental::None, true, AccidentalMode::Written);
+        assert_eq!((alter, state.get_printed(31, alter, true, false)), (1, Accidental::None));
+        let alter = state.get_alter(31, &Accidental::None, false, AccidentalMode::Written);
+        assert_eq!((alter, state.get_printed(31, alter, false, false)), (0, Accidental::Natural));
+        // a courtesy natural written out is kept
+        let alter = state.get_alter(31, &Accidental::Natural, false, AccidentalMode::Written);
+        assert_eq!((alter, state.get_printed(31, alter, false, true)), (0, Accidental::Natural));
+
+        // sounding pitch in G major: F# needs nothing, F needs a natural
+        let mut state = AccidentalState::new(KeySignature::Sharp1);
+        let alter = state.get_alter(31, &Accidental::Sharp, false, AccidentalMode::Sounding);
+        assert_eq!(state.get_printed(31, alter, false, false), Accidental::None);
+        let alter = state.get_alter(31, &Accidental::None, false, AccidentalMode::Sounding);
+        assert_eq!(state.get_printed(31, alter, false, false), Accidental::Natural);
+    }
 }
@@ -1,7 +1,63 @@
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum BarlineType {
     Invisible,
     Single,
     Double,
     Final,
+    Dashed,
+    Dotted,
+    Tick,
+    Heavy,
+    RepeatStart,
+    RepeatEnd,
+    RepeatBoth,
+}
+
+impl BarlineType {
+    pub const ALL: [BarlineType; 11] = [
+        BarlineType::Invisible,
+        BarlineType::Single,
+        BarlineType::Double,
+        BarlineType::Final,
+        BarlineType::Dashed,
+        BarlineType::Dotted,
+        BarlineType::Tick,
+        BarlineType::Heavy,
+        BarlineType::RepeatStart,
+        BarlineType::RepeatEnd,
+        BarlineType::RepeatBoth,
+    ];
+
+    /// Parses barline tokens like "bl", "bl||", "bl|.", "bl-", "bl:", "bl'", "bl!", "blx", or the repeats "bl|:", "bl:|" and "bl:|:"
+    pub fn find(s: &str) -> Self {
+        match s.trim().trim_start_matches("bl") {
+            "||" => BarlineType::Double,
+            "|." => BarlineType::Final,
+            "-" => BarlineType::Dashed,
+            ":" => BarlineType::Dotted,
+            "'" => BarlineType::Tick,
+            "!" => BarlineType::Heavy,
+            "x" => BarlineType::Invisible,
+            "|:" => BarlineType::RepeatStart,
+            ":|" => BarlineType::RepeatEnd,
+            ":|:" => BarlineType::RepeatBoth,
+            _ => BarlineType::Single,
+        }
+    }
+
+    pub fn get_code(&self) -> &'static str {
+        match self {
+            BarlineType::Invisible => "blx",
+            BarlineType::Single => "bl",
+            BarlineType::Double => "bl||",
+            BarlineType::Final => "bl|.",
+            BarlineType::Dashed => "bl-",
+            BarlineType::Dotted => "bl:",
+            BarlineType::Tick => "bl'",
+            BarlineType::Heavy => "bl!",
+            BarlineType::RepeatStart => "bl|:",
+            BarlineType::RepeatEnd => "bl:|",
+            BarlineType::RepeatBoth => "bl:|:",
+        }
+    }
 }
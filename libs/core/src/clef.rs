@@ -1,28 +1,117 @@
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 
 pub enum ClefSignature {
     None,
     Treble,
+    Treble8vb,
+    Treble8va,
+    FrenchViolin,
     Bass,
+    Bass8vb,
+    Soprano,
+    MezzoSoprano,
     Alto,
     Tenor,
+    Baritone,
     Percussion,
+    Tab,
 }
 
 impl ClefSignature {
+    /// Parses clef tokens. G clefs: "G", "G8vb", "G8va", "G1" (French violin). F clefs: "F", "F8vb".
+    /// C clefs by staff line counted from the bottom: "C1" (soprano) to "C5" (baritone), with "C" for alto and "T" for tenor.
+    /// "P" is percussion, "TAB" tablature, anything else no clef.
     pub fn find(s: &str) -> Self {
-        if s.contains("G") {
-            ClefSignature::Treble
-        } else if s.contains("F") {
-            ClefSignature::Bass
-        } else if s.contains("C") {
-            ClefSignature::Alto
-        } else if s.contains("T") {
-            ClefSignature::Tenor
-        } else if s.contains("P") {
-            ClefSignature::Percussion
-        } else {
-            ClefSignature::None
+        match s.trim().trim_end_matches("-added") {
+            "G" => ClefSignature::Treble,
+            "G8vb" => ClefSignature::Treble8vb,
+            "G8va" => ClefSignature::Treble8va,
+            "G1" => ClefSignature::FrenchViolin,
+            "F" => ClefSignature::Bass,
+            "F8vb" => ClefSignature::Bass8vb,
+            "C1" => ClefSignature::Soprano,
+            "C2" => ClefSignature::MezzoSoprano,
+            "C" | "C3" => ClefSignature::Alto,
+            "T" | "C4" => ClefSignature::Tenor,
+            "C5" => ClefSignature::Baritone,
+            "P" => ClefSignature::Percussion,
+            "TAB" => ClefSignature::Tab,
+            _ => ClefSignature::None,
+        }
+    }
+
+    pub fn get_code(&self) -> &'static str {
+        match self {
+            ClefSignature::None => "-",
+            ClefSignature::Treble => "G",
+            ClefSignature::Treble8vb => "G8vb",
+            ClefSignature::Treble8va => "G8va",
+            ClefSignature::FrenchViolin => "G1",
+            ClefSignature::Bass => "F",
+            ClefSignature::Bass8vb => "F8vb",
+            ClefSignature::Soprano => "C1",
+            ClefSignature::MezzoSoprano => "C2",
+            ClefSignature::Alto => "C",
+            ClefSignature::Tenor => "T",
+            ClefSignature::Baritone => "C5",
+            ClefSignature::Percussion => "P",
+            ClefSignature::Tab => "TAB",
+        }
+    }
+
+    /// Level of the staff line the clef is centered on: G for G clefs, F for F clefs, C4 for C clefs
+    pub fn get_line_level(&self) -> i8 {
+        match self {
+            ClefSignature::Treble | ClefSignature::Treble8vb | ClefSignature::Treble8va | ClefSignature::MezzoSoprano => 2,
+            ClefSignature::FrenchViolin | ClefSignature::Soprano => 4,
+            ClefSignature::Bass | ClefSignature::Bass8vb | ClefSignature::Tenor => -2,
+            ClefSignature::Baritone => -4,
+            ClefSignature::Alto | ClefSignature::Percussion | ClefSignature::Tab | ClefSignature::None => 0,
+        }
+    }
+
+    /// Diatonic step (see `Pitch`) of the middle staff line, level 0
+    pub fn get_middle_line_step(&self) -> i8 {
+        match self {
+            ClefSignature::Treble | ClefSignature::Percussion | ClefSignature::Tab | ClefSignature::None => 34, // B4
+            ClefSignature::Treble8vb => 27,                                                                     // B3
+            ClefSignature::Treble8va => 41,                                                                     // B5
+            ClefSignature::FrenchViolin => 36,                                                                  // D5
+            ClefSignature::Bass => 22,                                                                          // D3
+            ClefSignature::Bass8vb => 15,                                                                       // D2
+            ClefSignature::Soprano => 32,                                                                       // G4
+            ClefSignature::MezzoSoprano => 30,                                                                  // E4
+            ClefSignature::Alto => 28,                                                                          // C4
+            ClefSignature::Tenor => 26,                                                                         // A3
+            ClefSignature::Baritone => 24,                                                                      // F3
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test() {
+        assert_eq!(ClefSignature::find("T"), ClefSignature::Tenor);
+        assert_eq!(ClefSignature::find("G-added"), ClefSignature::Treble);
+
+        // the clef line carries the clef's own pitch: G4 = 32, F3 = 24, C4 = 28
+        for (clef, step) in [
+            (ClefSignature::Treble, 32),
+            (ClefSignature::FrenchViolin, 32),
+            (ClefSignature::Treble8vb, 25),
+            (ClefSignature::Bass, 24),
+            (ClefSignature::Bass8vb, 17),
+            (ClefSignature::Soprano, 28),
+            (ClefSignature::MezzoSoprano, 28),
+            (ClefSignature::Alto, 28),
+            (ClefSignature::Tenor, 28),
+            (ClefSignature::Baritone, 28),
+        ] {
+            assert_eq!(clef.get_middle_line_step() - clef.get_line_level(), step);
         }
     }
 }
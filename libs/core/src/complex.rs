@@ -117,7 +117,6 @@ impl ComplexUtils {
                 ComplexType::Lower(notes.get(*note_id).unwrap().clone())
             };
 
-            ComplexType::Upper(notes.get(*note_id).unwrap().clone());
             let id = cx.complexes.borrow().len();
             let complex = Complex {
                 id,
@@ -125,7 +124,7 @@ impl ComplexUtils {
                 position: note.position,
                 duration: *map_durations.get(&note.position).unwrap(),
                 position2: Some(column_position + note.position),
-                ctype: ctype,
+                ctype,
                 offsets: ComplexHeadOffsets::None,
             };
             cx.map_noteid_complexid.borrow_mut().insert(*note_id, id as ComplexId);
@@ -157,8 +156,8 @@ impl ComplexUtils {
 
             // println!("upper: note_id:{}, note_position:{}", note_id, note_position);
 
-            if !map.contains_key(&note_position) {
-                map.insert(note_position, vec![Some(*note_id), None]);
+            if let std::collections::btree_map::Entry::Vacant(e) = map.entry(note_position) {
+                e.insert(vec![Some(*note_id), None]);
             } else {
                 let notes = map.get_mut(&note_position).unwrap();
                 notes.push(Some(*note_id));
@@ -171,8 +170,8 @@ impl ComplexUtils {
 
             // println!("lower: note_id:{}, note_position:{}", note_id, note_position);
 
-            if !map.contains_key(&note_position) {
-                map.insert(note_position, vec![None, Some(*note_id)]);
+            if let std::collections::btree_map::Entry::Vacant(e) = map.entry(note_position) {
+                e.insert(vec![None, Some(*note_id)]);
             } else {
                 let notes = map.get_mut(&note_position).unwrap();
                 if notes[1].is_none() {
@@ -184,7 +183,7 @@ impl ComplexUtils {
         }
 
         // calculate complex durations
-        let mut positions: Vec<usize> = map.keys().map(|p| *p).collect::<Vec<_>>();
+        let mut positions: Vec<usize> = map.keys().copied().collect::<Vec<_>>();
 
         positions.push(part_duration);
         positions.sort();
@@ -247,8 +246,8 @@ impl ComplexUtils {
                     cx.map_noteid_configuration.borrow_mut().insert(note.id, NoteConfiguration::SingleLower);
                 }
                 ComplexType::UpperAndLower(ref upper, ref lower, _) => {
-                    cx.map_noteid_configuration.borrow_mut().insert(*&upper.id, NoteConfiguration::DoubleUpper);
-                    cx.map_noteid_configuration.borrow_mut().insert(*&lower.id, NoteConfiguration::DoubleLower);
+                    cx.map_noteid_configuration.borrow_mut().insert(upper.id, NoteConfiguration::DoubleUpper);
+                    cx.map_noteid_configuration.borrow_mut().insert(lower.id, NoteConfiguration::DoubleLower);
                 }
             }
 
@@ -261,7 +260,7 @@ impl ComplexUtils {
                 position: *position,
                 duration: *map_durations.get(position).unwrap(),
                 position2: Some(column_position + *position),
-                ctype: ctype,
+                ctype,
                 offsets,
             };
 
@@ -270,10 +269,8 @@ impl ComplexUtils {
 
             //-----------------------------------------------------
             // store complex in context
-            for note_id in note_ids {
-                if let Some(note_id) = note_id {
-                    cx.map_noteid_complexid.borrow_mut().insert(*note_id, id as ComplexId);
-                }
+            for note_id in note_ids.iter().flatten() {
+                cx.map_noteid_complexid.borrow_mut().insert(*note_id, id as ComplexId);
             }
         }
         // store partid_complexids in context
@@ -284,6 +281,11 @@ impl ComplexUtils {
     fn calculate_head_offsets(cx: &CoreContext, ctype: &ComplexType) -> ComplexHeadOffsets {
         match ctype {
             ComplexType::UpperAndLower(upper, lower, level_diff) => {
+                // voices drawn on different staves do not collide
+                let map_noteid_staffoffset = cx.map_noteid_staffoffset.borrow();
+                if map_noteid_staffoffset.get(&upper.id) != map_noteid_staffoffset.get(&lower.id) {
+                    return ComplexHeadOffsets::None;
+                }
                 //store offsets for notes?
                 match level_diff {
                     _ if *level_diff <= 0 => {
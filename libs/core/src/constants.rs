@@ -1 +1,3 @@
 pub const STEM_DEFAULT_LENGTH: f32 = 7.0;
+pub const STEM_CROSS_STAFF_MIN_LENGTH: f32 = 5.0;
+pub const BEAM_THICKNESS_LEVELS: f32 = 1.0;
@@ -1,12 +1,16 @@
 use std::{cell::RefCell, collections::BTreeMap};
 
 use crate::{
+    accidental::{Accidental, AccidentalMode},
+    clef::ClefSignature,
     complex::{Complex, ComplexId},
     direction::DirectionUD,
+    group::StaffGroup,
     head::{HeadId, HeadItem},
     hpart::{HPartItem, HPartItemsColumn, HPartItemsRow},
-    note::{NoteConfiguration, NoteId, NoteItem},
-    part::PartItem,
+    note::{NoteColor, NoteConfiguration, NoteId, NoteItem},
+    part::{PartHeader, PartItem},
+    repeat::{NavigationMark, Volta},
     stems::stemitems::{StemHeadPosition, StemItem},
     sysitem::SysItem,
     ties::{CheckedTieFrom, CheckedTieTo, TieFrom, TieTo},
@@ -39,6 +43,22 @@ pub struct CoreContext {
     pub map_noteid_tiesfrom: RefCell<BTreeMap<NoteId, Vec<TieFrom>>>,
     pub map_noteid_resolvedtiesto: RefCell<BTreeMap<NoteId, Vec<CheckedTieTo>>>,
     pub map_noteid_resolvedtiesfrom: RefCell<BTreeMap<NoteId, Vec<CheckedTieFrom>>>,
+
+    pub accidental_mode: RefCell<AccidentalMode>,
+    pub map_headid_alter: RefCell<BTreeMap<HeadId, i8>>,
+    pub map_headid_accidental: RefCell<BTreeMap<HeadId, Accidental>>,
+
+    pub voltas: RefCell<Vec<Volta>>,
+    pub map_colidx_navigations: RefCell<BTreeMap<usize, Vec<NavigationMark>>>,
+
+    pub map_noteid_clef: RefCell<BTreeMap<NoteId, ClefSignature>>,
+    pub map_noteid_restlevel: RefCell<BTreeMap<NoteId, i8>>,
+    /// Notes drawn on a neighbouring staff of their part, 1 for the staff below and -1 for the one above
+    pub map_noteid_staffoffset: RefCell<BTreeMap<NoteId, i8>>,
+    pub map_noteid_color: RefCell<BTreeMap<NoteId, NoteColor>>,
+
+    pub staff_groups: RefCell<Vec<StaffGroup>>,
+    pub map_partidx_header: RefCell<BTreeMap<usize, PartHeader>>,
 }
 
 impl CoreContext {
@@ -71,6 +91,21 @@ impl CoreContext {
             map_noteid_tiesfrom: RefCell::new(BTreeMap::new()),
             map_noteid_resolvedtiesto: RefCell::new(BTreeMap::new()),
             map_noteid_resolvedtiesfrom: RefCell::new(BTreeMap::new()),
+
+            accidental_mode: RefCell::new(AccidentalMode::default()),
+            map_headid_alter: RefCell::new(BTreeMap::new()),
+            map_headid_accidental: RefCell::new(BTreeMap::new()),
+
+            voltas: RefCell::new(Vec::new()),
+            map_colidx_navigations: RefCell::new(BTreeMap::new()),
+
+            map_noteid_clef: RefCell::new(BTreeMap::new()),
+            map_noteid_restlevel: RefCell::new(BTreeMap::new()),
+            map_noteid_staffoffset: RefCell::new(BTreeMap::new()),
+            map_noteid_color: RefCell::new(BTreeMap::new()),
+
+            staff_groups: RefCell::new(Vec::new()),
+            map_partidx_header: RefCell::new(BTreeMap::new()),
         };
         Box::leak(Box::new(cx))
     }
@@ -4,6 +4,7 @@ pub type SumDuration = usize;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 #[allow(dead_code)]
+#[derive(Default)]
 pub enum NoteDuration {
     D1Dot = 144,
     D1 = 96,
@@ -11,6 +12,7 @@ pub enum NoteDuration {
     D2 = 48,
     D4Dot = 36,
     D2Tri = 32,
+    #[default]
     D4 = 24,
     D8Dot = 18,
     D4Tri = 16,
@@ -50,35 +52,27 @@ impl TryFrom<usize> for NoteDuration {
 impl NoteDuration {
     #[allow(dead_code)]
     pub fn is_beamable(self) -> bool {
-        match self {
-            NoteDuration::D8 | NoteDuration::D16 | NoteDuration::D32 => true,
-            NoteDuration::D8Tri | NoteDuration::D16Tri => true,
-            NoteDuration::D8Dot | NoteDuration::D16Dot => true,
-            _ => false,
-        }
+        matches!(
+            self,
+            NoteDuration::D8 | NoteDuration::D16 | NoteDuration::D32 | NoteDuration::D8Tri | NoteDuration::D16Tri | NoteDuration::D8Dot | NoteDuration::D16Dot
+        )
     }
 
     pub fn is_dotted(self) -> bool {
-        match self {
-            NoteDuration::D1Dot | NoteDuration::D2Dot | NoteDuration::D4Dot | NoteDuration::D8Dot | NoteDuration::D16Dot => true,
-            _ => false,
-        }
+        matches!(self, NoteDuration::D1Dot | NoteDuration::D2Dot | NoteDuration::D4Dot | NoteDuration::D8Dot | NoteDuration::D16Dot)
     }
 
     #[allow(dead_code)]
     pub fn has_stem(self) -> bool {
-        match self {
-            NoteDuration::D1 | NoteDuration::D1Dot => false,
-            _ => true,
-        }
+        !matches!(self, NoteDuration::D1 | NoteDuration::D1Dot)
     }
 
     #[allow(dead_code)]
     pub fn has_flag(self) -> bool {
-        match self {
-            NoteDuration::D8 | NoteDuration::D8Dot | NoteDuration::D8Tri | NoteDuration::D16 | NoteDuration::D16Dot | NoteDuration::D16Tri | NoteDuration::D32 => true,
-            _ => false,
-        }
+        matches!(
+            self,
+            NoteDuration::D8 | NoteDuration::D8Dot | NoteDuration::D8Tri | NoteDuration::D16 | NoteDuration::D16Dot | NoteDuration::D16Tri | NoteDuration::D32
+        )
     }
 
     pub fn parse(s: &str) -> Result<NoteDuration, Box<dyn std::error::Error>> {
@@ -144,12 +138,6 @@ impl NoteDuration {
     }
 }
 
-impl Default for NoteDuration {
-    fn default() -> Self {
-        NoteDuration::D4
-    }
-}
-
 pub struct DurationUtils;
 impl DurationUtils {
     pub fn durations_smallest_base_value(durations: &[NoteDuration]) -> i8 {
@@ -0,0 +1,96 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StaffGroupType {
+    Brace,
+    Bracket,
+    SubBracket,
+}
+
+impl StaffGroupType {
+    pub fn find(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "brace" => Some(StaffGroupType::Brace),
+            "bracket" => Some(StaffGroupType::Bracket),
+            "sub" | "subbracket" => Some(StaffGroupType::SubBracket),
+            _ => None,
+        }
+    }
+
+    pub fn get_code(&self) -> &'static str {
+        match self {
+            StaffGroupType::Brace => "brace",
+            StaffGroupType::Bracket => "bracket",
+            StaffGroupType::SubBracket => "sub",
+        }
+    }
+}
+
+/// Staves `from_part_idx..=to_part_idx` joined by a brace or bracket, with barlines drawn through them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaffGroup {
+    pub gtype: StaffGroupType,
+    pub from_part_idx: usize,
+    pub to_part_idx: usize,
+}
+
+impl StaffGroup {
+    /// Parses groups like "bracket 1-4 sub 1-2 brace 5-6", with parts counted from 1 up to `parts_count`
+    pub fn find_groups(segments: &[String], parts_count: usize) -> Result<Vec<StaffGroup>, Box<dyn std::error::Error>> {
+        segments
+            .chunks(2)
+            .map(|pair| {
+                let gtype = StaffGroupType::find(&pair[0]).ok_or(format!("Unknown staff group '{}'", pair[0]))?;
+                let range = pair.get(1).ok_or(format!("Staff group '{}' without parts", pair[0]))?;
+                let (from, to) = range.split_once('-').unwrap_or((range, range));
+                let (from, to) = (from.trim().parse::<usize>()?, to.trim().parse::<usize>()?);
+                if from == 0 || to < from {
+                    return Err(format!("Invalid staff group parts '{}'", range).into());
+                }
+                if to > parts_count {
+                    return Err(format!("Staff group parts '{}' beyond the {} parts", range, parts_count).into());
+                }
+                Ok(StaffGroup {
+                    gtype,
+                    from_part_idx: from - 1,
+                    to_part_idx: to - 1,
+                })
+            })
+            .collect()
+    }
+
+    pub fn get_code(&self) -> String {
+        if self.from_part_idx == self.to_part_idx {
+            format!("{} {}", self.gtype.get_code(), self.from_part_idx + 1)
+        } else {
+            format!("{} {}-{}", self.gtype.get_code(), self.from_part_idx + 1, self.to_part_idx + 1)
+        }
+    }
+
+    /// True if barlines are drawn through from the staff `part_idx` to the one below
+    pub fn connects(groups: &[StaffGroup], part_idx: usize) -> bool {
+        groups.iter().any(|group| group.from_part_idx <= part_idx && part_idx < group.to_part_idx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test() {
+        let segments = ["bracket", "1-4", "sub", "1-2", "brace", "5-6"].map(|s| s.to_string());
+        let groups = StaffGroup::find_groups(&segments, 6).unwrap();
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[1].gtype, StaffGroupType::SubBracket);
+        assert_eq!((groups[2].from_part_idx, groups[2].to_part_idx), (4, 5));
+        assert_eq!(groups.iter().map(|group| group.get_code()).collect::<Vec<_>>().join(" "), "bracket 1-4 sub 1-2 brace 5-6");
+
+        // barlines are broken between the choir and the piano
+        assert!(StaffGroup::connects(&groups, 2));
+        assert!(!StaffGroup::connects(&groups, 3));
+        assert!(StaffGroup::connects(&groups, 4));
+
+        assert!(StaffGroup::find_groups(&["brace".to_string(), "2-1".to_string()], 6).is_err());
+        assert!(StaffGroup::find_groups(&["bracket".to_string(), "1-9".to_string()], 2).is_err());
+    }
+}
@@ -47,6 +47,23 @@ pub enum HPartType {
     Music { mtype: HPartMusicType, complexes: Vec<usize>, attr: HPartAttributes },
 }
 
+impl HPartType {
+    /// Music with bar pauses only, drawn as a rest centred in the bar
+    pub fn is_bar_rest(&self) -> bool {
+        match self {
+            HPartType::Music {
+                mtype: HPartMusicType::OneVoice { voice },
+                ..
+            } => matches!(voice, VoiceType2::Barpause(_)),
+            HPartType::Music {
+                mtype: HPartMusicType::TwoVoices { upper, lower },
+                ..
+            } => matches!((upper, lower), (VoiceType2::Barpause(_), VoiceType2::Barpause(_))),
+            _ => false,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct HPartAttributes {
     pub clef: ClefSignature,
@@ -1,8 +1,93 @@
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum KeySignature {
+    Sharp7,
+    Sharp6,
+    Sharp5,
+    Sharp4,
+    Sharp3,
     Sharp2,
-    Sparp1,
+    Sharp1,
     Neutral,
     Flat1,
     Flat2,
+    Flat3,
+    Flat4,
+    Flat5,
+    Flat6,
+    Flat7,
+}
+
+// Diatonic steps (C=0 .. B=6) in the order they are altered by the key signature
+const SHARPS_ORDER: [i8; 7] = [3, 0, 4, 1, 5, 2, 6];
+const FLATS_ORDER: [i8; 7] = [6, 2, 5, 1, 4, 0, 3];
+
+impl KeySignature {
+    /// Parses key tokens like "0", "2#", "##", "3b" or "bbb"
+    pub fn find(s: &str) -> Self {
+        let count = match s.chars().filter(|c| c.is_ascii_digit()).collect::<String>().parse::<i8>() {
+            Ok(count) => count,
+            Err(_) => s.chars().filter(|c| *c == '#' || *c == 'b').count() as i8,
+        };
+        if s.contains('#') {
+            KeySignature::from_fifths(count)
+        } else if s.contains('b') {
+            KeySignature::from_fifths(-count)
+        } else {
+            KeySignature::Neutral
+        }
+    }
+
+    /// Number of sharps (positive) or flats (negative)
+    pub fn fifths(&self) -> i8 {
+        match self {
+            KeySignature::Sharp7 => 7,
+            KeySignature::Sharp6 => 6,
+            KeySignature::Sharp5 => 5,
+            KeySignature::Sharp4 => 4,
+            KeySignature::Sharp3 => 3,
+            KeySignature::Sharp2 => 2,
+            KeySignature::Sharp1 => 1,
+            KeySignature::Neutral => 0,
+            KeySignature::Flat1 => -1,
+            KeySignature::Flat2 => -2,
+            KeySignature::Flat3 => -3,
+            KeySignature::Flat4 => -4,
+            KeySignature::Flat5 => -5,
+            KeySignature::Flat6 => -6,
+            KeySignature::Flat7 => -7,
+        }
+    }
+
+    pub fn from_fifths(fifths: i8) -> Self {
+        match fifths.clamp(-7, 7) {
+            7 => KeySignature::Sharp7,
+            6 => KeySignature::Sharp6,
+            5 => KeySignature::Sharp5,
+            4 => KeySignature::Sharp4,
+            3 => KeySignature::Sharp3,
+            2 => KeySignature::Sharp2,
+            1 => KeySignature::Sharp1,
+            -1 => KeySignature::Flat1,
+            -2 => KeySignature::Flat2,
+            -3 => KeySignature::Flat3,
+            -4 => KeySignature::Flat4,
+            -5 => KeySignature::Flat5,
+            -6 => KeySignature::Flat6,
+            -7 => KeySignature::Flat7,
+            _ => KeySignature::Neutral,
+        }
+    }
+
+    /// Alteration implied by the key signature for a diatonic step
+    pub fn get_alter(&self, step: i8) -> i8 {
+        let letter = step.rem_euclid(7);
+        let fifths = self.fifths();
+        if fifths > 0 && SHARPS_ORDER[..fifths as usize].contains(&letter) {
+            1
+        } else if fifths < 0 && FLATS_ORDER[..(-fifths) as usize].contains(&letter) {
+            -1
+        } else {
+            0
+        }
+    }
 }
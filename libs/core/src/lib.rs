@@ -6,16 +6,20 @@ pub mod constants;
 pub mod context;
 pub mod direction;
 pub mod duration;
+pub mod group;
 pub mod head;
 pub mod hpart;
 pub mod key;
 pub mod note;
 pub mod part;
+pub mod pitch;
+pub mod repeat;
 pub mod rest;
 pub mod stems;
 pub mod sysitem;
 pub mod ties;
 pub mod time;
+pub mod transpose;
 pub mod voice;
 // pub type ItemId = usize;
 
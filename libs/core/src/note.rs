@@ -64,3 +64,55 @@ impl NoteItem {
         }
     }
 }
+
+// colours a note can be given by name
+const NAMED_COLORS: [(&str, (u8, u8, u8)); 6] = [
+    ("red", (255, 0, 0)),
+    ("green", (0, 128, 0)),
+    ("blue", (0, 0, 255)),
+    ("orange", (255, 165, 0)),
+    ("purple", (128, 0, 128)),
+    ("gray", (128, 128, 128)),
+];
+
+/// Colour of a note set in the input, for marking notes. The highlight colour is the one of the output's theme
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteColor {
+    Highlight,
+    /// A colour given by name, kept so that the input is written back as it was
+    Named(&'static str),
+    RGB(u8, u8, u8),
+}
+
+impl NoteColor {
+    /// Parses "highlight", "#rrggbb" and the names red, green, blue, orange, purple and gray
+    pub fn find(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s == "highlight" {
+            return Some(NoteColor::Highlight);
+        }
+        if let Some((name, _)) = NAMED_COLORS.iter().find(|(name, _)| *name == s) {
+            return Some(NoteColor::Named(name));
+        }
+        let hex = s.strip_prefix('#').filter(|hex| hex.len() == 6 && hex.is_ascii())?;
+        let channel = |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16).ok();
+        Some(NoteColor::RGB(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    pub fn get_code(&self) -> String {
+        match self {
+            NoteColor::Highlight => "highlight".to_string(),
+            NoteColor::Named(name) => name.to_string(),
+            NoteColor::RGB(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        }
+    }
+
+    /// Red, green and blue of the colour, none for the highlight which is up to the theme
+    pub fn get_rgb(&self) -> Option<(u8, u8, u8)> {
+        match self {
+            NoteColor::Highlight => None,
+            NoteColor::Named(name) => NAMED_COLORS.iter().find(|(named, _)| named == name).map(|(_, rgb)| *rgb),
+            NoteColor::RGB(r, g, b) => Some((*r, *g, *b)),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{duration::SumDuration, voice::VoiceItem};
+use crate::{duration::SumDuration, transpose::Interval, voice::VoiceItem};
 
 pub type PartId = usize;
 
@@ -18,3 +18,51 @@ pub struct PartItem {
     pub ptype: PartType,
     pub complexids: Vec<usize>,
 }
+
+/// Per-part metadata from a `part` item, like `part 1 name="Clarinet in Bb" short="Cl." transp=M2 program=71`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PartHeader {
+    pub name: String,
+    pub short_name: String,
+    /// Written pitch relative to concert pitch, M2 for a Bb clarinet
+    pub transposition: Option<Interval>,
+    pub program: Option<u8>,
+}
+
+impl PartHeader {
+    /// Part index (counted from 1 in the segments) and header from `key=value` segments, with the quotes already removed
+    pub fn find(segments: &[String]) -> Result<(usize, PartHeader), Box<dyn std::error::Error>> {
+        let number = segments.first().ok_or("Part header without part number")?.parse::<usize>()?;
+        if number == 0 {
+            return Err("Part numbers start at 1".into());
+        }
+        let mut header = PartHeader::default();
+        for segment in segments.iter().skip(1) {
+            match segment.split_once('=').ok_or(format!("Invalid part header segment '{}'", segment))? {
+                ("name", value) => header.name = value.to_string(),
+                ("short", value) => header.short_name = value.to_string(),
+                ("transp", value) => header.transposition = Some(Interval::parse(value)?),
+                ("program", value) => header.program = Some(value.parse::<u8>()?),
+                (key, _) => return Err(format!("Unknown part header key '{}'", key).into()),
+            }
+        }
+        Ok((number - 1, header))
+    }
+
+    pub fn get_code(&self, part_idx: usize) -> String {
+        let mut segments = vec![format!("part {}", part_idx + 1)];
+        if !self.name.is_empty() {
+            segments.push(format!("name=\"{}\"", self.name));
+        }
+        if !self.short_name.is_empty() {
+            segments.push(format!("short=\"{}\"", self.short_name));
+        }
+        if let Some(transposition) = &self.transposition {
+            segments.push(format!("transp={}", transposition.get_code()));
+        }
+        if let Some(program) = self.program {
+            segments.push(format!("program={}", program));
+        }
+        segments.join(" ")
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{accidental::Accidental, clef::ClefSignature, key::KeySignature};
+
+// Semitones above C for the diatonic steps C D E F G A B
+const STEP_SEMITONES: [i8; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+/// Pitch as diatonic step counted from C0 (C4 = 28) and alteration in semitones
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pitch {
+    pub step: i8,
+    pub alter: i8,
+}
+
+impl Pitch {
+    pub fn new(step: i8, alter: i8) -> Self {
+        Pitch { step, alter }
+    }
+
+    /// Head level read against a clef. The alteration comes from the accidental, or from the key signature if there is none
+    pub fn from_level(clef: &ClefSignature, key: &KeySignature, level: i8, accidental: &Accidental) -> Self {
+        let step = clef.get_middle_line_step() - level;
+        let alter = accidental.get_alter().unwrap_or(key.get_alter(step));
+        Pitch { step, alter }
+    }
+
+    pub fn get_level(&self, clef: &ClefSignature) -> i8 {
+        clef.get_middle_line_step() - self.step
+    }
+
+    /// Midi note number, C4 = 60
+    pub fn get_semitones(&self) -> i16 {
+        Pitch::step_semitones(self.step) + self.alter as i16
+    }
+
+    fn step_semitones(step: i8) -> i16 {
+        let octave = step.div_euclid(7) as i16;
+        (octave + 1) * 12 + STEP_SEMITONES[step.rem_euclid(7) as usize] as i16
+    }
+
+    /// Enharmonic spelling of a double sharp or double flat using the neighbouring steps.
+    /// A spelling matching the key signature wins, otherwise sharps are preferred in sharp keys and flats in flat keys.
+    pub fn respell(&self, key: &KeySignature) -> Self {
+        if self.alter.abs() < 2 {
+            return *self;
+        }
+        let semitones = self.get_semitones();
+        let candidates = [self.step - 1, self.step + 1]
+            .iter()
+            .map(|step| Pitch::new(*step, (semitones - Pitch::step_semitones(*step)) as i8))
+            .filter(|pitch| pitch.alter.abs() < 2)
+            .collect::<Vec<_>>();
+
+        if let Some(pitch) = candidates.iter().find(|pitch| pitch.alter == key.get_alter(pitch.step)) {
+            return *pitch;
+        }
+        let prefer_flats = key.fifths() < 0;
+        candidates
+            .iter()
+            .find(|pitch| if prefer_flats { pitch.alter <= 0 } else { pitch.alter >= 0 })
+            .or(candidates.first())
+            .cloned()
+            .unwrap_or(*self)
+    }
+
+    /// Accidental to write in front of the head, None if the key signature already implies the alteration
+    pub fn get_accidental(&self, key: &KeySignature) -> Accidental {
+        if self.alter == key.get_alter(self.step) {
+            Accidental::None
+        } else {
+            Accidental::from_alter(self.alter)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test() {
+        let c4 = Pitch::from_level(&ClefSignature::Treble, &KeySignature::Neutral, 6, &Accidental::None);
+        assert_eq!(c4, Pitch::new(28, 0));
+        assert_eq!(c4.get_semitones(), 60);
+        assert_eq!(c4.get_level(&ClefSignature::Bass), -6);
+
+        let f_sharp = Pitch::from_level(&ClefSignature::Treble, &KeySignature::Sharp1, 3, &Accidental::None);
+        assert_eq!(f_sharp.alter, 1);
+
+        let b_double_flat = Pitch::new(34, -2);
+        assert_eq!(b_double_flat.respell(&KeySignature::Flat2), Pitch::new(33, 0));
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    barline::BarlineType,
+    context::CoreContext,
+    hpart::{HPartItemsColumnType, HPartMusicType, HPartType, VoiceType2},
+    note::NoteId,
+};
+
+/// Ending bracket over the columns `from_col_idx..to_col_idx`, played on the passes listed in `numbers`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Volta {
+    pub numbers: Vec<u8>,
+    pub from_col_idx: usize,
+    pub to_col_idx: usize,
+    /// Ends with a hook, i.e. the ending finishes at a repeat barline
+    pub closed: bool,
+}
+
+impl Volta {
+    /// Parses volta numbers like "1", "1,2" or "1 2". No numbers closes the previous volta
+    pub fn find_numbers(segments: &[String]) -> Vec<u8> {
+        segments.iter().flat_map(|s| s.split(',')).filter_map(|s| s.trim().trim_end_matches('.').parse::<u8>().ok()).collect()
+    }
+
+    pub fn get_label(&self) -> String {
+        self.numbers.iter().map(|number| format!("{}.", number)).collect::<Vec<_>>().join(" ")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NavigationMark {
+    Segno,
+    Coda,
+    ToCoda,
+    Fine,
+    DaCapo,
+    DaCapoAlFine,
+    DaCapoAlCoda,
+    DalSegno,
+    DalSegnoAlFine,
+    DalSegnoAlCoda,
+}
+
+impl NavigationMark {
+    pub fn find(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "segno" => Some(NavigationMark::Segno),
+            "coda" => Some(NavigationMark::Coda),
+            "tocoda" => Some(NavigationMark::ToCoda),
+            "fine" => Some(NavigationMark::Fine),
+            "dc" => Some(NavigationMark::DaCapo),
+            "dc-fine" => Some(NavigationMark::DaCapoAlFine),
+            "dc-coda" => Some(NavigationMark::DaCapoAlCoda),
+            "ds" => Some(NavigationMark::DalSegno),
+            "ds-fine" => Some(NavigationMark::DalSegnoAlFine),
+            "ds-coda" => Some(NavigationMark::DalSegnoAlCoda),
+            _ => None,
+        }
+    }
+
+    pub fn get_code(&self) -> &'static str {
+        match self {
+            NavigationMark::Segno => "segno",
+            NavigationMark::Coda => "coda",
+            NavigationMark::ToCoda => "tocoda",
+            NavigationMark::Fine => "fine",
+            NavigationMark::DaCapo => "dc",
+            NavigationMark::DaCapoAlFine => "dc-fine",
+            NavigationMark::DaCapoAlCoda => "dc-coda",
+            NavigationMark::DalSegno => "ds",
+            NavigationMark::DalSegnoAlFine => "ds-fine",
+            NavigationMark::DalSegnoAlCoda => "ds-coda",
+        }
+    }
+
+    /// Words of the mark. The segno and coda signs are music glyphs, drawn by the renderer
+    pub fn get_label(&self) -> Option<&'static str> {
+        match self {
+            NavigationMark::Segno | NavigationMark::Coda => None,
+            NavigationMark::ToCoda => Some("To Coda"),
+            NavigationMark::Fine => Some("Fine"),
+            NavigationMark::DaCapo => Some("D.C."),
+            NavigationMark::DaCapoAlFine => Some("D.C. al Fine"),
+            NavigationMark::DaCapoAlCoda => Some("D.C. al Coda"),
+            NavigationMark::DalSegno => Some("D.S."),
+            NavigationMark::DalSegnoAlFine => Some("D.S. al Fine"),
+            NavigationMark::DalSegnoAlCoda => Some("D.S. al Coda"),
+        }
+    }
+
+    fn is_da_capo(&self) -> bool {
+        matches!(self, NavigationMark::DaCapo | NavigationMark::DaCapoAlFine | NavigationMark::DaCapoAlCoda)
+    }
+
+    fn is_dal_segno(&self) -> bool {
+        matches!(self, NavigationMark::DalSegno | NavigationMark::DalSegnoAlFine | NavigationMark::DalSegnoAlCoda)
+    }
+}
+
+pub struct RepeatUtils;
+impl RepeatUtils {
+    /// Column indices in playback order, with repeats, voltas and navigation marks applied.
+    /// Repeats are not taken again after a D.C. or D.S. jump, Fine and To Coda only apply after it and To Coda only once.
+    pub fn expand_columns(cx: &CoreContext) -> Result<Vec<usize>, Box<dyn std::error::Error>> {
+        let columns = cx.columns.borrow();
+        let hparts = cx.hparts.borrow();
+        let voltas = cx.voltas.borrow();
+        let map_colidx_navigations = cx.map_colidx_navigations.borrow();
+
+        let barline_at = |col_idx: usize| -> Option<BarlineType> {
+            match &columns.get(col_idx)?.hptype {
+                HPartItemsColumnType::Barlines(ids) => match &hparts.get(*ids.first()?)?.hptype {
+                    HPartType::Barline(barline) => Some(barline.clone()),
+                    _ => None,
+                },
+                _ => None,
+            }
+        };
+        let mark_col_idx = |mark: NavigationMark| map_colidx_navigations.iter().find(|(_, marks)| marks.contains(&mark)).map(|(col_idx, _)| *col_idx);
+
+        let mut order: Vec<usize> = Vec::new();
+        let mut map_colidx_repeated: BTreeMap<usize, u8> = BTreeMap::new();
+        // first column after the repeat start barline
+        let mut repeat_start: usize = 0;
+        let mut pass: u8 = 1;
+        let mut jumped = false;
+        let mut coda_taken = false;
+        let mut col_idx: usize = 0;
+
+        'columns: while col_idx <= columns.len() {
+            for mark in map_colidx_navigations.get(&col_idx).map(|marks| marks.as_slice()).unwrap_or(&[]) {
+                match mark {
+                    NavigationMark::Fine if jumped => break 'columns,
+                    NavigationMark::ToCoda if jumped && !coda_taken => {
+                        let coda_col_idx = mark_col_idx(NavigationMark::Coda).ok_or("To Coda without Coda")?;
+                        if coda_col_idx <= col_idx {
+                            return Err("Coda before To Coda".into());
+                        }
+                        coda_taken = true;
+                        col_idx = coda_col_idx;
+                        continue 'columns;
+                    }
+                    mark if !jumped && (mark.is_da_capo() || mark.is_dal_segno()) => {
+                        jumped = true;
+                        pass = 1;
+                        col_idx = if mark.is_da_capo() { 0 } else { mark_col_idx(NavigationMark::Segno).ok_or("D.S. without Segno")? };
+                        continue 'columns;
+                    }
+                    _ => {}
+                }
+            }
+            if col_idx == columns.len() {
+                break;
+            }
+
+            // skip endings that don't belong to this pass, after a jump only the last ending is played
+            let is_last_ending = |volta: &Volta| !voltas.iter().any(|other| other.from_col_idx > volta.from_col_idx && other.from_col_idx <= volta.to_col_idx + 1);
+            if let Some(volta) = voltas
+                .iter()
+                .find(|volta| volta.from_col_idx == col_idx && if jumped { !is_last_ending(volta) } else { !volta.numbers.contains(&pass) })
+            {
+                col_idx = volta.to_col_idx;
+                continue;
+            }
+
+            order.push(col_idx);
+            match barline_at(col_idx) {
+                Some(BarlineType::RepeatStart) => {
+                    repeat_start = col_idx + 1;
+                }
+                Some(BarlineType::RepeatEnd | BarlineType::RepeatBoth) if !jumped => {
+                    let passes = voltas
+                        .iter()
+                        .filter(|volta| volta.from_col_idx >= repeat_start && volta.from_col_idx <= col_idx + 1)
+                        .flat_map(|volta| volta.numbers.iter().cloned())
+                        .max()
+                        .unwrap_or(2)
+                        .max(2);
+                    let repeated = map_colidx_repeated.entry(col_idx).or_insert(0);
+                    if *repeated + 1 < passes {
+                        *repeated += 1;
+                        pass += 1;
+                        col_idx = repeat_start;
+                        continue;
+                    }
+                    // played through, a following repeat without start barline goes back to here
+                    pass = 1;
+                    repeat_start = col_idx + 1;
+                }
+                _ => {}
+            }
+            col_idx += 1;
+        }
+
+        Ok(order)
+    }
+
+    /// Note ids of one voice of a part in playback order. Voice 0 is the upper or only voice, voice 1 the lower one
+    pub fn expand_notes(cx: &CoreContext, part_idx: usize, voice_idx: usize) -> Result<Vec<NoteId>, Box<dyn std::error::Error>> {
+        let columns = cx.columns.borrow();
+        let hparts = cx.hparts.borrow();
+
+        let mut note_ids: Vec<NoteId> = Vec::new();
+        for col_idx in RepeatUtils::expand_columns(cx)? {
+            let HPartItemsColumnType::Musics(ids) = &columns[col_idx].hptype else { continue };
+            let Some(hpart) = ids.get(part_idx).and_then(|id| hparts.get(*id)) else { continue };
+            let voice = match (&hpart.hptype, voice_idx) {
+                (
+                    HPartType::Music {
+                        mtype: HPartMusicType::OneVoice { voice },
+                        ..
+                    },
+                    0,
+                ) => voice,
+                (
+                    HPartType::Music {
+                        mtype: HPartMusicType::TwoVoices { upper, .. },
+                        ..
+                    },
+                    0,
+                ) => upper,
+                (
+                    HPartType::Music {
+                        mtype: HPartMusicType::TwoVoices { lower, .. },
+                        ..
+                    },
+                    1,
+                ) => lower,
+                _ => continue,
+            };
+            if let VoiceType2::NoteIds { note_ids: ids, .. } = voice {
+                note_ids.extend(ids.iter().cloned());
+            }
+        }
+        Ok(note_ids)
+    }
+}
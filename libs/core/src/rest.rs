@@ -1,4 +1,4 @@
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum RestType {
     Brevis,
     Whole,
@@ -7,3 +7,18 @@ pub enum RestType {
     Eighth,
     Sixteenth,
 }
+
+impl RestType {
+    /// Top and bottom of the glyph in levels, relative to the rest level
+    pub fn get_extents(&self) -> (i8, i8) {
+        match self {
+            // hangs from the line above the rest level
+            RestType::Whole => (-2, -1),
+            // sits on the rest level line
+            RestType::Half => (-1, 0),
+            RestType::Eighth => (-2, 2),
+            RestType::Sixteenth => (-2, 4),
+            RestType::Brevis | RestType::Quarter => (-3, 3),
+        }
+    }
+}
@@ -54,8 +54,8 @@ pub fn calculate_stemitem_directions(cx: &CoreContext, ptype: &PartType) -> Resu
     let mut stemitems = cx.stemitems.borrow_mut();
 
     match ptype {
-        PartType::OneVoice(voice_item) => match &voice_item.vtype {
-            VoiceType::NoteIds(_, _, stemitem_ids) => {
+        PartType::OneVoice(voice_item) => {
+            if let VoiceType::NoteIds(_, _, stemitem_ids) = &voice_item.vtype {
                 for stemitem_id in stemitem_ids {
                     let stemitem = stemitems.get_mut(*stemitem_id).unwrap();
                     match stemitem.stype {
@@ -73,10 +73,9 @@ pub fn calculate_stemitem_directions(cx: &CoreContext, ptype: &PartType) -> Resu
                     }
                 }
             }
-            _ => {}
-        },
-        PartType::TwoVoice(voice_item_upper, voice_item_lower) => match (&voice_item_upper.vtype, &voice_item_lower.vtype) {
-            (VoiceType::NoteIds(_, duration_upper, stemitem_ids_upper), VoiceType::NoteIds(_, duration_lower, stemitem_ids_lower)) => {
+        }
+        PartType::TwoVoice(voice_item_upper, voice_item_lower) => {
+            if let (VoiceType::NoteIds(_, duration_upper, stemitem_ids_upper), VoiceType::NoteIds(_, duration_lower, stemitem_ids_lower)) = (&voice_item_upper.vtype, &voice_item_lower.vtype) {
                 let duration_shorter = *duration_upper.min(duration_lower);
                 for stemitem_id in stemitem_ids_upper {
                     let stemitem = stemitems.get_mut(*stemitem_id).unwrap();
@@ -120,8 +119,7 @@ pub fn calculate_stemitem_directions(cx: &CoreContext, ptype: &PartType) -> Resu
                     }
                 }
             }
-            _ => {}
-        },
+        }
         _ => {}
     }
     Ok(())
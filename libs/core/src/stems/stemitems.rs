@@ -1,5 +1,5 @@
 use crate::{
-    constants::STEM_DEFAULT_LENGTH,
+    constants::{BEAM_THICKNESS_LEVELS, STEM_CROSS_STAFF_MIN_LENGTH, STEM_DEFAULT_LENGTH},
     context::CoreContext,
     direction::DirectionUD,
     duration::{DurationUtils, NoteDuration, SumDuration},
@@ -44,7 +44,7 @@ pub struct StemItemUtils;
 impl StemItemUtils {
     pub fn create_stem_items_from_notes(
         cx: &CoreContext,
-        note_ids: &Vec<StemItemId>,
+        note_ids: &[StemItemId],
         notes_duration: SumDuration,
         pattern_values: Vec<NoteDuration>,
     ) -> Result<Vec<StemItemId>, Box<dyn std::error::Error>> {
@@ -63,7 +63,7 @@ impl StemItemUtils {
 
                 // group of one note
                 1 => {
-                    let note = group.get(0).unwrap();
+                    let note = group.first().unwrap();
                     position = note.position;
                     duration = note.duration as usize;
 
@@ -149,7 +149,7 @@ impl StemItemUtils {
         Ok(ids)
     }
 
-    pub fn create_groups_of_notes(cx: &CoreContext, note_ids: &Vec<NoteId>, notes_duration: SumDuration, pattern_values: Vec<NoteDuration>) -> Vec<Vec<NoteItem>> {
+    pub fn create_groups_of_notes(cx: &CoreContext, note_ids: &[NoteId], notes_duration: SumDuration, pattern_values: Vec<NoteDuration>) -> Vec<Vec<NoteItem>> {
         let mut cycles: Vec<(usize, usize)> = Vec::new();
         let mut position = 0;
         let mut step = 0;
@@ -163,13 +163,7 @@ impl StemItemUtils {
         }
 
         let cx_notes = cx.notes.borrow();
-        let notes = note_ids
-            .iter()
-            .map(|note_id| {
-                let note = cx_notes.get(*note_id).unwrap();
-                note
-            })
-            .collect::<Vec<_>>();
+        let notes = note_ids.iter().map(|note_id| cx_notes.get(*note_id).unwrap()).collect::<Vec<_>>();
 
         let mut notes_iterator = notes.into_iter();
         let mut current_note: Option<&NoteItem> = notes_iterator.next();
@@ -242,10 +236,14 @@ impl StemItemUtils {
     }
 
     pub fn calculate_stem_lengths_for_notes(cx: &CoreContext, stemitem: &StemItem) -> Result<(), Box<dyn std::error::Error>> {
+        // cross-staff stems depend on the distance between the staves, see calculate_cross_staff_stem_lengths
+        if StemItemUtils::is_cross_staff(cx, stemitem) {
+            return Ok(());
+        }
         if let Some(direction) = &stemitem.direction {
             match &stemitem.stype {
                 StemType::NoteWithStem(item) => {
-                    let _ = calc_stemlengths_single(cx, item, direction)?;
+                    calc_stemlengths_single(cx, item, direction)?;
                 }
                 StemType::NotesBeamed(items, _) => {
                     calc_stemlengths_beamed(cx, items, direction)?;
@@ -258,6 +256,61 @@ impl StemItemUtils {
 
         Ok(())
     }
+
+    /// Staff of each note relative to its part, 0 unless drawn on a neighbouring staff
+    pub fn get_staff_offsets(cx: &CoreContext, items: &[StemNoteItem]) -> Vec<i8> {
+        let map_noteid_staffoffset = cx.map_noteid_staffoffset.borrow();
+        items.iter().map(|item| map_noteid_staffoffset.get(&item.note.id).cloned().unwrap_or(0)).collect()
+    }
+
+    /// True for beamed notes drawn on two staves, with the beam between them
+    pub fn is_cross_staff(cx: &CoreContext, stemitem: &StemItem) -> bool {
+        StemItemUtils::split_cross_staff(cx, stemitem).is_some()
+    }
+
+    /// Notes of a cross-staff group on the upper staff and on the lower staff
+    pub fn split_cross_staff<'a>(cx: &CoreContext, stemitem: &'a StemItem) -> Option<(Vec<&'a StemNoteItem>, Vec<&'a StemNoteItem>)> {
+        let StemType::NotesBeamed(items, _) = &stemitem.stype else {
+            return None;
+        };
+        let offsets = StemItemUtils::get_staff_offsets(cx, items);
+        let upper_offset = *offsets.iter().min()?;
+        let (upper, lower): (Vec<_>, Vec<_>) = items.iter().zip(offsets).partition(|(_, offset)| *offset == upper_offset);
+        if lower.is_empty() {
+            return None;
+        }
+        Some((upper.into_iter().map(|(item, _)| item).collect(), lower.into_iter().map(|(item, _)| item).collect()))
+    }
+
+    /// Smallest distance in levels between the middle lines of the two staves of a cross-staff group,
+    /// leaving room for the shortest stems and the beam between the lowest head above and the highest head below
+    pub fn get_cross_staff_min_distance(cx: &CoreContext, stemitem: &StemItem) -> f32 {
+        let Some((upper, lower)) = StemItemUtils::split_cross_staff(cx, stemitem) else {
+            return 0.0;
+        };
+        let upper_bottom_level = upper.iter().map(|item| item.bottom_level).max().unwrap_or(0);
+        let lower_top_level = lower.iter().map(|item| item.top_level).min().unwrap_or(0);
+        (upper_bottom_level - lower_top_level) as f32 + STEM_CROSS_STAFF_MIN_LENGTH * 2.0 + BEAM_THICKNESS_LEVELS
+    }
+
+    /// Stem levels of a cross-staff group with staves `staff_distance` levels apart, each in the levels of the staff the note is drawn on.
+    /// The beam is horizontal and halfway between the staves' heads, upper staff notes get stems down to it and lower staff notes stems up.
+    /// Returns the beam level on the upper staff.
+    pub fn calculate_cross_staff_stem_lengths(cx: &CoreContext, stemitem: &StemItem, staff_distance: f32) -> Result<f32, Box<dyn std::error::Error>> {
+        let (upper, lower) = StemItemUtils::split_cross_staff(cx, stemitem).ok_or("Stem item is not cross-staff")?;
+        let upper_bottom_level = upper.iter().map(|item| item.bottom_level).max().unwrap_or(0) as f32;
+        let lower_top_level = lower.iter().map(|item| item.top_level).min().unwrap_or(0) as f32 + staff_distance;
+        let beam_level = (upper_bottom_level + lower_top_level) / 2.0;
+
+        let mut stemitemlevels = cx.map_noteid_stemitemlevels.borrow_mut();
+        for item in upper {
+            stemitemlevels.insert(item.note.id, (DirectionUD::Down, item.top_level as f32, beam_level));
+        }
+        for item in lower {
+            stemitemlevels.insert(item.note.id, (DirectionUD::Up, beam_level - staff_distance, item.bottom_level as f32));
+        }
+        Ok(beam_level)
+    }
 }
 
 fn calc_stemlengths_single(cx: &CoreContext, item: &StemNoteItem, direction: &DirectionUD) -> Result<(), Box<dyn std::error::Error>> {
@@ -14,6 +14,11 @@ pub enum SysItemTypeId {
     Clefs(Vec<String>),
     Parts(Vec<Vec<String>>),
     Barlines(Vec<String>),
+    Keys(Vec<String>),
+    Volta(Vec<String>),
+    Navigation(Vec<String>),
+    Groups(Vec<String>),
+    PartHeader(Vec<String>),
 }
 
 #[derive(Debug)]
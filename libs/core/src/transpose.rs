@@ -0,0 +1,157 @@
+use crate::{key::KeySignature, pitch::Pitch};
+
+/// Interval as diatonic steps and semitones, negative values transpose downwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval {
+    pub steps: i8,
+    pub semitones: i8,
+}
+
+impl Interval {
+    pub const UNISON: Interval = Interval { steps: 0, semitones: 0 };
+    pub const MINOR_SECOND: Interval = Interval { steps: 1, semitones: 1 };
+    pub const MAJOR_SECOND: Interval = Interval { steps: 1, semitones: 2 };
+    pub const MINOR_THIRD: Interval = Interval { steps: 2, semitones: 3 };
+    pub const MAJOR_THIRD: Interval = Interval { steps: 2, semitones: 4 };
+    pub const PERFECT_FOURTH: Interval = Interval { steps: 3, semitones: 5 };
+    pub const AUGMENTED_FOURTH: Interval = Interval { steps: 3, semitones: 6 };
+    pub const DIMINISHED_FIFTH: Interval = Interval { steps: 4, semitones: 6 };
+    pub const PERFECT_FIFTH: Interval = Interval { steps: 4, semitones: 7 };
+    pub const MINOR_SIXTH: Interval = Interval { steps: 5, semitones: 8 };
+    pub const MAJOR_SIXTH: Interval = Interval { steps: 5, semitones: 9 };
+    pub const MINOR_SEVENTH: Interval = Interval { steps: 6, semitones: 10 };
+    pub const MAJOR_SEVENTH: Interval = Interval { steps: 6, semitones: 11 };
+    pub const OCTAVE: Interval = Interval { steps: 7, semitones: 12 };
+
+    pub fn new(steps: i8, semitones: i8) -> Self {
+        Interval { steps, semitones }
+    }
+
+    pub fn down(&self) -> Self {
+        Interval {
+            steps: -self.steps,
+            semitones: -self.semitones,
+        }
+    }
+
+    /// Distance in the circle of fifths, i.e. how many sharps the interval adds to a key signature
+    pub fn get_fifths(&self) -> i8 {
+        (7 * self.semitones as i16 - 12 * self.steps as i16) as i8
+    }
+
+    /// Parses intervals like "M2", "-P5", "m3", "A4", "d5" or "P8"
+    pub fn parse(s: &str) -> Result<Interval, Box<dyn std::error::Error>> {
+        let s = s.trim();
+        let (sign, s) = match s.strip_prefix('-') {
+            Some(rest) => (-1, rest),
+            None => (1, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let quality = s.chars().next().ok_or("Empty interval string")?;
+        let number: i8 = s[quality.len_utf8()..].parse().map_err(|_| format!("Invalid interval number in '{}'", s))?;
+        if number < 1 {
+            return Err(format!("Invalid interval number in '{}'", s).into());
+        }
+
+        let steps = number - 1;
+        let semitones = Pitch::new(steps, 0).get_semitones() as i8 - 12;
+        let is_perfect = matches!(steps.rem_euclid(7), 0 | 3 | 4);
+        let adjust = match (quality, is_perfect) {
+            ('P', true) | ('M', false) => 0,
+            ('m', false) => -1,
+            ('A', _) => 1,
+            ('d', true) => -1,
+            ('d', false) => -2,
+            _ => return Err(format!("Invalid interval quality in '{}'", s).into()),
+        };
+
+        Ok(Interval::new(sign * steps, sign * (semitones + adjust)))
+    }
+
+    /// Inverse of `parse`, like "M2" or "-P5"
+    pub fn get_code(&self) -> String {
+        let (steps, semitones) = (self.steps.abs(), if self.steps < 0 { -self.semitones } else { self.semitones });
+        let adjust = semitones - (Pitch::new(steps, 0).get_semitones() as i8 - 12);
+        let is_perfect = matches!(steps.rem_euclid(7), 0 | 3 | 4);
+        let quality = match (is_perfect, adjust) {
+            (true, 0) => "P",
+            (false, 0) => "M",
+            (false, -1) => "m",
+            (_, 1) => "A",
+            (true, -1) | (false, -2) => "d",
+            _ => "?",
+        };
+        format!("{}{}{}", if self.steps < 0 { "-" } else { "" }, quality, steps + 1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransposeMode {
+    /// Exact interval, key signatures follow
+    Chromatic,
+    /// Scale steps within the unchanged key signature
+    Diatonic,
+}
+
+pub struct TransposeUtils;
+impl TransposeUtils {
+    /// Target key signature, and the interval respelled enharmonically if the key would get more than six sharps or flats
+    pub fn transpose_key(key: &KeySignature, interval: Interval, mode: TransposeMode) -> (KeySignature, Interval) {
+        match mode {
+            TransposeMode::Diatonic => (*key, interval),
+            TransposeMode::Chromatic => {
+                let mut interval = interval;
+                let mut fifths = key.fifths() + interval.get_fifths();
+                while fifths > 6 {
+                    interval.steps += 1;
+                    fifths -= 12;
+                }
+                while fifths < -6 {
+                    interval.steps -= 1;
+                    fifths += 12;
+                }
+                (KeySignature::from_fifths(fifths), interval)
+            }
+        }
+    }
+
+    pub fn transpose_pitch(pitch: &Pitch, interval: Interval, mode: TransposeMode, key_from: &KeySignature, key_to: &KeySignature) -> Pitch {
+        let step = pitch.step + interval.steps;
+        let alter = match mode {
+            TransposeMode::Chromatic => (pitch.get_semitones() + interval.semitones as i16 - Pitch::new(step, 0).get_semitones()) as i8,
+            TransposeMode::Diatonic => key_to.get_alter(step) + pitch.alter - key_from.get_alter(pitch.step),
+        };
+        Pitch::new(step, alter).respell(key_to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test() {
+        assert_eq!(Interval::parse("M2").unwrap(), Interval::MAJOR_SECOND);
+        assert_eq!(Interval::parse("-P5").unwrap(), Interval::PERFECT_FIFTH.down());
+        assert_eq!(Interval::parse("m10").unwrap(), Interval::new(9, 15));
+        for code in ["M2", "-P5", "m10", "A4", "-d5", "P8"] {
+            assert_eq!(Interval::parse(code).unwrap().get_code(), code);
+        }
+
+        // Bb clarinet: concert F major is written in G major
+        let (key, interval) = TransposeUtils::transpose_key(&KeySignature::Flat1, Interval::MAJOR_SECOND, TransposeMode::Chromatic);
+        assert_eq!(key, KeySignature::Sharp1);
+        let b_flat = Pitch::new(34, -1);
+        assert_eq!(
+            TransposeUtils::transpose_pitch(&b_flat, interval, TransposeMode::Chromatic, &KeySignature::Flat1, &key),
+            Pitch::new(28 + 7, 0)
+        );
+
+        // Eb major up a minor second would give Fb major, respelled as E major
+        let (key, interval) = TransposeUtils::transpose_key(&KeySignature::Flat3, Interval::MINOR_SECOND, TransposeMode::Chromatic);
+        assert_eq!((key, interval.steps), (KeySignature::Sharp4, 0));
+        // A major up an augmented unison would give A# major, respelled as Bb major
+        let (key, interval) = TransposeUtils::transpose_key(&KeySignature::Sharp3, Interval::new(0, 1), TransposeMode::Chromatic);
+        assert_eq!((key, interval.steps), (KeySignature::Flat2, 1));
+    }
+}
@@ -7,7 +7,7 @@ use graphics::{
     graphicitem::{GraphicItem, GraphicItems},
     stroke::Stroke,
 };
-use rusttype::{point, Font, Point, Rect, Scale};
+use rusttype::{point, Point, Rect, Scale};
 use svg::builder::SvgBuilder;
 
 // cargo watch -q -c --ignore '**/*.svg' -x "run -q --example ex-fonts1"
@@ -35,7 +35,6 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     dbg!(_glyphs_height, _glyphs_width);
 
-    let mut x = 0.;
     let mut all_segments = Vec::new();
 
     for glyph in glyphs {
@@ -47,7 +46,7 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
                 max: Point { x: scale.x / 5., y: 0. },
             },
         };
-        x = glyph.position().x;
+        let x = glyph.position().x;
         let mut builder = PathBuilder {
             x,
             y: v_metrics.ascent + bounding_box.min.y,
@@ -62,7 +61,7 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let mut items: GraphicItems = Vec::new();
     for segments in all_segments {
-        items.push(GraphicItem::Path(segments, 0., 0., Stroke::Solid(0.1, Color::Black), Fill::Solid(Color::Tomato), None));
+        items.push(GraphicItem::Path(segments, 0., 0., Stroke::Solid(0.1, Color::Black), Fill::Solid(Color::Tomato), None, None));
     }
 
     let svg = SvgBuilder::new().build(items, None);
@@ -119,7 +118,7 @@ pub fn mainx() -> Result<(), Box<dyn std::error::Error>> {
 
     let mut items: GraphicItems = Vec::new();
     for segments in all_segments {
-        items.push(GraphicItem::Path(segments, 0., 0., Stroke::Solid(0.1, Color::Black), Fill::Solid(Color::Tomato), None));
+        items.push(GraphicItem::Path(segments, 0., 0., Stroke::Solid(0.1, Color::Black), Fill::Solid(Color::Tomato), None, None));
     }
 
     let svg = SvgBuilder::new().build(items, None);
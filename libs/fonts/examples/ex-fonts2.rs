@@ -73,6 +73,7 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
                 Stroke::Solid(0.1, Color::Black),
                 Fill::Solid(Color::DodgerBlue),
                 None,
+                None,
             ));
         }
     }
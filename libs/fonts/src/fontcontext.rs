@@ -1,14 +1,27 @@
-use graphics::{graphicitem::GraphicItem, path::PathSegment};
-use rusttype::Font;
+use graphics::{
+    graphicitem::{GraphicItem, TEXT_SIZE, TextMetrics},
+    path::PathSegment,
+    rectangle::Rectangle,
+};
+use rusttype::{Font, Scale, point};
 use std::cell::RefCell;
 
-use crate::utils::{get_dimensions, get_items, get_segments};
+use crate::{
+    pathbuilder::PathBuilder,
+    smufl::{EngravingDefaults, GlyphAnchors, LEGACY_GLYPHS, LEGACY_SIZE_PER_SPACE, SmuflMetadata},
+    utils::{get_dimensions, get_items, get_segments},
+};
+
+/// The built-in sans-serif font file, for backends that embed the font the text is measured in
+pub const SANSSERIF_FONT_DATA: &[u8] = include_bytes!("../assets/OpenSans-Regular.ttf");
 
 #[allow(unused)]
 #[derive(Debug)]
 pub struct FontContext<'a> {
     pub music_font: RefCell<Font<'a>>,
     pub sansserif_font: RefCell<Font<'a>>,
+    // metadata of the loaded SMuFL font, none for the built-in music font
+    pub music_metadata: RefCell<Option<SmuflMetadata>>,
 }
 
 #[allow(unused)]
@@ -16,7 +29,8 @@ impl<'a> FontContext<'a> {
     pub fn new() -> &'static FontContext<'a> {
         let cx = FontContext {
             music_font: RefCell::new(Font::try_from_bytes(include_bytes!("../assets/MTF-Cadence-Fin.ttf") as &[u8]).expect("Error constructing Font")),
-            sansserif_font: RefCell::new(Font::try_from_bytes(include_bytes!("../assets/OpenSans-Regular.ttf") as &[u8]).expect("Error constructing Font")),
+            sansserif_font: RefCell::new(Font::try_from_bytes(SANSSERIF_FONT_DATA).expect("Error constructing Font")),
+            music_metadata: RefCell::new(None),
         };
         Box::leak(Box::new(cx))
     }
@@ -44,4 +58,132 @@ impl<'a> FontContext<'a> {
         let font = &self.sansserif_font.borrow();
         get_items(font, scale, text)
     }
+
+    pub fn get_sansserif_string_segments(&self, scale: f32, text: &str) -> Vec<Vec<PathSegment>> {
+        let font = &self.sansserif_font.borrow();
+        get_segments(font, scale, text)
+    }
+
+    /// Distance from the top of the string segments down to the baseline
+    pub fn get_sansserif_ascent(&self, scale: f32) -> f32 {
+        self.sansserif_font.borrow().v_metrics(rusttype::Scale::uniform(scale)).ascent
+    }
+
+    /// Size of the sans-serif em at a scale, which is the height from descent to ascent
+    pub fn get_sansserif_em_size(&self, scale: f32) -> f32 {
+        let font = self.sansserif_font.borrow();
+        let v_metrics = font.v_metrics_unscaled();
+        scale * font.units_per_em() as f32 / (v_metrics.ascent - v_metrics.descent)
+    }
+
+    /// Glyph ids of a string in the sans-serif font with their characters and advance widths in ems
+    pub fn get_sansserif_glyphs(&self, text: &str) -> Vec<(u16, char, f32)> {
+        let font = self.sansserif_font.borrow();
+        let v_metrics = font.v_metrics_unscaled();
+        // at the scale of the unscaled height the metrics are in font units
+        let units = Scale::uniform(v_metrics.ascent - v_metrics.descent);
+        let em = font.units_per_em() as f32;
+        text.chars()
+            .map(|ch| {
+                let glyph = font.glyph(ch);
+                (glyph.id().0, ch, glyph.scaled(units).h_metrics().advance_width / em)
+            })
+            .collect()
+    }
+
+    /// Ink bounds of a string relative to the start of its baseline
+    pub fn get_sansserif_text_rectangle(&self, scale: f32, text: &str) -> Rectangle {
+        let font = self.sansserif_font.borrow();
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+        for glyph in font.layout(text, Scale::uniform(scale), point(0.0, 0.0)) {
+            // spaces have no bounding box
+            let Some(bbox) = glyph.unpositioned().exact_bounding_box() else { continue };
+            let x = glyph.position().x;
+            min_x = min_x.min(x + bbox.min.x);
+            min_y = min_y.min(bbox.min.y);
+            max_x = max_x.max(x + bbox.max.x);
+            max_y = max_y.max(bbox.max.y);
+        }
+        if min_x > max_x {
+            return (0.0, 0.0, 0.0, 0.0);
+        }
+        (min_x, min_y, max_x - min_x, max_y - min_y)
+    }
+
+    /// Replaces the music font by a SMuFL font (Bravura, Petaluma, Leland...) and the contents of its metadata JSON file
+    pub fn load_smufl_font(&self, font_data: Vec<u8>, metadata_json: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let font = Font::try_from_vec(font_data).ok_or("Font data could not be read")?;
+        let metadata = SmuflMetadata::from_json(metadata_json)?;
+        *self.music_font.borrow_mut() = font;
+        *self.music_metadata.borrow_mut() = Some(metadata);
+        Ok(())
+    }
+
+    /// Outline of a music glyph by its SMuFL name, relative to the glyph origin and sized for staff spaces of `space`
+    pub fn get_music_glyph_segments(&self, name: &str, space: f32) -> Option<Vec<PathSegment>> {
+        let (codepoint, move_y) = self.get_music_glyph(name)?;
+        let font = self.music_font.borrow();
+        let glyph = font.glyph(codepoint);
+        if glyph.id().0 == 0 {
+            return None;
+        }
+        let mut builder = PathBuilder {
+            x: 0.0,
+            y: move_y * space,
+            segments: Vec::new(),
+        };
+        glyph.scaled(Scale::uniform(self.get_music_font_size(space))).build_outline(&mut builder);
+        Some(builder.get_segments())
+    }
+
+    /// Bounds of a music glyph relative to its origin, from the font metadata when there is any
+    pub fn get_music_glyph_bbox(&self, name: &str, space: f32) -> Option<Rectangle> {
+        if let Some(bbox) = self.music_metadata.borrow().as_ref().and_then(|metadata| metadata.glyph_bboxes.get(name).cloned()) {
+            let (east, north) = bbox.bbox_ne;
+            let (west, south) = bbox.bbox_sw;
+            return Some((west * space, -north * space, (east - west) * space, (north - south) * space));
+        }
+        let (codepoint, move_y) = self.get_music_glyph(name)?;
+        let font = self.music_font.borrow();
+        let bbox = font.glyph(codepoint).scaled(Scale::uniform(self.get_music_font_size(space))).exact_bounding_box()?;
+        Some((bbox.min.x, bbox.min.y + move_y * space, bbox.width(), bbox.height()))
+    }
+
+    /// How far a music glyph reaches to the right of its origin
+    pub fn get_music_glyph_width(&self, name: &str, space: f32) -> Option<f32> {
+        self.get_music_glyph_bbox(name, space).map(|(x, _, w, _)| x + w)
+    }
+
+    /// Stem anchors of a music glyph in staff spaces with y pointing up, as given by the font metadata
+    pub fn get_music_glyph_anchors(&self, name: &str) -> Option<GlyphAnchors> {
+        self.music_metadata.borrow().as_ref().and_then(|metadata| metadata.glyphs_with_anchors.get(name).cloned())
+    }
+
+    pub fn get_engraving_defaults(&self) -> EngravingDefaults {
+        self.music_metadata.borrow().as_ref().map(|metadata| metadata.engraving_defaults.clone()).unwrap_or_default()
+    }
+
+    // a SMuFL em is four staff spaces
+    fn get_music_font_size(&self, space: f32) -> f32 {
+        match self.music_metadata.borrow().as_ref() {
+            Some(_) => space * 4.0,
+            None => space * LEGACY_SIZE_PER_SPACE,
+        }
+    }
+
+    fn get_music_glyph(&self, name: &str) -> Option<(char, f32)> {
+        match self.music_metadata.borrow().as_ref() {
+            Some(metadata) => metadata.get_codepoint(name).map(|codepoint| (codepoint, 0.0)),
+            None => LEGACY_GLYPHS
+                .iter()
+                .find(|(glyph_name, _, _)| *glyph_name == name)
+                .and_then(|(_, codepoint, move_y)| char::from_u32(*codepoint).map(|codepoint| (codepoint, *move_y))),
+        }
+    }
+}
+
+impl TextMetrics for FontContext<'_> {
+    fn get_text_rectangle(&self, text: &str) -> Rectangle {
+        self.get_sansserif_text_rectangle(TEXT_SIZE, text)
+    }
 }
@@ -1,11 +1,11 @@
 pub mod fontcontext;
-pub mod music_glyphs;
 pub mod pathbuilder;
+pub mod smufl;
 pub mod utils;
 
 #[cfg(test)]
 mod tests {
-    use crate::{fontcontext::FontContext, pathbuilder::PathBuilder};
+    use crate::{fontcontext::FontContext, pathbuilder::PathBuilder, smufl::SmuflMetadata};
     use graphics::{
         color::Color,
         fill::Fill,
@@ -62,7 +62,7 @@ mod tests {
 
         let mut items: GraphicItems = Vec::new();
         for segments in all_segments {
-            items.push(GraphicItem::Path(segments, 0., 0., Stroke::Solid(0.1, Color::Black), Fill::Solid(Color::Tomato), None));
+            items.push(GraphicItem::Path(segments, 0., 0., Stroke::Solid(0.1, Color::Black), Fill::Solid(Color::Tomato), None, None));
         }
 
         let svg = SvgBuilder::new().build(items, None);
@@ -117,10 +117,41 @@ mod tests {
 
         let mut items: GraphicItems = Vec::new();
         for segments in all_segments {
-            items.push(GraphicItem::Path(segments, 0., 0., Stroke::Solid(0.1, Color::Black), Fill::Solid(Color::Tomato), None));
+            items.push(GraphicItem::Path(segments, 0., 0., Stroke::Solid(0.1, Color::Black), Fill::Solid(Color::Tomato), None, None));
         }
 
         let svg = SvgBuilder::new().build(items, None);
         fs::write("out/fonts.svg", svg).unwrap();
     }
+
+    #[test]
+    fn test_smufl_glyphs() {
+        // the built-in font is measured from its outlines
+        let fcx = FontContext::new();
+        assert!((fcx.get_music_glyph_width("noteheadBlack", 10.0).unwrap() - 13.0).abs() < 0.1);
+        assert!(fcx.get_music_glyph_segments("gClef", 10.0).is_some());
+        assert!(fcx.get_music_glyph_segments("brace", 10.0).is_none());
+        // the double sharp is about a space square and centred on its line
+        let (_, y, w, h) = fcx.get_music_glyph_bbox("accidentalDoubleSharp", 10.0).unwrap();
+        assert!((w - 12.5).abs() < 0.1 && (h - 11.0).abs() < 0.1 && (y + h / 2.0).abs() < 0.1);
+
+        let json = r#"{
+            "fontName": "Test",
+            "engravingDefaults": { "stemThickness": 0.12, "staffLineThickness": 0.13 },
+            "glyphBBoxes": { "noteheadBlack": { "bBoxNE": [1.25, 0.5], "bBoxSW": [0.0, -0.5] } },
+            "glyphsWithAnchors": { "noteheadBlack": { "stemUpSE": [1.18, 0.168], "stemDownNW": [0.0, -0.168] } },
+            "optionalGlyphs": { "noteheadBlackSmall": { "codepoint": "U+F4A0" } }
+        }"#;
+        let metadata = SmuflMetadata::from_json(json).unwrap();
+        assert_eq!(metadata.get_codepoint("noteheadBlack"), Some('\u{E0A4}'));
+        assert_eq!(metadata.get_codepoint("noteheadBlackSmall"), Some('\u{F4A0}'));
+        assert_eq!(metadata.get_codepoint("unknown"), None);
+
+        // a loaded font is measured from its metadata
+        fcx.load_smufl_font(include_bytes!("../assets/MTF-Cadence-Fin.ttf").to_vec(), json).unwrap();
+        assert_eq!(fcx.get_music_glyph_bbox("noteheadBlack", 10.0), Some((0.0, -5.0, 12.5, 10.0)));
+        assert_eq!(fcx.get_music_glyph_anchors("noteheadBlack").unwrap().stem_up_se, Some((1.18, 0.168)));
+        assert_eq!(fcx.get_engraving_defaults().stem_thickness, Some(0.12));
+        assert!(fcx.load_smufl_font(Vec::new(), json).is_err());
+    }
 }
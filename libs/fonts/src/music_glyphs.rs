@@ -1,503 +0,0 @@
-use graphics::path::PathSegment;
-use PathSegment::L;
-use PathSegment::M;
-use PathSegment::Q;
-use PathSegment::Z;
-
-const GLYPH_FIVELINES: &[PathSegment] = &[
-    M(0.0, 30.552198),
-    L(0.0, 30.048368),
-    L(20.153164, 30.048368),
-    L(20.153164, 30.552198),
-    L(0.0, 30.552198),
-    Z,
-    M(0.0, 25.513906),
-    L(0.0, 25.010078),
-    L(20.153164, 25.010078),
-    L(20.153164, 25.513906),
-    L(0.0, 25.513906),
-    Z,
-    M(0.0, 20.475616),
-    L(0.0, 19.971786),
-    L(20.153164, 19.971786),
-    L(20.153164, 20.475616),
-    L(0.0, 20.475616),
-    Z,
-    M(0.0, 15.437325),
-    L(0.0, 14.9334955),
-    L(20.153164, 14.9334955),
-    L(20.153164, 15.437325),
-    L(0.0, 15.437325),
-    Z,
-    M(0.0, 10.399033),
-    L(0.0, 9.895205),
-    L(20.153164, 9.895205),
-    L(20.153164, 10.399033),
-    L(0.0, 10.399033),
-    Z,
-];
-
-const GLYPH_NOTEHEAD_WHITE: &[PathSegment] = &[
-    M(5.018138, 28.27489),
-    Q(4.4336963, 28.27489, 2.5493753, 29.594923),
-    Q(0.66505444, 30.914955, 0.66505444, 31.51955),
-    Q(0.66505444, 31.942766, 1.0278114, 32.1443),
-    Q(1.3905684, 32.34583, 1.8943975, 32.34583),
-    Q(2.4788394, 32.34583, 4.36316, 31.025797),
-    Q(6.2474813, 29.705765, 6.2474813, 29.10117),
-    Q(6.2474813, 28.677954, 5.884724, 28.476421),
-    Q(5.521967, 28.27489, 5.018138, 28.27489),
-    Z,
-    M(0.0, 31.07618),
-    Q(0.0, 29.625153, 1.2494962, 28.587265),
-    Q(2.4989924, 27.549377, 4.272471, 27.549377),
-    Q(4.8770657, 27.549377, 5.401048, 27.670296),
-    Q(5.9250307, 27.791214, 6.4087067, 28.27489),
-    Q(6.8923826, 28.758566, 6.8923826, 29.54454),
-    Q(6.8923826, 30.995567, 5.642886, 32.033455),
-    Q(4.3933897, 33.071342, 2.6199114, 33.071342),
-    Q(2.0153165, 33.071342, 1.4913342, 32.950424),
-    Q(0.9673519, 32.829506, 0.48367596, 32.34583),
-    Q(0.0, 31.862154, 0.0, 31.07618),
-    Z,
-];
-
-const GLYPH_NOTEHEAD_BLACK: &[PathSegment] = &[
-    M(0.0, 30.995567),
-    Q(0.0, 29.786377, 1.3301089, 28.667877),
-    Q(2.6602178, 27.549377, 4.050786, 27.549377),
-    Q(4.6150746, 27.549377, 5.1289806, 27.690449),
-    Q(5.642886, 27.83152, 6.106409, 28.33535),
-    Q(6.569932, 28.839178, 6.569932, 29.625153),
-    Q(6.569932, 30.733576, 5.21967, 31.90246),
-    Q(3.8694077, 33.071342, 2.5191455, 33.071342),
-    Q(1.954857, 33.071342, 1.4409513, 32.93027),
-    Q(0.9270456, 32.7892, 0.4635228, 32.28537),
-    Q(0.0, 31.78154, 0.0, 30.995567),
-    Z,
-];
-
-const GLYPH_NOTEHEAD_WHOLE: &[PathSegment] = &[
-    M(0.0, 30.008062),
-    Q(0.0, 29.121323, 0.9673519, 28.33535),
-    Q(1.9347038, 27.549377, 3.7484887, 27.549377),
-    Q(4.977832, 27.549377, 5.98549, 27.841597),
-    Q(6.9931483, 28.133818, 7.698509, 28.849255),
-    Q(8.40387, 29.564693, 8.40387, 30.612658),
-    Q(8.40387, 31.660622, 7.486901, 32.365982),
-    Q(6.569932, 33.071342, 4.9375253, 33.071342),
-    Q(3.9701734, 33.071342, 3.1640468, 32.93027),
-    Q(2.3579202, 32.7892, 1.6122532, 32.466747),
-    Q(0.8665861, 32.1443, 0.43329304, 31.51955),
-    Q(0.0, 30.894802, 0.0, 30.008062),
-    Z,
-    M(3.7484887, 28.093512),
-    Q(1.9750102, 28.093512, 1.9750102, 28.71826),
-    Q(1.9750102, 29.42362, 3.0532045, 30.975414),
-    Q(4.1313987, 32.527206, 4.8569126, 32.527206),
-    Q(6.590085, 32.527206, 6.590085, 31.90246),
-    Q(6.590085, 31.136639, 5.4715843, 29.615076),
-    Q(4.3530836, 28.093512, 3.7484887, 28.093512),
-    Z,
-];
-
-const GLYPH_CLEF_ALTO: &[PathSegment] = &[
-    M(6.630391, 30.31036),
-    Q(6.630391, 31.78154, 7.537284, 31.78154),
-    Q(7.819428, 31.78154, 8.293027, 31.469166),
-    Q(8.766626, 31.156792, 9.089077, 31.156792),
-    Q(10.983475, 31.156792, 12.343814, 32.365982),
-    Q(13.704152, 33.575172, 13.704152, 35.34865),
-    Q(13.704152, 40.38694, 8.363564, 40.38694),
-    Q(7.093914, 40.38694, 6.1970983, 39.56066),
-    Q(5.3002825, 38.734383, 5.3002825, 37.525192),
-    Q(5.3002825, 36.88029, 5.7537284, 36.426846),
-    Q(6.207175, 35.9734, 6.831923, 35.9734),
-    Q(7.4566708, 35.9734, 7.910117, 36.426846),
-    Q(8.363564, 36.88029, 8.363564, 37.525192),
-    Q(8.363564, 37.928257, 7.869811, 38.291016),
-    Q(7.3760586, 38.65377, 7.3760586, 39.05683),
-    Q(7.3760586, 39.399437, 7.668279, 39.611046),
-    Q(7.9605002, 39.822655, 8.363564, 39.822655),
-    Q(9.99597, 39.822655, 10.590488, 38.694077),
-    Q(11.185006, 37.5655, 11.185006, 35.34865),
-    Q(11.185006, 34.441757, 11.1144705, 33.857315),
-    Q(11.043934, 33.272873, 10.832326, 32.748894),
-    Q(10.620718, 32.22491, 10.187425, 31.972996),
-    Q(9.754131, 31.72108, 9.089077, 31.72108),
-    Q(8.041113, 31.72108, 7.4566708, 32.597744),
-    Q(6.872229, 33.474407, 6.872229, 34.723904),
-    Q(6.872229, 35.006046, 6.590085, 35.006046),
-    Q(6.3280935, 35.006046, 6.3280935, 34.723904),
-    Q(6.3280935, 33.857315, 5.9754133, 32.819427),
-    Q(5.622733, 31.78154, 5.118904, 31.056026),
-    Q(4.977832, 30.834343, 4.6956873, 30.914955),
-    Q(4.4538493, 30.975414, 4.4538493, 31.257559),
-    L(4.4538493, 40.18541),
-    Q(4.4538493, 40.38694, 4.252318, 40.38694),
-    L(3.8291013, 40.38694),
-    Q(3.6275697, 40.38694, 3.6275697, 40.18541),
-    L(3.6275697, 20.43531),
-    Q(3.6275697, 20.233778, 3.8291013, 20.233778),
-    L(4.252318, 20.233778),
-    Q(4.4538493, 20.233778, 4.4538493, 20.43531),
-    L(4.4538493, 29.322855),
-    Q(4.4538493, 29.605, 4.6956873, 29.685612),
-    Q(4.957679, 29.786377, 5.118904, 29.54454),
-    Q(5.622733, 28.819027, 5.9754133, 27.791214),
-    Q(6.3280935, 26.763403, 6.3280935, 25.896816),
-    Q(6.3280935, 25.614674, 6.590085, 25.614674),
-    Q(6.872229, 25.614674, 6.872229, 25.896816),
-    Q(6.872229, 27.146313, 7.4566708, 28.022976),
-    Q(8.041113, 28.89964, 9.089077, 28.89964),
-    Q(9.754131, 28.89964, 10.187425, 28.647724),
-    Q(10.620718, 28.39581, 10.832326, 27.871826),
-    Q(11.043934, 27.347845, 11.1144705, 26.763403),
-    Q(11.185006, 26.17896, 11.185006, 25.272068),
-    Q(11.185006, 23.055222, 10.590488, 21.926643),
-    Q(9.99597, 20.798065, 8.363564, 20.798065),
-    Q(7.9605002, 20.798065, 7.668279, 21.009674),
-    Q(7.3760586, 21.221283, 7.3760586, 21.563887),
-    Q(7.3760586, 21.96695, 7.869811, 22.329706),
-    Q(8.363564, 22.692463, 8.363564, 23.095528),
-    Q(8.363564, 23.740429, 7.910117, 24.193874),
-    Q(7.4566708, 24.64732, 6.831923, 24.64732),
-    Q(6.207175, 24.64732, 5.7537284, 24.193874),
-    Q(5.3002825, 23.740429, 5.3002825, 23.095528),
-    Q(5.3002825, 21.886337, 6.1970983, 21.060059),
-    Q(7.093914, 20.233778, 8.363564, 20.233778),
-    Q(13.704152, 20.233778, 13.704152, 25.272068),
-    Q(13.704152, 27.045547, 12.343814, 28.254738),
-    Q(10.983475, 29.463928, 9.089077, 29.463928),
-    Q(8.766626, 29.463928, 8.293027, 29.151554),
-    Q(7.819428, 28.83918, 7.537284, 28.83918),
-    Q(6.630391, 28.83918, 6.630391, 30.31036),
-    Z,
-    M(2.5997581, 40.38694),
-    L(0.20153165, 40.38694),
-    Q(0.0, 40.38694, 0.0, 40.18541),
-    L(0.0, 20.43531),
-    Q(0.0, 20.233778, 0.20153165, 20.233778),
-    L(2.5997581, 20.233778),
-    Q(2.80129, 20.233778, 2.80129, 20.43531),
-    L(2.80129, 40.18541),
-    Q(2.80129, 40.38694, 2.5997581, 40.38694),
-    Z,
-];
-
-const GLYPH_ACCIDENTAL_FLAT: &[PathSegment] = &[
-    M(0.9270456, 32.043533),
-    Q(0.9270456, 32.28537, 1.1285772, 32.365982),
-    Q(1.3704152, 32.46675, 1.5316405, 32.305523),
-    Q(2.8617494, 31.096333, 2.8617494, 29.564693),
-    Q(2.8617494, 29.020557, 2.629988, 28.617495),
-    Q(2.3982267, 28.214432, 1.9951633, 28.214432),
-    Q(1.5517937, 28.214432, 1.2696494, 28.59734),
-    Q(0.9875051, 28.980251, 0.9673519, 29.484081),
-    L(0.9270456, 32.043533),
-    Z,
-    M(1.4913342, 27.589684),
-    Q(2.3377671, 27.166466, 2.8819027, 27.166466),
-    Q(3.6477227, 27.166466, 4.1112456, 27.77106),
-    Q(4.5747685, 28.375656, 4.5747685, 29.222088),
-    Q(4.5747685, 29.80653, 4.4034667, 30.28013),
-    Q(4.2321644, 30.75373, 3.8694077, 31.146717),
-    Q(3.5066507, 31.539703, 3.1942766, 31.801693),
-    Q(2.8819027, 32.063686, 2.3780735, 32.43652),
-    Q(1.8742443, 32.809353, 1.6122532, 33.031036),
-    Q(1.5114874, 33.11165, 1.350262, 33.333336),
-    Q(1.1890367, 33.55502, 1.037888, 33.675938),
-    Q(0.88673925, 33.79686, 0.7053608, 33.79686),
-    Q(0.5038291, 33.79686, 0.37283355, 33.655785),
-    Q(0.24183798, 33.514713, 0.24183798, 33.29303),
-    L(0.0, 21.100365),
-    Q(0.26199114, 20.939138, 0.54413545, 20.939138),
-    Q(0.82627976, 20.939138, 1.0882709, 21.100365),
-    L(0.9673519, 27.267231),
-    Q(0.9673519, 27.468763, 1.1285772, 27.56953),
-    Q(1.3099557, 27.690449, 1.4913342, 27.589684),
-    Z,
-];
-
-const GLYPH_ACCIDENTAL_SHARP: &[PathSegment] = &[
-    M(4.3530836, 28.375656),
-    L(4.3530836, 30.834343),
-    Q(4.3530836, 30.995567, 4.4538493, 31.096333),
-    Q(4.554615, 31.1971, 4.7158403, 31.1971),
-    Q(4.7763, 31.1971, 4.8367596, 31.176945),
-    L(4.997985, 31.116486),
-    Q(5.0584445, 31.096333, 5.139057, 31.096333),
-    Q(5.3002825, 31.096333, 5.421201, 31.217253),
-    Q(5.5421205, 31.338171, 5.5421205, 31.499397),
-    L(5.5421205, 32.708588),
-    Q(5.5421205, 32.970577, 5.2801294, 33.071342),
-    L(4.5949216, 33.333336),
-    Q(4.3530836, 33.4341, 4.3530836, 33.655785),
-    L(4.3530836, 36.59815),
-    Q(4.3530836, 36.759373, 4.2422414, 36.870216),
-    Q(4.1313987, 36.981056, 3.9903266, 36.981056),
-    Q(3.8291013, 36.981056, 3.7182589, 36.870216),
-    Q(3.6074166, 36.759373, 3.6074166, 36.59815),
-    L(3.6074166, 34.19992),
-    Q(3.6074166, 34.038696, 3.4965742, 33.937927),
-    Q(3.3857317, 33.837166, 3.2446594, 33.837166),
-    Q(3.1640468, 33.837166, 3.1035874, 33.857315),
-    L(2.1765418, 34.220074),
-    Q(1.9347038, 34.300686, 1.9347038, 34.542526),
-    L(1.9347038, 37.484886),
-    Q(1.9347038, 37.64611, 1.8137848, 37.756954),
-    Q(1.6928658, 37.867798, 1.5517937, 37.867798),
-    Q(1.4107215, 37.867798, 1.2998791, 37.756954),
-    Q(1.1890367, 37.64611, 1.1890367, 37.484886),
-    L(1.1890367, 35.086662),
-    Q(1.1890367, 34.925434, 1.0781944, 34.82467),
-    Q(0.9673519, 34.723904, 0.82627976, 34.723904),
-    Q(0.76582026, 34.723904, 0.7053608, 34.744057),
-    L(0.54413545, 34.804516),
-    Q(0.5038291, 34.82467, 0.4030633, 34.82467),
-    Q(0.24183798, 34.82467, 0.12091899, 34.70375),
-    Q(0.0, 34.582832, 0.0, 34.421608),
-    L(0.0, 33.212418),
-    Q(0.0, 32.93027, 0.26199114, 32.829506),
-    L(0.94719875, 32.58767),
-    Q(1.1890367, 32.527206, 1.1890367, 32.245064),
-    L(1.1890367, 29.786377),
-    Q(1.1890367, 29.625153, 1.0781944, 29.524387),
-    Q(0.9673519, 29.42362, 0.82627976, 29.42362),
-    Q(0.76582026, 29.42362, 0.72551394, 29.443773),
-    L(0.54413545, 29.504234),
-    Q(0.5038291, 29.524387, 0.4030633, 29.524387),
-    Q(0.24183798, 29.524387, 0.12091899, 29.403467),
-    Q(0.0, 29.282549, 0.0, 29.121323),
-    L(0.0, 27.912132),
-    Q(0.0, 27.650143, 0.26199114, 27.549377),
-    L(0.9673519, 27.287384),
-    Q(1.1890367, 27.186619, 1.1890367, 26.964935),
-    L(1.1890367, 24.022572),
-    Q(1.1890367, 23.861347, 1.2998791, 23.750505),
-    Q(1.4107215, 23.639662, 1.5517937, 23.639662),
-    Q(1.6928658, 23.639662, 1.8137848, 23.750505),
-    Q(1.9347038, 23.861347, 1.9347038, 24.022572),
-    L(1.9347038, 26.4208),
-    Q(1.9347038, 26.582024, 2.0455463, 26.68279),
-    Q(2.1563888, 26.783556, 2.317614, 26.783556),
-    Q(2.3780735, 26.783556, 2.4385328, 26.763403),
-    L(3.3655787, 26.400646),
-    Q(3.6074166, 26.299881, 3.6074166, 26.058043),
-    L(3.6074166, 23.135834),
-    Q(3.6074166, 22.974607, 3.7182589, 22.863766),
-    Q(3.8291013, 22.752922, 3.9903266, 22.752922),
-    Q(4.1313987, 22.752922, 4.2422414, 22.863766),
-    Q(4.3530836, 22.974607, 4.3530836, 23.135834),
-    L(4.3530836, 25.554213),
-    Q(4.3530836, 25.695286, 4.4538493, 25.796051),
-    Q(4.554615, 25.896816, 4.6956873, 25.896816),
-    Q(4.756147, 25.896816, 4.8367596, 25.876663),
-    L(4.997985, 25.816204),
-    Q(5.038291, 25.796051, 5.139057, 25.796051),
-    Q(5.3002825, 25.796051, 5.421201, 25.91697),
-    Q(5.5421205, 26.03789, 5.5421205, 26.199114),
-    L(5.5421205, 27.408304),
-    Q(5.5421205, 27.690449, 5.2801294, 27.791214),
-    L(4.5949216, 28.033052),
-    Q(4.3530836, 28.093512, 4.3530836, 28.375656),
-    Z,
-    M(3.6074166, 31.358324),
-    L(3.6074166, 28.919792),
-    Q(3.6074166, 28.778719, 3.4965742, 28.667877),
-    Q(3.3857317, 28.557034, 3.2446594, 28.557034),
-    Q(3.1842, 28.557034, 3.1237407, 28.577187),
-    L(2.1765418, 28.919792),
-    Q(1.9347038, 29.020557, 1.9347038, 29.262396),
-    L(1.9347038, 31.700928),
-    Q(1.9347038, 31.862154, 2.0354695, 31.96292),
-    Q(2.1362355, 32.063686, 2.2974608, 32.063686),
-    Q(2.3579202, 32.063686, 2.4183798, 32.043533),
-    L(3.3655787, 31.700928),
-    Q(3.6074166, 31.600163, 3.6074166, 31.358324),
-    Z,
-];
-
-const GLYPH_ACCIDENTAL_NEUTRAL: &[PathSegment] = &[
-    M(1.3905684, 26.68279),
-    L(3.56711, 26.239422),
-    L(3.6275697, 26.239422),
-    Q(3.9903266, 26.239422, 3.9903266, 26.582024),
-    L(4.1313987, 37.867798),
-    Q(3.8492546, 38.00887, 3.6275697, 38.00887),
-    Q(3.4058847, 38.00887, 3.1237407, 37.867798),
-    L(3.1842, 34.300686),
-    Q(3.1842, 34.159615, 3.083434, 34.04877),
-    Q(2.9826684, 33.937927, 2.821443, 33.937927),
-    Q(2.7811368, 33.937927, 2.7609835, 33.937927),
-    L(0.5844418, 34.40145),
-    L(0.5239823, 34.40145),
-    Q(0.16122532, 34.40145, 0.16122532, 34.05885),
-    L(0.0, 22.752922),
-    Q(0.2821443, 22.61185, 0.5239823, 22.61185),
-    Q(0.7456671, 22.61185, 1.0278114, 22.752922),
-    L(0.9673519, 26.299881),
-    Q(0.9673519, 26.461105, 1.0681177, 26.571949),
-    Q(1.1688836, 26.68279, 1.3301089, 26.68279),
-    Q(1.350262, 26.68279, 1.3905684, 26.68279),
-    Z,
-    M(1.3099557, 32.325676),
-    L(2.9020557, 31.96292),
-    Q(3.2043533, 31.90246, 3.2043533, 31.600163),
-    L(3.2648127, 28.677954),
-    Q(3.2648127, 28.516727, 3.1438937, 28.39581),
-    Q(3.0229747, 28.27489, 2.8617494, 28.315197),
-    L(1.2494962, 28.6578),
-    Q(0.94719875, 28.71826, 0.94719875, 29.000404),
-    L(0.88673925, 31.983072),
-    Q(0.88673925, 32.1443, 1.0177348, 32.255142),
-    Q(1.1487304, 32.365982, 1.3099557, 32.325676),
-    Z,
-];
-
-pub const GLYPH_CLEF_BASS: &[PathSegment] = &[
-    M(11.487304, 32.829506),
-    Q(11.487304, 32.40629, 11.789601, 32.103992),
-    Q(12.091899, 31.801693, 12.515116, 31.801693),
-    Q(12.938332, 31.801693, 13.240629, 32.103992),
-    Q(13.542927, 32.40629, 13.542927, 32.829506),
-    Q(13.542927, 33.252724, 13.240629, 33.55502),
-    Q(12.938332, 33.857315, 12.515116, 33.857315),
-    Q(12.091899, 33.857315, 11.789601, 33.55502),
-    Q(11.487304, 33.252724, 11.487304, 32.829506),
-    Z,
-    M(11.487304, 27.791214),
-    Q(11.487304, 27.367998, 11.789601, 27.0657),
-    Q(12.091899, 26.763403, 12.515116, 26.763403),
-    Q(12.938332, 26.763403, 13.240629, 27.0657),
-    Q(13.542927, 27.367998, 13.542927, 27.791214),
-    Q(13.542927, 28.214432, 13.240629, 28.516727),
-    Q(12.938332, 28.819025, 12.515116, 28.819025),
-    Q(12.091899, 28.819025, 11.789601, 28.516727),
-    Q(11.487304, 28.214432, 11.487304, 27.791214),
-    Z,
-    M(2.458686, 27.912132),
-    Q(3.3655787, 27.912132, 3.9500203, 28.496574),
-    Q(4.534462, 29.081017, 4.534462, 29.92745),
-    Q(4.534462, 30.81419, 3.9500203, 31.438938),
-    Q(3.3655787, 32.063686, 2.5191455, 32.063686),
-    Q(1.6122532, 32.063686, 0.93712217, 31.438938),
-    Q(0.26199114, 30.81419, 0.26199114, 29.92745),
-    Q(0.26199114, 27.750908, 1.713019, 26.380493),
-    Q(3.1640468, 25.010078, 5.3204355, 25.010078),
-    Q(7.7589684, 25.010078, 9.300686, 26.410723),
-    Q(10.842402, 27.811367, 10.842402, 30.068521),
-    Q(10.842402, 37.202744, 0.48367596, 41.77751),
-    Q(0.3426038, 41.83797, 0.26199114, 41.83797),
-    Q(0.0, 41.83797, 0.0, 41.57598),
-    Q(0.0, 41.434906, 0.26199114, 41.27368),
-    Q(1.8742443, 40.326485, 2.9322855, 39.600967),
-    Q(3.9903266, 38.875454, 5.118904, 37.847645),
-    Q(6.2474813, 36.81983, 6.8923826, 35.781944),
-    Q(7.537284, 34.744057, 7.9605002, 33.333336),
-    Q(8.383717, 31.922613, 8.383717, 30.270054),
-    Q(8.383717, 28.174124, 7.4667478, 26.904476),
-    Q(6.5497785, 25.634827, 4.957679, 25.634827),
-    Q(3.9903266, 25.634827, 3.2043533, 26.088272),
-    Q(2.4183798, 26.541718, 2.1160822, 27.327692),
-    Q(2.0354695, 27.589684, 2.1362355, 27.730755),
-    Q(2.2773075, 27.912132, 2.458686, 27.912132),
-    Z,
-];
-
-pub const GLYPH_CLEF_TREBLE: &[PathSegment] = &[
-    M(7.4220147, 18.250267),
-    Q(7.4220147, 19.48727, 8.067408, 20.607742),
-    Q(8.7128, 21.728216, 10.272499, 23.305843),
-    Q(12.5851555, 21.961275, 13.490498, 20.822874),
-    Q(14.395841, 19.684473, 14.395841, 18.196484),
-    Q(14.395841, 16.816061, 13.47257, 15.946573),
-    Q(12.5493, 15.077087, 11.007529, 15.077087),
-    Q(9.411975, 15.077087, 8.416995, 15.937611),
-    Q(7.4220147, 16.798134, 7.4220147, 18.250267),
-    Z,
-    M(10.2007885, 36.930798),
-    Q(14.521334, 36.930798, 17.371817, 34.169952),
-    L(9.537468, 26.56866),
-    Q(7.547508, 27.787735, 6.7228394, 28.585512),
-    Q(5.8981714, 29.38329, 5.5037646, 30.297596),
-    Q(5.109358, 31.211903, 5.109358, 32.377193),
-    Q(5.109358, 34.47472, 6.498745, 35.70276),
-    Q(7.8881316, 36.930798, 10.2007885, 36.930798),
-    Z,
-    M(2.0258157, 32.448906),
-    Q(2.0258157, 30.11832, 3.271782, 28.325562),
-    Q(4.5177484, 26.532806, 7.744711, 24.704193),
-    Q(6.2208676, 23.001074, 5.6740766, 22.122623),
-    Q(5.1272855, 21.244171, 4.8045893, 20.29401),
-    Q(4.481893, 19.34385, 4.481893, 18.321978),
-    Q(4.481893, 15.632841, 6.2387953, 14.1269245),
-    Q(7.995697, 12.62101, 11.133022, 12.62101),
-    Q(14.037289, 12.62101, 15.704554, 14.117962),
-    Q(17.371817, 15.614914, 17.371817, 18.286121),
-    Q(17.371817, 20.204372, 16.152742, 21.826818),
-    Q(14.933667, 23.449263, 12.119039, 25.116528),
-    L(19.41556, 32.126205),
-    Q(20.419504, 31.014698, 21.020079, 29.517746),
-    Q(21.620651, 28.020794, 22.032986, 26.245964),
-    L(25.044819, 26.245964),
-    Q(23.825743, 31.37325, 21.369665, 34.02653),
-    L(26.730011, 39.243454),
-    L(22.624596, 39.243454),
-    L(19.307995, 36.052345),
-    Q(17.192541, 37.952667, 15.005378, 38.777336),
-    Q(12.818214, 39.602005, 10.129078, 39.602005),
-    Q(6.27465, 39.602005, 4.150233, 37.701683),
-    Q(2.0258157, 35.80136, 2.0258157, 32.448906),
-    Z,
-];
-
-pub const GLYPH_CLEF_X: &[PathSegment] = &[
-    M(11.487304, 32.829506),
-    Q(11.487304, 32.40629, 11.789601, 32.103992),
-    Q(12.091899, 31.801693, 12.515116, 31.801693),
-    Q(12.938332, 31.801693, 13.240629, 32.103992),
-    Q(13.542927, 32.40629, 13.542927, 32.829506),
-    Q(13.542927, 33.252724, 13.240629, 33.55502),
-    Q(12.938332, 33.857315, 12.515116, 33.857315),
-    Q(12.091899, 33.857315, 11.789601, 33.55502),
-    Q(11.487304, 33.252724, 11.487304, 32.829506),
-    Z,
-    M(11.487304, 27.791214),
-    Q(11.487304, 27.367998, 11.789601, 27.0657),
-    Q(12.091899, 26.763403, 12.515116, 26.763403),
-    Q(12.938332, 26.763403, 13.240629, 27.0657),
-    Q(13.542927, 27.367998, 13.542927, 27.791214),
-    Q(13.542927, 28.214432, 13.240629, 28.516727),
-    Q(12.938332, 28.819025, 12.515116, 28.819025),
-    Q(12.091899, 28.819025, 11.789601, 28.516727),
-    Q(11.487304, 28.214432, 11.487304, 27.791214),
-    Z,
-    M(2.458686, 27.912132),
-    Q(3.3655787, 27.912132, 3.9500203, 28.496574),
-    Q(4.534462, 29.081017, 4.534462, 29.92745),
-    Q(4.534462, 30.81419, 3.9500203, 31.438938),
-    Q(3.3655787, 32.063686, 2.5191455, 32.063686),
-    Q(1.6122532, 32.063686, 0.93712217, 31.438938),
-    Q(0.26199114, 30.81419, 0.26199114, 29.92745),
-    Q(0.26199114, 27.750908, 1.713019, 26.380493),
-    Q(3.1640468, 25.010078, 5.3204355, 25.010078),
-    Q(7.7589684, 25.010078, 9.300686, 26.410723),
-    Q(10.842402, 27.811367, 10.842402, 30.068521),
-    Q(10.842402, 37.202744, 0.48367596, 41.77751),
-    Q(0.3426038, 41.83797, 0.26199114, 41.83797),
-    Q(0.0, 41.83797, 0.0, 41.57598),
-    Q(0.0, 41.434906, 0.26199114, 41.27368),
-    Q(1.8742443, 40.326485, 2.9322855, 39.600967),
-    Q(3.9903266, 38.875454, 5.118904, 37.847645),
-    Q(6.2474813, 36.81983, 6.8923826, 35.781944),
-    Q(7.537284, 34.744057, 7.9605002, 33.333336),
-    Q(8.383717, 31.922613, 8.383717, 30.270054),
-    Q(8.383717, 28.174124, 7.4667478, 26.904476),
-    Q(6.5497785, 25.634827, 4.957679, 25.634827),
-    Q(3.9903266, 25.634827, 3.2043533, 26.088272),
-    Q(2.4183798, 26.541718, 2.1160822, 27.327692),
-    Q(2.0354695, 27.589684, 2.1362355, 27.730755),
-    Q(2.2773075, 27.912132, 2.458686, 27.912132),
-    Z,
-];
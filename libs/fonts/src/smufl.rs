@@ -0,0 +1,138 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Codepoints of the glyphs the engraver draws, as assigned by the SMuFL glyphnames.json
+pub const SMUFL_CODEPOINTS: &[(&str, u32)] = &[
+    ("brace", 0xE000),
+    ("staff5Lines", 0xE014),
+    ("segno", 0xE047),
+    ("coda", 0xE048),
+    ("gClef", 0xE050),
+    ("gClef8vb", 0xE052),
+    ("gClef8va", 0xE053),
+    ("cClef", 0xE05C),
+    ("fClef", 0xE062),
+    ("fClef8vb", 0xE064),
+    ("unpitchedPercussionClef1", 0xE069),
+    ("6stringTabClef", 0xE06D),
+    ("noteheadWhole", 0xE0A2),
+    ("noteheadHalf", 0xE0A3),
+    ("noteheadBlack", 0xE0A4),
+    ("augmentationDot", 0xE1E7),
+    ("flag8thUp", 0xE240),
+    ("flag8thDown", 0xE241),
+    ("flag16thUp", 0xE242),
+    ("flag16thDown", 0xE243),
+    ("accidentalFlat", 0xE260),
+    ("accidentalNatural", 0xE261),
+    ("accidentalSharp", 0xE262),
+    ("accidentalDoubleSharp", 0xE263),
+    ("accidentalDoubleFlat", 0xE264),
+    ("restWhole", 0xE4E3),
+    ("restHalf", 0xE4E4),
+    ("restQuarter", 0xE4E5),
+    ("rest8th", 0xE4E6),
+    ("rest16th", 0xE4E7),
+];
+
+/// Glyphs of the built-in font, which predates SMuFL: name, codepoint and the move down to the SMuFL origin in staff spaces
+pub const LEGACY_GLYPHS: &[(&str, u32, f32)] = &[
+    ("staff5Lines", 61, 0.0),
+    // no coda, the engraver draws one instead
+    ("segno", 37, 0.0),
+    ("gClef", 38, 0.0),
+    ("cClef", 66, 0.0),
+    ("fClef", 63, 0.0),
+    ("noteheadWhole", 119, 0.0),
+    ("noteheadHalf", 729, 0.0),
+    ("noteheadBlack", 339, 0.0),
+    ("flag8thUp", 106, 0.0),
+    ("flag8thDown", 236, 0.0),
+    ("accidentalFlat", 98, 0.0),
+    ("accidentalNatural", 110, 0.0),
+    ("accidentalSharp", 35, 0.0),
+    // the x shaped double sharp sits at U+00BF, the inverted question mark, in the legacy encoding
+    ("accidentalDoubleSharp", 191, 0.0),
+    // no accidentalDoubleFlat, the engraver draws two flats instead
+    // the one rest block serves both, hanging from its line or sitting on it
+    ("restWhole", 61623, LEGACY_REST_BLOCK_TOP),
+    ("restHalf", 61623, LEGACY_REST_BLOCK_TOP - LEGACY_REST_BLOCK_HEIGHT),
+    ("restQuarter", 338, 0.0),
+    ("rest8th", 61668, 0.0),
+    ("rest16th", 61637, 0.0),
+];
+
+// the rest block of the built-in font, its top a staff space above the baseline, in staff spaces
+const LEGACY_REST_BLOCK_TOP: f32 = 1.0;
+const LEGACY_REST_BLOCK_HEIGHT: f32 = 0.58;
+
+/// Font size of the built-in font per staff space
+pub const LEGACY_SIZE_PER_SPACE: f32 = 9.9;
+
+/// The parts of a SMuFL font metadata file the engraver uses. Distances are in staff spaces, with y pointing up
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmuflMetadata {
+    #[serde(default)]
+    pub font_name: String,
+    #[serde(default)]
+    pub engraving_defaults: EngravingDefaults,
+    #[serde(default, rename = "glyphBBoxes")]
+    pub glyph_bboxes: BTreeMap<String, GlyphBBox>,
+    #[serde(default)]
+    pub glyphs_with_anchors: BTreeMap<String, GlyphAnchors>,
+    #[serde(default)]
+    pub optional_glyphs: BTreeMap<String, OptionalGlyph>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngravingDefaults {
+    pub staff_line_thickness: Option<f32>,
+    pub stem_thickness: Option<f32>,
+    pub beam_thickness: Option<f32>,
+    pub beam_spacing: Option<f32>,
+    pub leger_line_thickness: Option<f32>,
+    pub leger_line_extension: Option<f32>,
+    pub thin_barline_thickness: Option<f32>,
+    pub thick_barline_thickness: Option<f32>,
+    pub barline_separation: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct GlyphBBox {
+    #[serde(rename = "bBoxNE")]
+    pub bbox_ne: (f32, f32),
+    #[serde(rename = "bBoxSW")]
+    pub bbox_sw: (f32, f32),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+pub struct GlyphAnchors {
+    #[serde(rename = "stemUpSE")]
+    pub stem_up_se: Option<(f32, f32)>,
+    #[serde(rename = "stemDownNW")]
+    pub stem_down_nw: Option<(f32, f32)>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OptionalGlyph {
+    pub codepoint: String,
+}
+
+impl SmuflMetadata {
+    pub fn from_json(json: &str) -> Result<SmuflMetadata, Box<dyn std::error::Error>> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Codepoint of a glyph by its SMuFL name, the font's own optional glyphs first
+    pub fn get_codepoint(&self, name: &str) -> Option<char> {
+        match self.optional_glyphs.get(name) {
+            Some(glyph) => u32::from_str_radix(glyph.codepoint.trim_start_matches("U+"), 16).ok().and_then(char::from_u32),
+            None => SMUFL_CODEPOINTS
+                .iter()
+                .find(|(glyph_name, _)| *glyph_name == name)
+                .and_then(|(_, codepoint)| char::from_u32(*codepoint)),
+        }
+    }
+}
@@ -30,7 +30,7 @@ pub fn get_items(font: &Font, scale: f32, text: &str) -> Vec<GraphicItem> {
     let segments = get_segments(font, scale, text);
     let mut items = Vec::new();
     for segment in segments {
-        items.push(GraphicItem::Path(segment, 0.0, 0.0, Stroke::None, Fill::Solid(Color::Red), None));
+        items.push(GraphicItem::Path(segment, 0.0, 0.0, Stroke::None, Fill::Solid(Color::Red), None, None));
     }
     items
 }
@@ -52,7 +52,6 @@ pub fn get_segments(font: &Font, scale: f32, text: &str) -> Vec<Vec<PathSegment>
             segments2.push(segments);
         }
     }
-    dbg!(&segments2);
     segments2
 }
 
@@ -60,11 +59,12 @@ pub fn get_dimensions(font: &Font, scale: f32, text: &str) -> (f32, f32) {
     let scale = Scale::uniform(scale);
     let v_metrics = font.v_metrics(scale);
     let glyphs: Vec<PositionedGlyph<'_>> = font.layout(text, scale, point(0., v_metrics.ascent)).collect();
-    let glyphs_height = (v_metrics.ascent - v_metrics.descent).ceil() as f32;
+    let glyphs_height = (v_metrics.ascent - v_metrics.descent).ceil();
     let glyphs_width = {
-        let min_x = glyphs.first().map(|g| g.pixel_bounding_box().unwrap().min.x).unwrap_or(0) as f32;
-        let max_x = glyphs.last().map(|g| g.pixel_bounding_box().unwrap().max.x).unwrap_or(0) as f32;
-        (max_x - min_x)
+        // spaces have no bounding box
+        let min_x = glyphs.iter().find_map(|g| g.pixel_bounding_box()).map(|bb| bb.min.x).unwrap_or(0) as f32;
+        let max_x = glyphs.iter().rev().find_map(|g| g.pixel_bounding_box()).map(|bb| bb.max.x).unwrap_or(0) as f32;
+        max_x - min_x 
     };
     (glyphs_width, glyphs_height)
 }
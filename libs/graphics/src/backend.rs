@@ -0,0 +1,63 @@
+use crate::color::Color;
+use crate::fill::Fill;
+use crate::graphicitem::{GraphicItem, GraphicItems, PathCache, TextMetrics, items_bounding_box, items_move};
+use crate::path::PathSegment;
+use crate::stroke::Stroke;
+use crate::transform::Transform;
+
+/// An output format for graphic items, drawn in item order between `begin` and `finish`.
+/// Groups nest, and a group's transform applies on top of the transforms of the groups around it.
+pub trait RenderBackend {
+    type Output;
+
+    /// Starts a drawing of `width` by `height` with the origin at the top left, a new page for backends with pages
+    fn begin(&mut self, width: f32, height: f32);
+    fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, stroke: &Stroke, id: Option<&str>);
+    #[allow(clippy::too_many_arguments)]
+    fn rect(&mut self, x: f32, y: f32, w: f32, h: f32, stroke: &Stroke, fill: &Fill, id: Option<&str>);
+    /// Ellipse centred at (cx, cy)
+    #[allow(clippy::too_many_arguments)]
+    fn ellipse(&mut self, cx: f32, cy: f32, rx: f32, ry: f32, stroke: &Stroke, fill: &Fill, id: Option<&str>);
+    /// Path segments drawn at (x, y), a cached path may be drawn again by reference
+    #[allow(clippy::too_many_arguments)]
+    fn path(&mut self, segments: &[PathSegment], x: f32, y: f32, stroke: &Stroke, fill: &Fill, cache: Option<&PathCache>, id: Option<&str>);
+    fn text(&mut self, x: f32, y: f32, text: &str, color: &Color, id: Option<&str>);
+    fn begin_group(&mut self, id: Option<&str>, class: Option<&str>, transform: &Transform);
+    fn end_group(&mut self);
+    fn finish(&mut self) -> Self::Output;
+    /// Measures text items for the size of the drawing
+    fn text_metrics(&mut self) -> &dyn TextMetrics;
+}
+
+/// Draws the items with a margin around their bounding box, which sets the size of the drawing
+pub fn render_items<B: RenderBackend>(backend: &mut B, items: GraphicItems, margin: f32) -> B::Output {
+    render_page(backend, items, margin);
+    backend.finish()
+}
+
+/// Draws the items like `render_items` without finishing, so that backends with pages can draw several
+pub fn render_page<B: RenderBackend>(backend: &mut B, items: GraphicItems, margin: f32) {
+    let bbox = items_bounding_box(&items, backend.text_metrics());
+    let items = items_move(items, margin - bbox.0, margin - bbox.1);
+    backend.begin(bbox.2 - bbox.0 + 2.0 * margin, bbox.3 - bbox.1 + 2.0 * margin);
+    for item in items.iter() {
+        render_item(backend, item);
+    }
+}
+
+pub fn render_item<B: RenderBackend>(backend: &mut B, item: &GraphicItem) {
+    match item {
+        GraphicItem::Line(x1, y1, x2, y2, stroke, id) => backend.line(*x1, *y1, *x2, *y2, stroke, id.as_deref()),
+        GraphicItem::Rect(x, y, w, h, stroke, fill, id) => backend.rect(*x, *y, *w, *h, stroke, fill, id.as_deref()),
+        GraphicItem::Ellipse(cx, cy, rx, ry, stroke, fill, id) => backend.ellipse(*cx, *cy, *rx, *ry, stroke, fill, id.as_deref()),
+        GraphicItem::Path(segments, x, y, stroke, fill, cache, id) => backend.path(segments, *x, *y, stroke, fill, cache.as_ref(), id.as_deref()),
+        GraphicItem::Text(x, y, text, color, id) => backend.text(*x, *y, text, color, id.as_deref()),
+        GraphicItem::Group(children, transform, class, id) => {
+            backend.begin_group(id.as_deref(), class.as_deref(), transform);
+            for child in children.iter() {
+                render_item(backend, child);
+            }
+            backend.end_group();
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Color {
     Black,
     Gray,
@@ -36,7 +36,7 @@ impl Display for Color {
             Color::Purple => write!(f, "purple"),
             Color::RGBA(r, g, b, a) => {
                 // Custom color in rgba format
-                return write!(f, "rgba({},{},{},{})", r, g, b, a);
+                write!(f, "rgba({},{},{},{})", r, g, b, a)
             }
         }
     }
@@ -4,4 +4,49 @@ use crate::color::Color;
 pub enum Fill {
     None,
     Solid(Color),
+    LinearGradient(LinearGradient),
+}
+
+impl Fill {
+    /// Single colour of the fill, the first stop of a gradient for outputs that only paint solid colours
+    pub fn get_color(&self) -> Option<Color> {
+        match self {
+            Fill::None => None,
+            Fill::Solid(color) => Some(*color),
+            Fill::LinearGradient(gradient) => gradient.stops.first().map(|(_, color)| *color),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LinearGradient {
+    // start and end of the gradient vector, as fractions of the bounding box of the filled item
+    pub x1: f32,
+    pub y1: f32,
+    pub x2: f32,
+    pub y2: f32,
+    // offsets from 0.0 to 1.0 along the vector, in increasing order
+    pub stops: Vec<(f32, Color)>,
+}
+
+impl LinearGradient {
+    pub fn horizontal(from: Color, to: Color) -> LinearGradient {
+        LinearGradient {
+            x1: 0.0,
+            y1: 0.0,
+            x2: 1.0,
+            y2: 0.0,
+            stops: vec![(0.0, from), (1.0, to)],
+        }
+    }
+
+    pub fn vertical(from: Color, to: Color) -> LinearGradient {
+        LinearGradient {
+            x1: 0.0,
+            y1: 0.0,
+            x2: 0.0,
+            y2: 1.0,
+            stops: vec![(0.0, from), (1.0, to)],
+        }
+    }
 }
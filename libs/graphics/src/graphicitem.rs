@@ -1,23 +1,57 @@
+use crate::color::Color;
 use crate::fill::Fill;
 use crate::path::{PathSegment, PathUtils};
+use crate::rectangle::Rectangle;
 use crate::stroke::Stroke;
+use crate::transform::Transform;
+
+/// Font size of the text items, the default font size of svg text
+pub const TEXT_SIZE: f32 = 16.0;
 
 #[derive(Debug, Clone)]
 pub enum PathCache {
     UseCache,
 }
 
+/// The `Option<String>` of each item identifies what it is drawn for, as space separated words: the kind of element
+/// first, then `name-value` pairs of the objects it belongs to, e.g. `"head-30 note-12 part-0 bar-1"` or `"ledger note-12 part-0 bar-1"`.
+/// A value on the kind makes the element itself identifiable.
+///
+/// A `Group` draws its children with its transform on top of the transforms of the groups around it.
+/// Its optional class and id are written as they are.
 #[derive(Debug, Clone)]
 pub enum GraphicItem {
     Line(f32, f32, f32, f32, Stroke, Option<String>),
     Rect(f32, f32, f32, f32, Stroke, Fill, Option<String>),
     Ellipse(f32, f32, f32, f32, Stroke, Fill, Option<String>),
-    Path(Vec<PathSegment>, f32, f32, Stroke, Fill, Option<PathCache>),
-    Text(f32, f32, String, Option<String>),
+    Path(Vec<PathSegment>, f32, f32, Stroke, Fill, Option<PathCache>, Option<String>),
+    Text(f32, f32, String, Color, Option<String>),
+    Group(Vec<GraphicItem>, Transform, Option<String>, Option<String>),
+}
+
+impl GraphicItem {
+    pub fn get_id(&self) -> Option<&str> {
+        match self {
+            GraphicItem::Line(.., id) | GraphicItem::Rect(.., id) | GraphicItem::Ellipse(.., id) | GraphicItem::Path(.., id) | GraphicItem::Text(.., id) | GraphicItem::Group(.., id) => id.as_deref(),
+        }
+    }
+
+    pub fn with_id(mut self, new_id: Option<String>) -> GraphicItem {
+        match &mut self {
+            GraphicItem::Line(.., id) | GraphicItem::Rect(.., id) | GraphicItem::Ellipse(.., id) | GraphicItem::Path(.., id) | GraphicItem::Text(.., id) | GraphicItem::Group(.., id) => *id = new_id,
+        }
+        self
+    }
 }
 
 pub type GraphicItems = Vec<GraphicItem>;
 
+/// Measures the text of `GraphicItem::Text`, which carries no font of its own
+pub trait TextMetrics {
+    /// Ink bounds of the text at `TEXT_SIZE`, relative to the start of its baseline
+    fn get_text_rectangle(&self, text: &str) -> Rectangle;
+}
+
 pub fn items_scale(items: GraphicItems, scale_x: f32, scale_y: f32) -> GraphicItems {
     items
         .iter()
@@ -25,7 +59,7 @@ pub fn items_scale(items: GraphicItems, scale_x: f32, scale_y: f32) -> GraphicIt
             GraphicItem::Line(x1, y1, x2, y2, stroke, id) => GraphicItem::Line(x1 * scale_x, y1 * scale_y, x2 * scale_x, y2 * scale_y, stroke.clone(), id.clone()),
             GraphicItem::Rect(x, y, w, h, stroke, fill, id) => GraphicItem::Rect(x * scale_x, y * scale_y, w * scale_x, h * scale_y, stroke.clone(), fill.clone(), id.clone()),
             GraphicItem::Ellipse(x, y, w, h, stroke, fill, id) => GraphicItem::Ellipse(x * scale_x, y * scale_y, w * scale_x, h * scale_y, stroke.clone(), fill.clone(), id.clone()),
-            GraphicItem::Path(segments, x, y, stroke, fill, id) => {
+            GraphicItem::Path(segments, x, y, stroke, fill, cache, id) => {
                 let new_segments = segments
                     .iter()
                     .map(|segment| match segment {
@@ -36,9 +70,10 @@ pub fn items_scale(items: GraphicItems, scale_x: f32, scale_y: f32) -> GraphicIt
                         PathSegment::Z => PathSegment::Z,
                     })
                     .collect();
-                GraphicItem::Path(new_segments, x * scale_x, y * scale_y, stroke.clone(), fill.clone(), id.clone())
+                GraphicItem::Path(new_segments, x * scale_x, y * scale_y, stroke.clone(), fill.clone(), cache.clone(), id.clone())
             }
-            GraphicItem::Text(x, y, text, id) => GraphicItem::Text(x * scale_x, y * scale_y, text.clone(), id.clone()),
+            GraphicItem::Text(x, y, text, color, id) => GraphicItem::Text(x * scale_x, y * scale_y, text.clone(), *color, id.clone()),
+            GraphicItem::Group(children, transform, class, id) => GraphicItem::Group(children.clone(), transform.then(&Transform::scale(scale_x, scale_y)), class.clone(), id.clone()),
         })
         .collect()
 }
@@ -50,16 +85,18 @@ pub fn items_move(items: GraphicItems, move_x: f32, move_y: f32) -> GraphicItems
             GraphicItem::Line(x1, y1, x2, y2, stroke, id) => GraphicItem::Line(x1 + move_x, y1 + move_y, x2 + move_x, y2 + move_y, stroke.clone(), id.clone()),
             GraphicItem::Rect(x, y, w, h, stroke, fill, id) => GraphicItem::Rect(x + move_x, y + move_y, *w, *h, stroke.clone(), fill.clone(), id.clone()),
             GraphicItem::Ellipse(x, y, w, h, stroke, fill, id) => GraphicItem::Ellipse(x + move_x, y + move_y, *w, *h, stroke.clone(), fill.clone(), id.clone()),
-            GraphicItem::Path(segments, x, y, stroke, fill, id) => {
+            GraphicItem::Path(segments, x, y, stroke, fill, cache, id) => {
                 let new_segments = PathUtils::path_move(segments.clone(), move_x, move_y);
-                GraphicItem::Path(new_segments, *x, *y, stroke.clone(), fill.clone(), id.clone())
+                GraphicItem::Path(new_segments, *x, *y, stroke.clone(), fill.clone(), cache.clone(), id.clone())
             }
-            GraphicItem::Text(x, y, text, id) => GraphicItem::Text(x + move_x, y + move_y, text.clone(), id.clone()),
+            GraphicItem::Text(x, y, text, color, id) => GraphicItem::Text(x + move_x, y + move_y, text.clone(), *color, id.clone()),
+            GraphicItem::Group(children, transform, class, id) => GraphicItem::Group(children.clone(), transform.then(&Transform::translate(move_x, move_y)), class.clone(), id.clone()),
         })
         .collect()
 }
 
-pub fn items_bounding_box(items: &GraphicItems) -> (f32, f32, f32, f32) {
+/// Bounds of the items as (min_x, min_y, max_x, max_y)
+pub fn items_bounding_box(items: &GraphicItems, text_metrics: &dyn TextMetrics) -> (f32, f32, f32, f32) {
     let mut min_x: f32 = f32::MAX;
     let mut min_y: f32 = f32::MAX;
     let mut max_x: f32 = f32::MIN;
@@ -84,7 +121,7 @@ pub fn items_bounding_box(items: &GraphicItems) -> (f32, f32, f32, f32) {
             max_x = max_x.max(*x + w);
             max_y = max_y.max(*y + h);
         }
-        GraphicItem::Path(segments, x, y, _, _, _) => {
+        GraphicItem::Path(segments, x, y, ..) => {
             let (min_x_, min_y_, max_x_, max_y_) = PathUtils::path_bounding_box(segments.clone());
             // dbg!(min_x_, min_y_, max_x_, max_y_, x, y);
 
@@ -96,16 +133,27 @@ pub fn items_bounding_box(items: &GraphicItems) -> (f32, f32, f32, f32) {
             // min_y = min_y.min(min_y_ + y);
             // max_x = max_x.max(max_x_ - x);
             // max_y = max_y.max(max_y_ - y);
-        } //-----------
-        _ => {} //
-
-                // GraphicItem::Text(x, y, text, font, _) => {
-                //     let (w, h) = text_size(text.clone(), font.clone());
-                //     min_x = min_x.min(x);
-                //     min_y = min_y.min(y);
-                //     max_x = max_x.max(x + w);
-                //     max_y = max_y.max(y + h);
-                // }
+        }
+        GraphicItem::Text(x, y, text, ..) => {
+            let (text_x, text_y, w, h) = text_metrics.get_text_rectangle(text);
+            min_x = min_x.min(x + text_x);
+            min_y = min_y.min(y + text_y);
+            max_x = max_x.max(x + text_x + w);
+            max_y = max_y.max(y + text_y + h);
+        }
+        GraphicItem::Group(children, transform, ..) => {
+            // the corners of the children's bounds, transformed
+            let (min_x_, min_y_, max_x_, max_y_) = items_bounding_box(children, text_metrics);
+            if min_x_ <= max_x_ {
+                for (x, y) in [(min_x_, min_y_), (max_x_, min_y_), (min_x_, max_y_), (max_x_, max_y_)] {
+                    let (x, y) = transform.apply(x, y);
+                    min_x = min_x.min(x);
+                    min_y = min_y.min(y);
+                    max_x = max_x.max(x);
+                    max_y = max_y.max(y);
+                }
+            }
+        }
     });
 
     (min_x, min_y, max_x, max_y)
@@ -1,15 +1,51 @@
+pub mod backend;
 pub mod color;
 pub mod fill;
 pub mod graphicitem;
 pub mod path;
 pub mod rectangle;
 pub mod stroke;
+pub mod theme;
+pub mod transform;
 
 #[cfg(test)]
 mod tests {
+    use crate::{
+        color::Color,
+        fill::Fill,
+        graphicitem::{GraphicItem, TextMetrics, items_bounding_box},
+        path::{PathSegment, PathUtils},
+        rectangle::Rectangle,
+        stroke::Stroke,
+        transform::Transform,
+    };
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test1() {
         assert!(true);
     }
+
+    #[test]
+    fn test_bounding_box() {
+        // curves reach halfway to a quadratic control point and three quarters to cubic ones
+        let quadratic = vec![PathSegment::M(0., 0.), PathSegment::Q(10., 20., 20., 0.)];
+        assert_eq!(PathUtils::path_bounding_box(quadratic), (0., 0., 20., 10.));
+        let cubic = vec![PathSegment::M(0., 0.), PathSegment::C(0., -10., 10., -10., 10., 0.), PathSegment::Z];
+        assert_eq!(PathUtils::path_bounding_box(cubic), (0., -7.5, 10., 0.));
+
+        struct Metrics;
+        impl TextMetrics for Metrics {
+            fn get_text_rectangle(&self, text: &str) -> Rectangle {
+                (1., -12., text.len() as f32 * 8., 16.)
+            }
+        }
+        let items = vec![GraphicItem::Text(100., 50., "Fine".to_string(), Color::Black, None)];
+        assert_eq!(items_bounding_box(&items, &Metrics), (101., 38., 133., 54.));
+
+        // groups are bounded by the transformed corners of their children
+        let rect = GraphicItem::Rect(0., 0., 10., 20., Stroke::None, Fill::None, None);
+        let items = vec![GraphicItem::Group(vec![rect], Transform::scale(2., -1.).then(&Transform::translate(5., 0.)), None, None)];
+        assert_eq!(items_bounding_box(&items, &Metrics), (5., -20., 25., 0.));
+    }
 }
@@ -69,36 +69,38 @@ impl PathUtils {
         path_buf
     }
 
+    /// Tight bounds of the outline as (min_x, min_y, max_x, max_y), curves included up to their extremes rather than their control points
     pub fn path_bounding_box(segments: PathSegments) -> (f32, f32, f32, f32) {
         let mut min_x = f32::MAX;
         let mut min_y = f32::MAX;
         let mut max_x = f32::MIN;
         let mut max_y = f32::MIN;
+        let mut add = |x: f32, y: f32| {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        };
 
+        let (mut current_x, mut current_y) = (0.0, 0.0);
         segments.iter().for_each(|segment| match segment {
-            PathSegment::M(x, y) => {
-                min_x = min_x.min(*x);
-                min_y = min_y.min(*y);
-                max_x = max_x.max(*x);
-                max_y = max_y.max(*y);
-            }
-            PathSegment::L(x, y) => {
-                min_x = min_x.min(*x);
-                min_y = min_y.min(*y);
-                max_x = max_x.max(*x);
-                max_y = max_y.max(*y);
+            PathSegment::M(x, y) | PathSegment::L(x, y) => {
+                add(*x, *y);
+                (current_x, current_y) = (*x, *y);
             }
             PathSegment::Q(x1, y1, x2, y2) => {
-                min_x = min_x.min(*x1).min(*x2);
-                min_y = min_y.min(*y1).min(*y2);
-                max_x = max_x.max(*x1).max(*x2);
-                max_y = max_y.max(*y1).max(*y2);
+                add(*x2, *y2);
+                for t in quadratic_extremes(current_x, *x1, *x2).into_iter().chain(quadratic_extremes(current_y, *y1, *y2)) {
+                    add(quadratic_at(current_x, *x1, *x2, t), quadratic_at(current_y, *y1, *y2, t));
+                }
+                (current_x, current_y) = (*x2, *y2);
             }
             PathSegment::C(x1, y1, x2, y2, x3, y3) => {
-                min_x = min_x.min(*x1).min(*x2).min(*x3);
-                min_y = min_y.min(*y1).min(*y2).min(*y3);
-                max_x = max_x.max(*x1).max(*x2).max(*x3);
-                max_y = max_y.max(*y1).max(*y2).max(*y3);
+                add(*x3, *y3);
+                for t in cubic_extremes(current_x, *x1, *x2, *x3).into_iter().chain(cubic_extremes(current_y, *y1, *y2, *y3)) {
+                    add(cubic_at(current_x, *x1, *x2, *x3, t), cubic_at(current_y, *y1, *y2, *y3, t));
+                }
+                (current_x, current_y) = (*x3, *y3);
             }
             PathSegment::Z => {}
         });
@@ -106,3 +108,38 @@ impl PathUtils {
         (min_x, min_y, max_x, max_y)
     }
 }
+
+fn quadratic_at(p0: f32, p1: f32, p2: f32, t: f32) -> f32 {
+    let mt = 1.0 - t;
+    mt * mt * p0 + 2.0 * mt * t * p1 + t * t * p2
+}
+
+fn cubic_at(p0: f32, p1: f32, p2: f32, p3: f32, t: f32) -> f32 {
+    let mt = 1.0 - t;
+    mt * mt * mt * p0 + 3.0 * mt * mt * t * p1 + 3.0 * mt * t * t * p2 + t * t * t * p3
+}
+
+// curve parameters strictly inside the segment where the derivative along one axis is zero
+fn quadratic_extremes(p0: f32, p1: f32, p2: f32) -> Option<f32> {
+    let denominator = p0 - 2.0 * p1 + p2;
+    Some((p0 - p1) / denominator).filter(|t| denominator != 0.0 && *t > 0.0 && *t < 1.0)
+}
+
+fn cubic_extremes(p0: f32, p1: f32, p2: f32, p3: f32) -> Vec<f32> {
+    // the derivative divided by 3 is a t^2 + b t + c
+    let a = -p0 + 3.0 * p1 - 3.0 * p2 + p3;
+    let b = 2.0 * (p0 - 2.0 * p1 + p2);
+    let c = p1 - p0;
+    let roots = if a.abs() < 1e-6 {
+        if b != 0.0 { vec![-c / b] } else { Vec::new() }
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            Vec::new()
+        } else {
+            let root = discriminant.sqrt();
+            vec![(-b + root) / (2.0 * a), (-b - root) / (2.0 * a)]
+        }
+    };
+    roots.into_iter().filter(|t| *t > 0.0 && *t < 1.0).collect()
+}
@@ -28,6 +28,17 @@ pub fn rectangles_overlap_x(lefts: &[Rectangle], rights: &[Rectangle]) -> f32 {
     result
 }
 
+pub fn rectangles_overlap_y(tops: &[Rectangle], bottoms: &[Rectangle]) -> f32 {
+    let mut result: f32 = 0.;
+    tops.iter().for_each(|top| {
+        bottoms.iter().for_each(|bottom| {
+            let ol = rectangle_overlap_y(*top, *bottom);
+            result = result.max(ol);
+        });
+    });
+    result
+}
+
 pub fn widest_of_rectangles(rects: &[Rectangle]) -> f32 {
     let mut result: f32 = 0.;
     rects.iter().for_each(|rect| {
@@ -4,4 +4,90 @@ use crate::color::Color;
 pub enum Stroke {
     None,
     Solid(f32, Color),
+    Styled(f32, Color, StrokeStyle),
+}
+
+impl Stroke {
+    pub fn dashed(width: f32, color: Color, dash: f32, gap: f32) -> Stroke {
+        Stroke::Styled(width, color, StrokeStyle::dashed(dash, gap))
+    }
+
+    pub fn dotted(width: f32, color: Color, gap: f32) -> Stroke {
+        Stroke::Styled(width, color, StrokeStyle::dotted(gap))
+    }
+
+    /// Width, colour and style of a visible stroke, a solid stroke has the default style
+    pub fn get_style(&self) -> Option<(f32, Color, StrokeStyle)> {
+        match self {
+            Stroke::None => None,
+            Stroke::Solid(width, color) => Some((*width, *color, StrokeStyle::default())),
+            Stroke::Styled(width, color, style) => Some((*width, *color, style.clone())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrokeStyle {
+    // alternating dash and gap lengths, empty for a continuous line
+    pub dash: Vec<f32>,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    pub opacity: f32,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        StrokeStyle {
+            dash: Vec::new(),
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            opacity: 1.0,
+        }
+    }
+}
+
+impl StrokeStyle {
+    pub fn dashed(dash: f32, gap: f32) -> StrokeStyle {
+        StrokeStyle {
+            dash: vec![dash, gap],
+            ..StrokeStyle::default()
+        }
+    }
+
+    /// Zero length dashes with round caps, giving dots as wide as the line
+    pub fn dotted(gap: f32) -> StrokeStyle {
+        StrokeStyle {
+            dash: vec![0.0, gap],
+            cap: LineCap::Round,
+            ..StrokeStyle::default()
+        }
+    }
+
+    pub fn with_cap(self, cap: LineCap) -> StrokeStyle {
+        StrokeStyle { cap, ..self }
+    }
+
+    pub fn with_join(self, join: LineJoin) -> StrokeStyle {
+        StrokeStyle { join, ..self }
+    }
+
+    pub fn with_opacity(self, opacity: f32) -> StrokeStyle {
+        StrokeStyle { opacity, ..self }
+    }
 }
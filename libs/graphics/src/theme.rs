@@ -0,0 +1,43 @@
+use crate::color::Color;
+
+/// Colours of a rendered score. Without a background the drawing is transparent
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub foreground: Color,
+    pub background: Option<Color>,
+    pub staff_lines: Color,
+    // for notes marked to stand out
+    pub highlight: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::light()
+    }
+}
+
+impl Theme {
+    /// Black on white
+    pub fn light() -> Theme {
+        Theme {
+            foreground: Color::Black,
+            background: Some(Color::White),
+            staff_lines: Color::Black,
+            highlight: Color::DodgerBlue,
+        }
+    }
+
+    pub fn dark() -> Theme {
+        Theme {
+            foreground: Color::RGBA(230, 230, 230, 1.0),
+            background: Some(Color::RGBA(30, 30, 30, 1.0)),
+            staff_lines: Color::RGBA(160, 160, 160, 1.0),
+            highlight: Color::Orange,
+        }
+    }
+
+    /// The theme without a background, for placing the drawing on a page of its own colour
+    pub fn transparent(self) -> Theme {
+        Theme { background: None, ..self }
+    }
+}
@@ -0,0 +1,99 @@
+/// Affine transform mapping (x, y) to (a * x + c * y + e, b * x + d * y + f), the matrix order of SVG and PDF
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        e: 0.0,
+        f: 0.0,
+    };
+
+    pub fn translate(x: f32, y: f32) -> Transform {
+        Transform { e: x, f: y, ..Transform::IDENTITY }
+    }
+
+    pub fn scale(scale_x: f32, scale_y: f32) -> Transform {
+        Transform {
+            a: scale_x,
+            d: scale_y,
+            ..Transform::IDENTITY
+        }
+    }
+
+    /// Clockwise rotation around the origin, as y points down
+    pub fn rotate(degrees: f32) -> Transform {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Transform {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            ..Transform::IDENTITY
+        }
+    }
+
+    /// Skew along x and y by angles in degrees, like svg `skewX` and `skewY`
+    pub fn skew(x_degrees: f32, y_degrees: f32) -> Transform {
+        Transform {
+            b: y_degrees.to_radians().tan(),
+            c: x_degrees.to_radians().tan(),
+            ..Transform::IDENTITY
+        }
+    }
+
+    /// This transform followed by `next`
+    pub fn then(&self, next: &Transform) -> Transform {
+        Transform {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            e: next.a * self.e + next.c * self.f + next.e,
+            f: next.b * self.e + next.d * self.f + next.f,
+        }
+    }
+
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (self.a * x + self.c * y + self.e, self.b * x + self.d * y + self.f)
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Transform::IDENTITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_then() {
+        // scaled around the origin, then moved
+        let transform = Transform::scale(2.0, 3.0).then(&Transform::translate(10.0, 20.0));
+        assert_eq!(transform.apply(1.0, 1.0), (12.0, 23.0));
+        assert_eq!(Transform::translate(10.0, 20.0).then(&Transform::scale(2.0, 3.0)).apply(1.0, 1.0), (22.0, 63.0));
+        assert!(Transform::IDENTITY.then(&Transform::default()).is_identity());
+
+        let (x, y) = Transform::rotate(90.0).apply(1.0, 0.0);
+        assert!(x.abs() < 1e-6 && (y - 1.0).abs() < 1e-6);
+        let (x, y) = Transform::skew(45.0, 0.0).apply(0.0, 2.0);
+        assert!((x - 2.0).abs() < 1e-6 && y == 2.0);
+    }
+}
@@ -2,7 +2,7 @@ use std::cell::RefCell;
 use std::fmt::Debug;
 
 use crate::griditem::{GridColumn, GridItem, GridItemType, GridRow};
-use graphics::rectangle::{Rectangle, rectangles_overlap_x};
+use graphics::rectangle::{Rectangle, rectangles_overlap_x, rectangles_overlap_y};
 use utils::f32_ext::round::F32ExtRound2;
 
 #[derive(Debug)]
@@ -42,8 +42,8 @@ where
         let mut colrowids: Vec<Vec<usize>> = Vec::new();
         let rowcount = items.len();
         let colcount = items[0].len();
-        for rowidx in 0..rowcount {
-            let ccount = items[rowidx].len();
+        for (rowidx, rowitems) in items.iter().enumerate() {
+            let ccount = rowitems.len();
             if ccount != colcount {
                 panic!("Row {} has different column count than the first row", rowidx);
             }
@@ -69,8 +69,8 @@ where
 
         for colidx in 0..colcount {
             let mut colids = Vec::<usize>::new();
-            for rowidx in 0..rowcount {
-                colids.push(colrowids[rowidx][colidx]);
+            for rowids in colrowids.iter() {
+                colids.push(rowids[colidx]);
             }
 
             let gridcol = GridColumn { colidx, item_ids: colids.to_vec() };
@@ -92,7 +92,7 @@ where
 
     //----------------------------------------------------------
 
-    pub fn handle_column_spacing(&self, allotments: &Vec<f32>, spacing_factor: f32) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn handle_column_spacing(&self, allotments: &[f32], spacing_factor: f32) -> Result<(), Box<dyn std::error::Error>> {
         let minimal_spacing = self.calculate_minimal_col_spacing()?;
         let minimal_width = minimal_spacing.iter().sum::<f32>();
         // dbg!(&minimal_width);
@@ -108,8 +108,8 @@ where
         Ok(())
     }
 
-    fn calculate_alloted_col_spacing(&self, allotments: &Vec<f32>, factor: f32) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
-        let alloted_overlaps = calculate_overlaps_with_factor(&allotments, factor);
+    fn calculate_alloted_col_spacing(&self, allotments: &[f32], factor: f32) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+        let alloted_overlaps = calculate_overlaps_with_factor(allotments, factor);
         let alloted_width = alloted_overlaps.iter().sum::<f32>();
         Ok(alloted_overlaps)
     }
@@ -126,10 +126,7 @@ where
         let mut spacing: Vec<f32> = Vec::new();
         //-----------------------------------------------
         // Create cols_overlaps - should be deleted later?
-        for _ in 0..colcount {
-            spacing.push(0.0);
-        }
-        spacing.push(0.0);
+        spacing.extend(std::iter::repeat_n(0.0, colcount + 1));
 
         //-----------------------------------------------
         let mut prev_rect_data: Vec<PrevRectData> = vec![None; self.rows.borrow().len()];
@@ -165,7 +162,7 @@ where
                             let overlap_x = rectangles_overlap_x(prev_rects, right_rects);
                             let factor = (overlap_x - sum_cols_overlaps).max(0.0);
                             if factor > 0.0 {
-                                spacing[right_colidx] = spacing[right_colidx] + factor;
+                                spacing[right_colidx] += factor;
                             }
                         } else {
                             // println!("- No previous rectangles to compare with");
@@ -220,21 +217,38 @@ where
         Ok(final_spacing)
     }
 
-    pub fn handle_row_heights(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let rows = self.rows.borrow().len();
+    /// Distances between the rows, from the vertical extents of the rectangles of neighbouring rows at the same x.
+    /// `min_distances[i]` is the smallest distance between row i and row i + 1, call after the column spacing is set.
+    pub fn handle_row_heights(&self, min_distances: &[f32], padding: f32) -> Result<(), Box<dyn std::error::Error>> {
+        let self_items = self.items.borrow();
+        let self_rows = self.rows.borrow();
+        let cols_widths = self.cols_widths.borrow();
         let mut heights = self.rows_heights.borrow_mut();
 
-        //------------------------------------------------
-        // set default row heights
+        // rectangles of each row, moved to their column x
+        let rows_rects = self_rows
+            .iter()
+            .map(|row| {
+                let mut move_x = 0.0;
+                let mut rects: Vec<Rectangle> = Vec::new();
+                for (colidx, item_id) in row.item_ids.iter().enumerate() {
+                    move_x += cols_widths.get(colidx).cloned().unwrap_or(0.0);
+                    if let GridItemType::Rectangles(items) = &self_items[*item_id].gitype {
+                        rects.extend(items.iter().map(|(r, _)| (r.0 + move_x, r.1, r.2, r.3)));
+                    }
+                }
+                rects
+            })
+            .collect::<Vec<_>>();
+
+        heights.clear();
         heights.push(0.0);
-        if rows > 0 {
-            for r_idx in 1..rows {
-                heights.push(80.0);
-            }
+        for rects in rows_rects.windows(2) {
+            let min_distance = min_distances.get(heights.len() - 1).or(min_distances.last()).cloned().unwrap_or(0.0);
+            let overlap = rectangles_overlap_y(&rects[0], &rects[1]);
+            heights.push(min_distance.max(overlap + padding).r2());
         }
 
-        // dbg!(&heights);
-
         Ok(())
     }
 }
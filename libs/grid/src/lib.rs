@@ -16,6 +16,7 @@ mod tests2 {
     use crate::{gridcontext::GridContext, griditem::GridItemType, TestEnum};
 
     #[test]
+    #[allow(unused_must_use)]
     fn example2() {
         let cx: &'static GridContext<TestEnum> = GridContext::<TestEnum>::new();
 
@@ -39,4 +40,23 @@ mod tests2 {
         cx.calculate_minimal_col_spacing();
         dbg!(cx.cols_widths.borrow());
     }
+
+    #[test]
+    fn row_heights() {
+        let cx: &'static GridContext<TestEnum> = GridContext::<TestEnum>::new();
+        // a low note in the first row above a high note in the second row, in different columns
+        let items = vec![
+            vec![GridItemType::Rectangles(vec![((0.0, 60.0, 10.0, 10.0), TestEnum::Blue)]), GridItemType::Empty],
+            vec![GridItemType::Empty, GridItemType::Rectangles(vec![((0.0, -50.0, 10.0, 10.0), TestEnum::Red)])],
+        ];
+        cx.add_items(items).unwrap();
+
+        cx.set_cols_widths(vec![0.0, 20.0, 10.0]).unwrap();
+        cx.handle_row_heights(&[80.0], 10.0).unwrap();
+        assert_eq!(*cx.rows_heights.borrow(), vec![0.0, 80.0]);
+
+        cx.set_cols_widths(vec![0.0, 5.0, 10.0]).unwrap();
+        cx.handle_row_heights(&[80.0], 10.0).unwrap();
+        assert_eq!(*cx.rows_heights.borrow(), vec![0.0, 130.0]);
+    }
 }
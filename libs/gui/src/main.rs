@@ -1,9 +1,9 @@
-use std::{fmt::Error, time::Duration};
+use std::time::Duration;
 
 use iced::{
     Element, Task,
     alignment::Horizontal::Left,
-    widget::{Svg, Text, TextInput, center, column, svg, text, text_input},
+    widget::{Svg, Text, TextInput, column, svg, text, text_input},
 };
 use render::output::Generate;
 
@@ -34,7 +34,9 @@ impl App {
                 self.input_text = value;
 
                 // If abort_handler is set, abort it
-                self.abort_handler.as_ref().map(|abort_handler| abort_handler.abort());
+                if let Some(abort_handler) = self.abort_handler.as_ref() {
+                    abort_handler.abort()
+                }
 
                 // Create a handler for the async task
                 let handler = Task::perform(tokio::time::sleep(Duration::from_millis(1000)), |_| AppMessage::SetDebouncedValue);
@@ -68,7 +70,7 @@ impl App {
     fn view(&self) -> Element<'_, AppMessage> {
         let error: Text = text(self.error_message.as_str()).size(20).color(iced::Color::from_rgb(1.0, 0.0, 0.0));
         let input: TextInput<'_, AppMessage> = text_input("", self.input_text.as_str()).on_input(AppMessage::InputChanged).padding(15).size(30).align_x(Left);
-        let text: Text = text(self.debounced_text.as_str()).size(30);
+        let _text: Text = text(self.debounced_text.as_str()).size(30);
 
         let svg: Svg = svg(svg::Handle::from_memory(self.svg_string.clone().into_bytes()));
         column![error, input, /*text,*/ svg].into()
@@ -2,4 +2,6 @@ pub mod parse;
 pub mod parse2;
 pub mod parse2utils;
 pub mod resolve_ties;
+pub mod transpose;
 pub mod utils;
+pub mod write2;
@@ -5,7 +5,7 @@ use core::complex::{ComplexInfo, ComplexUtils};
 use core::context::CoreContext;
 use core::duration::{NoteDuration, SumDuration};
 use core::head::HeadItem;
-use core::note::{NoteItem, NoteType};
+use core::note::{NoteColor, NoteItem, NoteType};
 use core::part::{PartId, PartItem, PartType};
 
 use core::stems::stemdirections::calculate_stemitem_directions;
@@ -44,11 +44,11 @@ pub fn parse_head(_cx: &CoreContext, value: &str, _note_id: usize) -> Result<Hea
     // store ties
     let _tie_to: Option<TieTo> = TieTo::find(value, level);
     let _tie_from: Option<TieFrom> = TieFrom::find(value, level);
-    if _tie_to.is_some() {
-        _cx.map_noteid_tiesto.borrow_mut().entry(_note_id).or_default().push(_tie_to.unwrap());
+    if let Some(tie_to) = _tie_to {
+        _cx.map_noteid_tiesto.borrow_mut().entry(_note_id).or_default().push(tie_to);
     }
-    if _tie_from.is_some() {
-        _cx.map_noteid_tiesfrom.borrow_mut().entry(_note_id).or_default().push(_tie_from.unwrap());
+    if let Some(tie_from) = _tie_from {
+        _cx.map_noteid_tiesfrom.borrow_mut().entry(_note_id).or_default().push(tie_from);
     }
 
     //------------------------------------------
@@ -58,11 +58,7 @@ pub fn parse_head(_cx: &CoreContext, value: &str, _note_id: usize) -> Result<Hea
     //------------------------------------------
     // create head item
     let id = _cx.heads.borrow().len();
-    let info: HeadItem = HeadItem {
-        id,
-        level: level,
-        accidental: accidental,
-    };
+    let info: HeadItem = HeadItem { id, level, accidental };
     _cx.heads.borrow_mut().push(info.clone());
     Ok(info)
 }
@@ -88,6 +84,12 @@ pub fn parse_notetype(_cx: &CoreContext, value: &str, note_id: usize) -> Result<
     let ntype = match value {
         "r" => NoteType::Rest,
         "s" => NoteType::Space,
+        // rest at an explicit level, like "r-4"
+        _ if value.starts_with('r') => {
+            let level = value[1..].parse::<i8>().map_err(|_| format!("Invalid rest level in '{}'", value))?;
+            _cx.map_noteid_restlevel.borrow_mut().insert(note_id, level);
+            NoteType::Rest
+        }
         _ => {
             let head_infos = parse_heads(_cx, value, note_id)?;
             NoteType::Heads(head_infos)
@@ -111,16 +113,47 @@ pub fn parse_notes(cx: &CoreContext, value: &str) -> Result<(Vec<usize>, SumDura
     let mut duration: NoteDuration = NoteDuration::D4;
     let mut ids: Vec<usize> = Vec::new();
 
+    // clef change before the next note, like "clef:F"
+    let mut clef: Option<ClefSignature> = None;
+    // following notes drawn on the staff below or above, like "staff:+1", until "staff:0"
+    let mut staff_offset: i8 = 0;
+    // colour of the next note, like "color:red" or "color:highlight"
+    let mut color: Option<NoteColor> = None;
+
     let values = value.split(" ").filter(|s| !s.is_empty());
     for v in values {
         if v.starts_with("D") || v.starts_with("d") {
             duration = NoteDuration::parse(v)?
+        } else if let Some(clef_value) = v.strip_prefix("clef:") {
+            clef = Some(ClefSignature::find(clef_value));
+        } else if let Some(color_value) = v.strip_prefix("color:") {
+            color = Some(NoteColor::find(color_value).ok_or_else(|| format!("Invalid note color in '{}'", v))?);
+        } else if let Some(staff_value) = v.strip_prefix("staff:") {
+            staff_offset = staff_value.parse::<i8>().map_err(|_| format!("Invalid staff offset in '{}'", v))?;
+            if !(-1..=1).contains(&staff_offset) {
+                return Err(format!("Notes can only move to a neighbouring staff, not '{}'", v).into());
+            }
         } else {
-            let id = parse_note(cx, v, sum_duration, duration.clone())?;
-            sum_duration += duration.clone() as usize;
+            let id = parse_note(cx, v, sum_duration, duration)?;
+            if let Some(clef) = clef.take() {
+                cx.map_noteid_clef.borrow_mut().insert(id, clef);
+            }
+            if let Some(color) = color.take() {
+                cx.map_noteid_color.borrow_mut().insert(id, color);
+            }
+            if staff_offset != 0 {
+                cx.map_noteid_staffoffset.borrow_mut().insert(id, staff_offset);
+            }
+            sum_duration += duration as usize;
             ids.push(id);
         }
     }
+    if clef.is_some() {
+        return Err(format!("Clef change without a following note in '{}'", value).into());
+    }
+    if color.is_some() {
+        return Err(format!("Note color without a following note in '{}'", value).into());
+    }
 
     Ok((ids, sum_duration))
 }
@@ -160,10 +193,7 @@ pub fn parse_parttype(cx: &CoreContext, value: &str) -> Result<PartType, Box<dyn
         match nr_of_voices {
             1 => {
                 let voiceitem = parse_voice(cx, value)?;
-                match voiceitem.vtype {
-                    // VoiceType::Barpause => Ok(PartType::Barpause),
-                    _ => PartType::OneVoice(voiceitem),
-                }
+                PartType::OneVoice(voiceitem)
                 // PartType::OneVoice(voiceitem)
             }
             2 => {
@@ -237,15 +267,15 @@ pub fn parse_sysitemtype(_cx: &CoreContext, value: &str) -> Result<(SysItemType,
     let mut max_duration: usize = 0;
 
     let (t, parts_count) = if value.starts_with("clef") {
-        let segments = value.split(" ").filter(|s| !s.is_empty()).skip(1).map(|s| ClefSignature::find(s)).collect::<Vec<_>>();
+        let segments = value.split(" ").filter(|s| !s.is_empty()).skip(1).map(ClefSignature::find).collect::<Vec<_>>();
         let parts_count = segments.len();
         (SysItemType::Clefs(segments), parts_count)
     } else if value.starts_with("bl") {
         if value.starts_with("bld") {
             dbg!(11111);
-            (SysItemType::Barline(BarlineType::Double), 1 as usize)
+            (SysItemType::Barline(BarlineType::Double), 1_usize)
         } else {
-            (SysItemType::Barline(BarlineType::Single), 1 as usize)
+            (SysItemType::Barline(BarlineType::Single), 1_usize)
         }
     } else {
         let parts_ids = parse_parts(_cx, value)?;
@@ -338,7 +368,7 @@ pub fn parse_sysitemlist(cx: &CoreContext, value: &str) -> Result<SysItemList, B
         })
         .collect::<Vec<_>>();
     let partsnotesvecs = create_part_notes_vecs(cx, max_parts_count)?;
-    let _ = handle_ties(cx, &partsnotesvecs)?;
+    handle_ties(cx, &partsnotesvecs)?;
     dbg!(cx.map_noteid_resolvedtiesfrom.borrow());
     dbg!(cx.map_noteid_resolvedtiesto.borrow());
 
@@ -361,11 +391,12 @@ pub fn level_from_str(s: &str) -> Result<i8, Box<dyn Error>> {
 mod tests {
 
     use super::*;
+    use core::direction::DirectionUD;
 
     #[test]
     fn test_n() {
         let cx = CoreContext::new();
-        let _ = parse_note(&cx, "b1,-3", 0, NoteDuration::D8).unwrap();
+        let _ = parse_note(cx, "b1,-3", 0, NoteDuration::D8).unwrap();
         dbg!(&cx);
     }
 
@@ -377,6 +408,29 @@ mod tests {
         dbg!(&cx);
     }
 
+    #[test]
+    fn test_cross_staff() {
+        let cx = CoreContext::new();
+        let ids = parse_notes(cx, "d16 2 0 staff:+1 -3 -5").unwrap();
+        let map_noteid_staffoffset = cx.map_noteid_staffoffset.borrow().clone();
+        assert_eq!(map_noteid_staffoffset, BTreeMap::from([(ids.0[2], 1), (ids.0[3], 1)]));
+        assert!(parse_notes(cx, "staff:+2 0").is_err());
+
+        let cx = CoreContext::new();
+        let _ = parse_part(cx, "d16 2 0 staff:+1 -3 -5", 0).unwrap();
+        let stemitems = cx.stemitems.borrow();
+        assert!(StemItemUtils::is_cross_staff(cx, &stemitems[0]));
+        // lowest head above at 2, highest below at -5
+        assert_eq!(StemItemUtils::get_cross_staff_min_distance(cx, &stemitems[0]), 7.0 + 11.0);
+
+        // the beam halfway between the heads, levels of the lower notes counted on their own staff
+        let beam_level = StemItemUtils::calculate_cross_staff_stem_lengths(cx, &stemitems[0], 18.0).unwrap();
+        assert_eq!(beam_level, 7.5);
+        let stemitemlevels = cx.map_noteid_stemitemlevels.borrow();
+        assert_eq!(stemitemlevels[&0], (DirectionUD::Down, 2.0, 7.5));
+        assert_eq!(stemitemlevels[&3], (DirectionUD::Up, -10.5, -5.0));
+    }
+
     #[test]
     fn test_v() {
         let cx = CoreContext::new();
@@ -13,18 +13,10 @@ pub struct Parse2;
 
 impl Parse2 {
     pub fn sysitemlist2(cx: &CoreContext, value: &str, split_parts: bool) -> Result<(), Box<dyn Error>> {
-        let mut value = value.trim();
-        if value.starts_with("|") {
-            value = value[1..].trim();
-        }
-        if value.ends_with("|") {
-            value = &value[..value.len() - 1];
-        }
-
-        let items_str = value.split("|").collect::<Vec<_>>();
+        let items_str = Parse2Utils::split_sysitems(value);
         // let mut max_parts_count = 0;
         let mut bpvmap: Vec<SysItemTypeId> = Vec::new();
-        for (_item_idx, item_str) in items_str.iter().filter(|s| !s.trim().is_empty()).enumerate() {
+        for item_str in items_str.iter().filter(|s| !s.trim().is_empty()) {
             Parse2::sysitemtype2(cx, item_str, &mut bpvmap)?;
         }
         //------------------------------------------------
@@ -43,6 +35,7 @@ impl Parse2 {
         Parse2Utils::map_notes_by_voices(cx)?;
         Parse2Utils::resolve_ties_from(cx)?;
         Parse2Utils::resolve_ties_to(cx)?;
+        Parse2Utils::calculate_accidentals(cx)?;
 
         Ok(())
     }
@@ -53,13 +46,34 @@ impl Parse2 {
             value = value[1..].trim();
         }
         //-----------------------------------
-        if value.starts_with("clef") {
+        if value.starts_with("clef") && !value.starts_with("clef:") {
             // Clefs
             let clef_segments = value.split(" ").skip(1).filter(|s| !s.is_empty()).map(|s| s.to_string()).collect::<Vec<_>>();
             bpvmap.push(SysItemTypeId::Clefs(clef_segments));
+        } else if value.starts_with("group") {
+            // Staff groups like "group bracket 1-4 brace 5-6"
+            let group_segments = value.split(" ").skip(1).filter(|s| !s.is_empty()).map(|s| s.to_string()).collect::<Vec<_>>();
+            bpvmap.push(SysItemTypeId::Groups(group_segments));
+        } else if value.starts_with("part ") {
+            // Part header like "part 1 name=\"Clarinet in Bb\" short=\"Cl.\" transp=M2 program=71"
+            let header_segments = Parse2Utils::split_quoted(value).into_iter().skip(1).collect::<Vec<_>>();
+            bpvmap.push(SysItemTypeId::PartHeader(header_segments));
+        } else if value.starts_with("key") {
+            // Key signatures, one for all parts or one per part
+            let key_segments = value.split(" ").skip(1).filter(|s| !s.is_empty()).map(|s| s.to_string()).collect::<Vec<_>>();
+            bpvmap.push(SysItemTypeId::Keys(key_segments));
+        } else if value.starts_with("volta") {
+            // Ending bracket from here on, no numbers closes the open one
+            let volta_segments = value.split(" ").skip(1).filter(|s| !s.is_empty()).map(|s| s.to_string()).collect::<Vec<_>>();
+            bpvmap.push(SysItemTypeId::Volta(volta_segments));
+        } else if value.starts_with("nav") {
+            // Navigation marks like segno, coda, tocoda, fine, dc, ds-coda
+            let navigation_segments = value.split(" ").skip(1).filter(|s| !s.is_empty()).map(|s| s.to_string()).collect::<Vec<_>>();
+            bpvmap.push(SysItemTypeId::Navigation(navigation_segments));
         } else if value.starts_with("bl") {
-            // Barlines
-            bpvmap.push(SysItemTypeId::Barlines(vec!["Single".to_string()]));
+            // Barlines, one for all parts or one per part
+            let barline_segments = value.split(" ").filter(|s| !s.is_empty()).map(|s| s.to_string()).collect::<Vec<_>>();
+            bpvmap.push(SysItemTypeId::Barlines(barline_segments));
         } else {
             // Parts, voices
             if value.starts_with("/") {
@@ -67,10 +81,10 @@ impl Parse2 {
             }
             let mut pa: Vec<Vec<String>> = vec![];
             let part_segments = value.split("/").filter(|s| !s.is_empty()).collect::<Vec<_>>();
-            for (_part_idx, part_segment) in part_segments.iter().enumerate() {
+            for part_segment in part_segments.iter() {
                 let voice_segments = part_segment.split("%").filter(|s| !s.is_empty()).collect::<Vec<_>>();
                 let mut va: Vec<String> = vec![];
-                for (_voice_idx, voice_segment) in voice_segments.iter().enumerate() {
+                for voice_segment in voice_segments.iter() {
                     let mut v = voice_segment.trim().to_string();
                     if v.is_empty() {
                         v = "Voice-added1".to_string(); // Default voice
@@ -87,9 +101,7 @@ impl Parse2 {
 
     pub fn voicetype(cx: &CoreContext, value: &str, default_duration: SumDuration) -> Result<VoiceType2, Box<dyn Error>> {
         let value = value.trim();
-        let vtype = if value.starts_with("bp") {
-            VoiceType2::Barpause(default_duration)
-        } else if value.starts_with("Voice") {
+        let vtype = if value.starts_with("bp") || value.starts_with("Voice") {
             VoiceType2::Barpause(default_duration)
         } else {
             let (note_ids, duration) = parse_notes(cx, value)?;
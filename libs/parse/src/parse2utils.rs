@@ -1,20 +1,41 @@
 use core::{
+    accidental::{Accidental, AccidentalMode, AccidentalState},
     barline::BarlineType,
     clef::ClefSignature,
-    complex::ComplexUtils,
+    complex::{ComplexType, ComplexUtils},
     context::CoreContext,
     direction::DirectionUD,
+    group::StaffGroup,
+    head::HeadItem,
     hpart::{HPartAttributes, HPartItem, HPartItemsColumn, HPartItemsColumnType, HPartItemsRow, HPartMusicType, HPartType, VoiceType2},
     key::KeySignature,
+    note::{NoteItem, NoteType},
+    part::PartHeader,
+    repeat::{NavigationMark, Volta},
     stems::{headpositions::HeadPositionUtils, stemdirections::StemDirectionUtils, stemitems::StemItemUtils},
     sysitem::SysItemTypeId,
     ties::{CheckedTieFrom, CheckedTieTo, TieFrom},
     time::{TimeDenominator, TimeNominator, TimeSignature},
 };
-use std::{collections::HashMap, error::Error};
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+};
 
 use crate::parse2::Parse2;
 
+pub enum HeadWalkItem<'a> {
+    Barline,
+    Head {
+        hpart: &'a HPartItem,
+        attr: &'a HPartAttributes,
+        note: &'a NoteItem,
+        head: &'a HeadItem,
+        /// Clef in effect for the head, `attr.clef` unless changed earlier in the bar
+        clef: &'a ClefSignature,
+    },
+}
+
 pub struct Parse2Utils;
 
 #[allow(unused_mut)]
@@ -24,15 +45,59 @@ impl Parse2Utils {
         let mut time_map: HashMap<usize, TimeSignature> = HashMap::new();
         let mut key_map: HashMap<usize, KeySignature> = HashMap::new();
 
+        let mut open_volta: Option<Volta> = None;
+
         let mut column_position = 0;
-        for (col_idx, item) in bpvmap.iter().enumerate() {
+        for item in bpvmap.iter() {
             let mut column_duration: usize = 0;
+            let col_idx = _cx.columns.borrow().len();
 
             match item {
+                SysItemTypeId::Volta(segments) => {
+                    // Voltas don't create a column, they span the columns until the next volta
+                    if let Some(volta) = open_volta.take() {
+                        Parse2Utils::close_volta(_cx, volta, col_idx);
+                    }
+                    let numbers = Volta::find_numbers(segments);
+                    if !numbers.is_empty() {
+                        open_volta = Some(Volta {
+                            numbers,
+                            from_col_idx: col_idx,
+                            to_col_idx: col_idx,
+                            closed: false,
+                        });
+                    }
+                }
+
+                SysItemTypeId::Groups(segments) => {
+                    _cx.staff_groups.borrow_mut().extend(StaffGroup::find_groups(segments, parts_count)?);
+                }
+
+                SysItemTypeId::PartHeader(segments) => {
+                    let (part_idx, header) = PartHeader::find(segments)?;
+                    _cx.map_partidx_header.borrow_mut().insert(part_idx, header);
+                }
+
+                SysItemTypeId::Navigation(segments) => {
+                    for segment in segments.iter() {
+                        let mark = NavigationMark::find(segment).ok_or(format!("Unknown navigation mark '{}'", segment))?;
+                        _cx.map_colidx_navigations.borrow_mut().entry(col_idx).or_default().push(mark);
+                    }
+                }
+
+                SysItemTypeId::Keys(segments) => {
+                    // Keys don't create a column of their own, they apply to the following music
+                    for part_idx in 0..parts_count {
+                        let segment = if segments.len() == 1 { segments.first() } else { segments.get(part_idx) };
+                        if let Some(segment) = segment {
+                            key_map.insert(part_idx, KeySignature::find(segment));
+                        }
+                    }
+                }
+
                 SysItemTypeId::Clefs(segments) => {
                     let mut items_ids: Vec<usize> = vec![];
-                    for part_idx in 0..parts_count {
-                        let item = &segments[part_idx];
+                    for (part_idx, item) in segments.iter().enumerate().take(parts_count) {
                         let clef_signature = ClefSignature::find(item);
                         clef_map.insert(part_idx, clef_signature.clone());
                         let hpart: HPartType = HPartType::Clef(clef_signature);
@@ -59,11 +124,10 @@ impl Parse2Utils {
                     _cx.columns.borrow_mut().push(column);
                 }
 
-                SysItemTypeId::Barlines(_segments) => {
+                SysItemTypeId::Barlines(segments) => {
                     let mut items_ids: Vec<usize> = vec![];
-                    for part_idx in 0..parts_count {
-                        // let item = &segments[part_idx];
-                        let barline = BarlineType::Single;
+                    for (part_idx, segment) in segments.iter().enumerate().take(parts_count) {
+                        let barline = BarlineType::find(segment);
                         let hpart: HPartType = HPartType::Barline(barline);
                         let id = _cx.hparts.borrow().len();
                         let item: HPartItem = HPartItem {
@@ -92,9 +156,7 @@ impl Parse2Utils {
                     let mut column_duration: usize = 0;
                     let mut item_types: Vec<HPartType> = vec![];
 
-                    for part_idx in 0..parts_count {
-                        let item = &parts[part_idx];
-
+                    for (part_idx, item) in parts.iter().enumerate().take(parts_count) {
                         let attr: HPartAttributes = HPartAttributes {
                             clef: clef_map.get(&part_idx).cloned().unwrap_or(ClefSignature::Treble),
                             time: time_map.get(&part_idx).cloned().unwrap_or(TimeSignature::TimeSignature(TimeNominator::Four, TimeDenominator::Four)),
@@ -109,9 +171,12 @@ impl Parse2Utils {
                                 let complexes = match &voicetype {
                                     VoiceType2::NoteIds { note_ids, duration, stemitem_ids: _ } => {
                                         column_duration = column_duration.max(*duration);
-                                        ComplexUtils::create_complexes_for_one_voice(_cx, &note_ids, *duration, true, part_idx, column_position)
+                                        ComplexUtils::create_complexes_for_one_voice(_cx, note_ids, *duration, true, part_idx, column_position)
+                                    }
+                                    VoiceType2::Barpause(duration) => {
+                                        column_duration = column_duration.max(*duration);
+                                        Vec::new()
                                     }
-                                    _ => Vec::new(),
                                 };
 
                                 HPartType::Music {
@@ -137,18 +202,21 @@ impl Parse2Utils {
                                         },
                                     ) => {
                                         column_duration = column_duration.max(*upper_dur).max(*lower_dur);
-                                        ComplexUtils::create_complexes_for_two_voices(_cx, &upper_ids, &lower_ids, *upper_dur, *lower_dur, column_position)
+                                        ComplexUtils::create_complexes_for_two_voices(_cx, upper_ids, lower_ids, *upper_dur, *lower_dur, column_position)
                                     }
 
                                     (_, VoiceType2::NoteIds { note_ids, duration, stemitem_ids: _ }) => {
                                         column_duration = column_duration.max(*duration);
-                                        ComplexUtils::create_complexes_for_one_voice(_cx, &note_ids, *duration, false, part_idx, column_position)
+                                        ComplexUtils::create_complexes_for_one_voice(_cx, note_ids, *duration, false, part_idx, column_position)
                                     }
                                     (VoiceType2::NoteIds { note_ids, duration, stemitem_ids: _ }, _) => {
                                         column_duration = column_duration.max(*duration);
-                                        ComplexUtils::create_complexes_for_one_voice(_cx, &note_ids, *duration, true, part_idx, column_position)
+                                        ComplexUtils::create_complexes_for_one_voice(_cx, note_ids, *duration, true, part_idx, column_position)
+                                    }
+                                    (VoiceType2::Barpause(duration), VoiceType2::Barpause(_)) => {
+                                        column_duration = column_duration.max(*duration);
+                                        Vec::new()
                                     }
-                                    _ => Vec::new(),
                                 };
                                 HPartType::Music {
                                     mtype: HPartMusicType::TwoVoices { upper, lower },
@@ -161,6 +229,11 @@ impl Parse2Utils {
                             }
                         };
 
+                        // a clef change inside the bar carries over to the following bars
+                        if let Some(clef) = Parse2Utils::get_last_clef_change(_cx, &htype) {
+                            clef_map.insert(part_idx, clef);
+                        }
+
                         item_types.push(htype);
                     }
 
@@ -193,6 +266,11 @@ impl Parse2Utils {
             }
         }
 
+        if let Some(volta) = open_volta.take() {
+            let col_idx = _cx.columns.borrow().len();
+            Parse2Utils::close_volta(_cx, volta, col_idx);
+        }
+
         //------------------------------------------
 
         // for part_idx in 0..parts_count {
@@ -213,19 +291,96 @@ impl Parse2Utils {
         Ok(())
     }
 
+    /// The latest clef change among the voices of a music item
+    fn get_last_clef_change(cx: &CoreContext, htype: &HPartType) -> Option<ClefSignature> {
+        let HPartType::Music { mtype, .. } = htype else { return None };
+        let voices = match mtype {
+            HPartMusicType::OneVoice { voice } => vec![voice],
+            HPartMusicType::TwoVoices { upper, lower } => vec![upper, lower],
+        };
+        let notes = cx.notes.borrow();
+        let map_noteid_clef = cx.map_noteid_clef.borrow();
+        voices
+            .into_iter()
+            .filter_map(|voice| match voice {
+                VoiceType2::NoteIds { note_ids, .. } => Some(note_ids),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|note_id| Some((notes.get(*note_id)?.position, map_noteid_clef.get(note_id)?)))
+            .max_by_key(|(position, _)| *position)
+            .map(|(_, clef)| clef.clone())
+    }
+
+    /// A volta ends with a hook when its last column is a repeat barline
+    fn close_volta(cx: &CoreContext, mut volta: Volta, to_col_idx: usize) {
+        let columns = cx.columns.borrow();
+        let hparts = cx.hparts.borrow();
+        volta.to_col_idx = to_col_idx;
+        volta.closed = match to_col_idx.checked_sub(1).and_then(|col_idx| columns.get(col_idx)).map(|column| &column.hptype) {
+            Some(HPartItemsColumnType::Barlines(ids)) => ids
+                .first()
+                .is_some_and(|id| matches!(hparts[*id].hptype, HPartType::Barline(BarlineType::RepeatEnd | BarlineType::RepeatBoth))),
+            _ => false,
+        };
+        cx.voltas.borrow_mut().push(volta);
+    }
+
+    /// Splits at whitespace outside double quotes and removes the quotes, `name="Violin I"` gives `name=Violin I`
+    pub fn split_quoted(value: &str) -> Vec<String> {
+        let mut segments: Vec<String> = Vec::new();
+        let mut segment = String::new();
+        let mut quoted = false;
+        for c in value.chars() {
+            match c {
+                '"' => quoted = !quoted,
+                c if c.is_whitespace() && !quoted => {
+                    if !segment.is_empty() {
+                        segments.push(std::mem::take(&mut segment));
+                    }
+                }
+                c => segment.push(c),
+            }
+        }
+        if !segment.is_empty() {
+            segments.push(segment);
+        }
+        segments
+    }
+
+    /// Splits a sysitem list at `|`, except for the `|` belonging to barline tokens like `bl||`, `bl|.` or `bl:|`
+    pub fn split_sysitems(value: &str) -> Vec<String> {
+        let mut items: Vec<String> = Vec::new();
+        let mut item = String::new();
+        let mut chars = value.chars().peekable();
+        while let Some(c) = chars.next() {
+            let word = item.rsplit(char::is_whitespace).next().unwrap_or("");
+            let joined = format!("{}|", word);
+            let in_barline = word.starts_with("bl")
+                && BarlineType::ALL
+                    .iter()
+                    .map(|barline| barline.get_code())
+                    .any(|code| code == joined || (code.starts_with(joined.as_str()) && chars.peek().is_some_and(|next| code[joined.len()..].starts_with(*next))));
+            if c == '|' && !in_barline {
+                items.push(std::mem::take(&mut item));
+            } else {
+                item.push(c);
+            }
+        }
+        items.push(item);
+        items.into_iter().filter(|item| !item.trim().is_empty()).collect()
+    }
+
     pub fn split_double_voices(bpvmap: &mut [SysItemTypeId], parts_count: usize) -> usize {
         // todo!()
         let mut max_voices_count = 0;
         for part_idx in 0..parts_count {
             let mut part_voices_count = 0;
             for item in bpvmap.iter() {
-                match item {
-                    SysItemTypeId::Parts(parts) => {
-                        if let Some(part) = parts.get(part_idx) {
-                            part_voices_count = part_voices_count.max(part.len());
-                        }
-                    }
-                    _ => {}
+                if let SysItemTypeId::Parts(parts) = item
+                    && let Some(part) = parts.get(part_idx)
+                {
+                    part_voices_count = part_voices_count.max(part.len());
                 }
             }
 
@@ -233,25 +388,22 @@ impl Parse2Utils {
 
             if part_voices_count > 1 {
                 for item in bpvmap.iter_mut() {
-                    match item {
-                        SysItemTypeId::Parts(parts) => {
-                            if let Some(part) = parts.get_mut(part_idx) {
-                                if part.len() != part_voices_count {
-                                    println!("Fix voices for part {}: {:?}", part_idx, part);
-                                    parts.insert(part_idx + 1, vec!["Voice-added3".to_string()]);
-                                // Default voice
-                                } else {
-                                    println!("Part {} already has correct voices - Split them!: {:?}", part_idx, part);
-                                    let v = part.pop();
-                                    // let v = part.pop();
-                                    if let Some(voice) = v {
-                                        println!("HOHO - Split voice: {}", voice);
-                                        parts.insert(part_idx + 1, vec![voice.clone()]);
-                                    }
-                                }
+                    if let SysItemTypeId::Parts(parts) = item
+                        && let Some(part) = parts.get_mut(part_idx)
+                    {
+                        if part.len() != part_voices_count {
+                            println!("Fix voices for part {}: {:?}", part_idx, part);
+                            parts.insert(part_idx + 1, vec!["Voice-added3".to_string()]);
+                        // Default voice
+                        } else {
+                            println!("Part {} already has correct voices - Split them!: {:?}", part_idx, part);
+                            let v = part.pop();
+                            // let v = part.pop();
+                            if let Some(voice) = v {
+                                println!("HOHO - Split voice: {}", voice);
+                                parts.insert(part_idx + 1, vec![voice.clone()]);
                             }
                         }
-                        _ => {}
                     }
                 }
             }
@@ -268,6 +420,7 @@ impl Parse2Utils {
             let part_count = match item {
                 SysItemTypeId::Clefs(x) | SysItemTypeId::Barlines(x) => x.len(),
                 SysItemTypeId::Parts(x) => x.len(),
+                SysItemTypeId::Keys(_) | SysItemTypeId::Volta(_) | SysItemTypeId::Navigation(_) | SysItemTypeId::Groups(_) | SysItemTypeId::PartHeader(_) => 0,
             };
             max_parts_count = max_parts_count.max(part_count);
         }
@@ -275,13 +428,10 @@ impl Parse2Utils {
         for part_idx in 0..max_parts_count {
             let mut voices_count = 1;
             for item in bpvmap.iter() {
-                match item {
-                    SysItemTypeId::Parts(parts) => {
-                        if let Some(part) = parts.get(part_idx) {
-                            voices_count = voices_count.max(part.len());
-                        }
-                    }
-                    _ => {}
+                if let SysItemTypeId::Parts(parts) = item
+                    && let Some(part) = parts.get(part_idx)
+                {
+                    voices_count = voices_count.max(part.len());
                 }
             }
             parts_config.push(voices_count);
@@ -297,14 +447,16 @@ impl Parse2Utils {
             match item {
                 SysItemTypeId::Clefs(clef_segments) => {
                     // dbg!(&clef_segments);
-                    while *&clef_segments.len() < parts_count {
+                    while clef_segments.len() < parts_count {
                         clef_segments.push("G-added".to_string()); // Default clef
                     }
                     // dbg!(&clef_segments);
                 }
                 SysItemTypeId::Barlines(barline_segments) => {
-                    while *&barline_segments.len() < parts_count {
-                        barline_segments.push("Single-added".to_string()); // Default barline
+                    // a single barline applies to all parts
+                    let default_segment = if barline_segments.len() == 1 { barline_segments[0].clone() } else { "bl".to_string() };
+                    while barline_segments.len() < parts_count {
+                        barline_segments.push(default_segment.clone());
                     }
                 }
                 _ => {}
@@ -316,38 +468,29 @@ impl Parse2Utils {
             let mut max_voices_count = 0;
             // println!("Check part:{}", item_idx);
             for item in bpvmap.iter() {
-                match item {
-                    SysItemTypeId::Parts(parts) => {
-                        let part = &parts.get(item_idx);
-                        match part {
-                            Some(part) => {
-                                max_voices_count = max_voices_count.max(part.len());
-                            }
-                            None => {}
-                        }
+                if let SysItemTypeId::Parts(parts) = item {
+                    let part = &parts.get(item_idx);
+                    if let Some(part) = part {
+                        max_voices_count = max_voices_count.max(part.len());
                     }
-                    _ => {}
                 }
             }
             // println!("Max voices for part {}: {}", item_idx, max_voices_count);
 
             for item in bpvmap.iter_mut() {
-                match item {
-                    SysItemTypeId::Parts(parts) => {
-                        let part = &mut parts.get_mut(item_idx);
-                        // dbg!(part);
-                        match part {
-                            Some(part) => {
-                                while *&part.len() < max_voices_count {
-                                    part.push("Voice-added".to_string()); // Default voice
-                                }
-                            }
-                            None => {
-                                parts.push(vec!["Voice-added".to_string()]);
+                if let SysItemTypeId::Parts(parts) = item {
+                    let part = &mut parts.get_mut(item_idx);
+                    // dbg!(part);
+                    match part {
+                        Some(part) => {
+                            while part.len() < max_voices_count {
+                                part.push("Voice-added".to_string()); // Default voice
                             }
                         }
+                        None => {
+                            parts.push(vec!["Voice-added".to_string()]);
+                        }
                     }
-                    _ => {}
                 }
             }
         }
@@ -385,49 +528,46 @@ impl Parse2Utils {
 
                 match &item.hptype {
                     HPartType::Music {
-                        mtype: HPartMusicType::OneVoice { voice },
+                        mtype:
+                            HPartMusicType::OneVoice {
+                                voice:
+                                    VoiceType2::NoteIds {
+                                        note_ids: _,
+                                        duration: _,
+                                        stemitem_ids,
+                                    },
+                            },
                         complexes: _,
                         attr: _,
-                    } => match voice {
-                        VoiceType2::NoteIds {
-                            note_ids: _,
-                            duration: _,
-                            stemitem_ids,
-                        } => {
-                            stemitem_ids.iter().for_each(|stemitem_id| {
-                                StemDirectionUtils::set_direction_auto(cx, *stemitem_id);
-                            });
-                        }
-                        _ => {}
-                    },
+                    } => {
+                        stemitem_ids.iter().for_each(|stemitem_id| {
+                            StemDirectionUtils::set_direction_auto(cx, *stemitem_id);
+                        });
+                    }
                     HPartType::Music {
                         mtype: HPartMusicType::TwoVoices { upper, lower },
                         complexes: _,
                         attr: _,
                     } => {
-                        match upper {
-                            VoiceType2::NoteIds {
-                                note_ids: _,
-                                duration: _,
-                                stemitem_ids,
-                            } => {
-                                stemitem_ids.iter().for_each(|stemitem_id| {
-                                    StemDirectionUtils::set_direction_force(cx, *stemitem_id, DirectionUD::Up);
-                                });
-                            }
-                            _ => {}
+                        if let VoiceType2::NoteIds {
+                            note_ids: _,
+                            duration: _,
+                            stemitem_ids,
+                        } = upper
+                        {
+                            stemitem_ids.iter().for_each(|stemitem_id| {
+                                StemDirectionUtils::set_direction_force(cx, *stemitem_id, DirectionUD::Up);
+                            });
                         }
-                        match lower {
-                            VoiceType2::NoteIds {
-                                note_ids: _,
-                                duration: _,
-                                stemitem_ids,
-                            } => {
-                                stemitem_ids.iter().for_each(|stemitem_id| {
-                                    StemDirectionUtils::set_direction_force(cx, *stemitem_id, DirectionUD::Down);
-                                });
-                            }
-                            _ => {}
+                        if let VoiceType2::NoteIds {
+                            note_ids: _,
+                            duration: _,
+                            stemitem_ids,
+                        } = lower
+                        {
+                            stemitem_ids.iter().for_each(|stemitem_id| {
+                                StemDirectionUtils::set_direction_force(cx, *stemitem_id, DirectionUD::Down);
+                            });
                         }
                     }
 
@@ -452,30 +592,29 @@ impl Parse2Utils {
         let mut map_notids_per_voice = cx.map_notids_per_voice.borrow_mut();
         let mut map_stemitem_ids_per_voice = cx.map_stemitem_ids_per_voice.borrow_mut();
 
-        for (_part_idx, row) in rows.iter().enumerate() {
+        for row in rows.iter() {
             let hpart_ids = &row.hpart_ids;
             for hpart_id in hpart_ids.iter() {
                 let hpart = hparts.get(*hpart_id).unwrap();
-                match &hpart.hptype {
-                    HPartType::Music { mtype, complexes: _, attr: _ } => match mtype {
+                if let HPartType::Music { mtype, complexes: _, attr: _ } = &hpart.hptype {
+                    match mtype {
                         HPartMusicType::OneVoice { voice } => {
                             if let VoiceType2::NoteIds { note_ids, duration: _, stemitem_ids } = voice {
-                                map_notids_per_voice.entry((hpart.part_idx, 0)).or_insert_with(Vec::new).extend(note_ids.iter().cloned());
-                                map_stemitem_ids_per_voice.entry((hpart.part_idx, 0)).or_insert_with(Vec::new).extend(stemitem_ids.iter().cloned());
+                                map_notids_per_voice.entry((hpart.part_idx, 0)).or_default().extend(note_ids.iter().cloned());
+                                map_stemitem_ids_per_voice.entry((hpart.part_idx, 0)).or_default().extend(stemitem_ids.iter().cloned());
                             }
                         }
                         HPartMusicType::TwoVoices { upper, lower } => {
                             if let VoiceType2::NoteIds { note_ids, duration: _, stemitem_ids } = upper {
-                                map_notids_per_voice.entry((hpart.part_idx, 0)).or_insert_with(Vec::new).extend(note_ids.iter().cloned());
-                                map_stemitem_ids_per_voice.entry((hpart.part_idx, 0)).or_insert_with(Vec::new).extend(stemitem_ids.iter().cloned());
+                                map_notids_per_voice.entry((hpart.part_idx, 0)).or_default().extend(note_ids.iter().cloned());
+                                map_stemitem_ids_per_voice.entry((hpart.part_idx, 0)).or_default().extend(stemitem_ids.iter().cloned());
                             }
                             if let VoiceType2::NoteIds { note_ids, duration: _, stemitem_ids } = lower {
-                                map_notids_per_voice.entry((hpart.part_idx, 1)).or_insert_with(Vec::new).extend(note_ids.iter().cloned());
-                                map_stemitem_ids_per_voice.entry((hpart.part_idx, 1)).or_insert_with(Vec::new).extend(stemitem_ids.iter().cloned());
+                                map_notids_per_voice.entry((hpart.part_idx, 1)).or_default().extend(note_ids.iter().cloned());
+                                map_stemitem_ids_per_voice.entry((hpart.part_idx, 1)).or_default().extend(stemitem_ids.iter().cloned());
                             }
                         }
-                    },
-                    _ => {}
+                    }
                 }
             }
         }
@@ -535,13 +674,13 @@ impl Parse2Utils {
                                     if right_head_levels.contains(left_level) {
                                         // Found a tie resolution
                                         let tie_to = CheckedTieTo::Resolved(*left_level);
-                                        cx.map_noteid_resolvedtiesto.borrow_mut().entry(left_id).or_insert_with(Vec::new).push(tie_to);
+                                        cx.map_noteid_resolvedtiesto.borrow_mut().entry(left_id).or_default().push(tie_to);
                                         let tie_from = CheckedTieFrom::Resolved(*left_level);
-                                        cx.map_noteid_resolvedtiesfrom.borrow_mut().entry(right_id).or_insert_with(Vec::new).push(tie_from);
+                                        cx.map_noteid_resolvedtiesfrom.borrow_mut().entry(right_id).or_default().push(tie_from);
                                     } else {
                                         // Did not find a tie resolution
                                         let tie_to = CheckedTieTo::Unresolved(*left_level);
-                                        cx.map_noteid_resolvedtiesto.borrow_mut().entry(left_id).or_insert_with(Vec::new).push(tie_to);
+                                        cx.map_noteid_resolvedtiesto.borrow_mut().entry(left_id).or_default().push(tie_to);
                                     }
                                 } // _ => {
                                   //     todo!("Handle other TieFrom types");
@@ -553,19 +692,19 @@ impl Parse2Utils {
             }
 
             // Handle very last note in the voice
-            if let Some(last_id) = note_ids.last() {
-                if map_ties_from.contains_key(last_id) {
-                    // Note has ties from
-                    let left_tied_levels = map_ties_from.get(last_id).ok_or("Last note has no ties from")?;
-                    for left_tied_level in left_tied_levels.iter() {
-                        match left_tied_level {
-                            TieFrom::Level(left_level) => {
-                                let tie_to = CheckedTieTo::Unresolved(*left_level);
-                                cx.map_noteid_resolvedtiesto.borrow_mut().entry(*last_id).or_insert_with(Vec::new).push(tie_to);
-                            } // _ => {
-                              //     todo!("Handle other TieFrom types");
-                              // }
-                        }
+            if let Some(last_id) = note_ids.last()
+                && map_ties_from.contains_key(last_id)
+            {
+                // Note has ties from
+                let left_tied_levels = map_ties_from.get(last_id).ok_or("Last note has no ties from")?;
+                for left_tied_level in left_tied_levels.iter() {
+                    match left_tied_level {
+                        TieFrom::Level(left_level) => {
+                            let tie_to = CheckedTieTo::Unresolved(*left_level);
+                            cx.map_noteid_resolvedtiesto.borrow_mut().entry(*last_id).or_default().push(tie_to);
+                        } // _ => {
+                          //     todo!("Handle other TieFrom types");
+                          // }
                     }
                 }
             }
@@ -574,6 +713,108 @@ impl Parse2Utils {
         Ok(())
     }
 
+    /// Walks the heads of every part in time order, with both voices of a part together
+    pub fn walk_heads(cx: &CoreContext, mut f: impl FnMut(usize, HeadWalkItem) -> Result<(), Box<dyn Error>>) -> Result<(), Box<dyn Error>> {
+        let rows = cx.rows.borrow();
+        let hparts = cx.hparts.borrow();
+        let complexes = cx.complexes.borrow();
+        let map_noteid_clef = cx.map_noteid_clef.borrow();
+        let map_noteid_staffoffset = cx.map_noteid_staffoffset.borrow();
+
+        // notes drawn on a neighbouring staff are read in that staff's clef
+        let staff_clefs: BTreeMap<(usize, usize), &ClefSignature> = hparts
+            .iter()
+            .filter_map(|hpart| match &hpart.hptype {
+                HPartType::Music { attr, .. } => Some(((hpart.part_idx, hpart.col_idx), &attr.clef)),
+                _ => None,
+            })
+            .collect();
+
+        for row in rows.iter() {
+            for hpart_id in row.hpart_ids.iter() {
+                let hpart = hparts.get(*hpart_id).ok_or("HPart not found")?;
+                match &hpart.hptype {
+                    HPartType::Barline(_) => f(row.part_idx, HeadWalkItem::Barline)?,
+                    HPartType::Music { complexes: complex_ids, attr, .. } => {
+                        let mut clef = attr.clef.clone();
+                        for complex_id in complex_ids.iter() {
+                            let complex = complexes.get(*complex_id).ok_or("Complex not found")?;
+                            let notes = match &complex.ctype {
+                                ComplexType::Upper(note) | ComplexType::Lower(note) => vec![note],
+                                ComplexType::UpperAndLower(upper, lower, _) => vec![upper, lower],
+                            };
+                            if let Some(changed) = notes.iter().find_map(|note| map_noteid_clef.get(&note.id)) {
+                                clef = changed.clone();
+                            }
+                            for note in notes {
+                                let note_clef = match map_noteid_staffoffset.get(&note.id) {
+                                    Some(offset) => row
+                                        .part_idx
+                                        .checked_add_signed(*offset as isize)
+                                        .and_then(|part_idx| staff_clefs.get(&(part_idx, hpart.col_idx)).cloned())
+                                        .unwrap_or(&clef),
+                                    None => &clef,
+                                };
+                                if let NoteType::Heads(heads) = &note.ntype {
+                                    for head in heads.iter() {
+                                        f(
+                                            row.part_idx,
+                                            HeadWalkItem::Head {
+                                                hpart,
+                                                attr,
+                                                note,
+                                                head,
+                                                clef: note_clef,
+                                            },
+                                        )?;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    HPartType::Clef(_) => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// True if the head continues a tie from the previous note
+    pub fn is_tied_to(cx: &CoreContext, note_id: usize, level: i8) -> bool {
+        cx.map_noteid_resolvedtiesfrom
+            .borrow()
+            .get(&note_id)
+            .is_some_and(|ties| ties.iter().any(|tie| matches!(tie, CheckedTieFrom::Resolved(tied_level) if *tied_level == level)))
+    }
+
+    pub fn calculate_accidentals(cx: &CoreContext) -> Result<(), Box<dyn Error>> {
+        let mode = *cx.accidental_mode.borrow();
+        let mut states: BTreeMap<usize, AccidentalState> = BTreeMap::new();
+
+        Parse2Utils::walk_heads(cx, |part_idx, item| {
+            match item {
+                HeadWalkItem::Barline => {
+                    if let Some(state) = states.get_mut(&part_idx) {
+                        state.barline();
+                    }
+                }
+                HeadWalkItem::Head { attr, note, head, clef, .. } => {
+                    let state = states.entry(part_idx).or_insert_with(|| AccidentalState::new(attr.key));
+                    state.set_key(attr.key);
+                    let step = clef.get_middle_line_step() - head.level;
+                    let tied = Parse2Utils::is_tied_to(cx, note.id, head.level);
+                    let alter = state.get_alter(step, &head.accidental, tied, mode);
+                    // in sounding pitch the engraver decides, written accidentals are kept
+                    let explicit = mode == AccidentalMode::Written && head.accidental != Accidental::None;
+                    let printed = state.get_printed(step, alter, tied, explicit);
+                    cx.map_headid_alter.borrow_mut().insert(head.id, alter);
+                    cx.map_headid_accidental.borrow_mut().insert(head.id, printed);
+                }
+            }
+            Ok(())
+        })
+    }
+
     pub(crate) fn set_stemitems_stemlengths(cx: &CoreContext) -> Result<(), Box<dyn std::error::Error>> {
         cx.stemitems.borrow().iter().for_each(|stemitem| {
             let _ = StemItemUtils::calculate_stem_lengths_for_notes(cx, stemitem);
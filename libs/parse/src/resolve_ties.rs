@@ -7,15 +7,14 @@ use std::error::Error;
 
 pub fn handle_ties(cx: &CoreContext, partnotes_data: &Vec<(VecPartNotes, VecPartNotes)>) -> Result<(), Box<dyn Error>> {
     for (partnotes_upper, partnotes_lower) in partnotes_data {
-        handle_partnotes(cx, 0, &partnotes_upper)?;
-        handle_partnotes(cx, 1, &partnotes_lower)?;
+        handle_partnotes(cx, 0, partnotes_upper)?;
+        handle_partnotes(cx, 1, partnotes_lower)?;
     }
 
     Ok(())
 }
 
 #[allow(dead_code, unused_variables, unused_imports)]
-
 fn handle_partnotes(cx: &CoreContext, partidx: usize, partnotes: &VecPartNotes) -> Result<(), Box<dyn Error>> {
     for item in partnotes.windows(2) {
         let left = item[0];
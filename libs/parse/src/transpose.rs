@@ -0,0 +1,162 @@
+use core::{
+    accidental::{Accidental, AccidentalMode, AccidentalState},
+    context::CoreContext,
+    hpart::HPartType,
+    pitch::Pitch,
+    transpose::{Interval, TransposeMode, TransposeUtils},
+};
+use std::{collections::BTreeMap, error::Error};
+
+use crate::{
+    parse2::Parse2,
+    parse2utils::{HeadWalkItem, Parse2Utils},
+    write2::Write2,
+};
+
+pub struct Transpose;
+
+impl Transpose {
+    /// Transposed score in the notation accepted by `Parse2::sysitemlist2`.
+    /// Accidentals are written where the accidental state of the transposed bar needs them,
+    /// and where the input wrote out a courtesy accidental the state did not need.
+    pub fn transpose_to_string(cx: &CoreContext, interval: Interval, mode: TransposeMode) -> Result<String, Box<dyn Error>> {
+        let map_headid_alter = cx.map_headid_alter.borrow();
+        let mode_written = *cx.accidental_mode.borrow() == AccidentalMode::Written;
+
+        let mut map_hpartid_key = BTreeMap::new();
+        let mut map_hpartid_interval = BTreeMap::new();
+        for hpart in cx.hparts.borrow().iter() {
+            if let HPartType::Music { attr, .. } = &hpart.hptype {
+                let (key_to, part_interval) = TransposeUtils::transpose_key(&attr.key, interval, mode);
+                map_hpartid_key.insert(hpart.id, key_to);
+                map_hpartid_interval.insert(hpart.id, part_interval);
+            }
+        }
+
+        let mut map_headid_head = BTreeMap::new();
+        // accidental states of the input and of the transposed staves
+        let mut states: BTreeMap<usize, (AccidentalState, AccidentalState)> = BTreeMap::new();
+
+        Parse2Utils::walk_heads(cx, |part_idx, item| {
+            match item {
+                HeadWalkItem::Barline => {
+                    if let Some((state_from, state_to)) = states.get_mut(&part_idx) {
+                        state_from.barline();
+                        state_to.barline();
+                    }
+                }
+                HeadWalkItem::Head { hpart, attr, note, head, clef } => {
+                    let key_to = map_hpartid_key[&hpart.id];
+                    let part_interval = map_hpartid_interval[&hpart.id];
+
+                    let mut pitch = Pitch::from_level(clef, &attr.key, head.level, &head.accidental);
+                    if let Some(alter) = map_headid_alter.get(&head.id) {
+                        pitch.alter = *alter;
+                    }
+                    let pitch_to = TransposeUtils::transpose_pitch(&pitch, part_interval, mode, &attr.key, &key_to);
+
+                    let (state_from, state_to) = states.entry(part_idx).or_insert_with(|| (AccidentalState::new(attr.key), AccidentalState::new(key_to)));
+                    state_from.set_key(attr.key);
+                    state_to.set_key(key_to);
+                    let tied = Parse2Utils::is_tied_to(cx, note.id, head.level);
+                    let needed = state_from.get_printed(pitch.step, pitch.alter, tied, false) != Accidental::None;
+                    let courtesy = mode_written && head.accidental != Accidental::None && !needed;
+                    let accidental = state_to.get_printed(pitch_to.step, pitch_to.alter, tied, courtesy);
+                    map_headid_head.insert(head.id, (pitch_to.get_level(clef), accidental));
+                }
+            }
+            Ok(())
+        })?;
+
+        Write2::sysitemlist2_with(cx, &map_headid_head, &map_hpartid_key)
+    }
+
+    /// Transposed score as a new context. Stem directions and head positions are calculated from the new levels.
+    pub fn transpose(cx: &CoreContext, interval: Interval, mode: TransposeMode) -> Result<&'static CoreContext, Box<dyn Error>> {
+        let code = Transpose::transpose_to_string(cx, interval, mode)?;
+        let transposed_cx = CoreContext::new();
+        Parse2::sysitemlist2(transposed_cx, &code, false)?;
+        Ok(transposed_cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use core::key::KeySignature;
+
+    // key signature and sounding pitch of every head, in part order
+    fn get_pitches(cx: &CoreContext) -> Vec<(KeySignature, Pitch)> {
+        let map_headid_alter = cx.map_headid_alter.borrow();
+        let mut pitches = Vec::new();
+        Parse2Utils::walk_heads(cx, |_, item| {
+            if let HeadWalkItem::Head { attr, head, clef, .. } = item {
+                let mut pitch = Pitch::from_level(clef, &attr.key, head.level, &head.accidental);
+                if let Some(alter) = map_headid_alter.get(&head.id) {
+                    pitch.alter = *alter;
+                }
+                pitches.push((attr.key, pitch));
+            }
+            Ok(())
+        })
+        .unwrap();
+        pitches
+    }
+
+    #[test]
+    fn test_clarinet() {
+        let cx = CoreContext::new();
+        Parse2::sysitemlist2(cx, "clef G F | key b | 0 -1 d8 n-2 -3_ / 7 | bl | _-3 d2 #3 / 8", false).unwrap();
+        assert_eq!(Write2::sysitemlist2(cx).unwrap(), "clef G F | key 1b | 0 -1 d8 n-2 -3_ / 7 | bl | _-3 d2 #3 / 8");
+
+        let code = Transpose::transpose_to_string(cx, Interval::MAJOR_SECOND, TransposeMode::Chromatic).unwrap();
+        // the courtesy natural written on the D stays on the E it becomes
+        assert_eq!(code, "clef G F | key 1# | -1 -2 d8 n-3 -4_ / 6 | bl | _-4 d2 #2 / 7");
+
+        let code = Transpose::transpose_to_string(cx, Interval::MAJOR_SECOND.down(), TransposeMode::Diatonic).unwrap();
+        assert_eq!(code, "clef G F | key 1b | 1 0 d8 n-1 -2_ / 8 | bl | _-2 d2 #4 / 9");
+
+        // written for a Bb clarinet: every head a major second up, spelled a step up in G major
+        let transposed_cx = Transpose::transpose(cx, Interval::MAJOR_SECOND, TransposeMode::Chromatic).unwrap();
+        let (concert, written) = (get_pitches(cx), get_pitches(transposed_cx));
+        assert_eq!(written.len(), concert.len());
+        for ((_, from), (key, to)) in concert.iter().zip(written.iter()) {
+            assert_eq!(*key, KeySignature::Sharp1);
+            assert_eq!((to.step, to.get_semitones()), (from.step + 1, from.get_semitones() + 2));
+        }
+        // concert Bb4 C5 D5 E5 E5 F#4 are written C5 D5 E5 F#5 F#5 G#4
+        let upper = written.iter().take(6).map(|(_, pitch)| (pitch.step, pitch.alter)).collect::<Vec<_>>();
+        assert_eq!(upper, vec![(35, 0), (36, 0), (37, 0), (38, 1), (38, 1), (32, 1)]);
+    }
+
+    #[test]
+    fn test_enharmonic() {
+        // Eb major up a minor second is spelled as E major, not Fb major, so the Cb becomes a C natural
+        let cx = CoreContext::new();
+        Parse2::sysitemlist2(cx, "clef G | key 3b | 1 b-1 0", false).unwrap();
+        let code = Transpose::transpose_to_string(cx, Interval::MINOR_SECOND, TransposeMode::Chromatic).unwrap();
+        assert_eq!(code, "clef G | key 4# | 1 n-1 0");
+
+        let transposed_cx = Transpose::transpose(cx, Interval::MINOR_SECOND, TransposeMode::Chromatic).unwrap();
+        let pitches = get_pitches(transposed_cx);
+        assert!(pitches.iter().all(|(key, _)| *key == KeySignature::Sharp4));
+        assert_eq!(
+            pitches.iter().map(|(_, pitch)| *pitch).collect::<Vec<_>>(),
+            vec![Pitch::new(33, 0), Pitch::new(35, 0), Pitch::new(34, 0)]
+        );
+    }
+
+    #[test]
+    fn test_clef_change() {
+        // after clef:F level 0 is a D3, not the B4 sharpened earlier in the bar, and the bass clef carries over to the next bar
+        let cx = CoreContext::new();
+        Parse2::sysitemlist2(cx, "clef G | #0 clef:F 0 | bl | 0", false).unwrap();
+        assert_eq!(Write2::sysitemlist2(cx).unwrap(), "clef G | #0 clef:F 0 | bl | 0");
+        let alters = cx.map_headid_alter.borrow().values().cloned().collect::<Vec<_>>();
+        assert_eq!(alters, vec![1, 0, 0]);
+
+        let code = Transpose::transpose_to_string(cx, Interval::MAJOR_SECOND, TransposeMode::Chromatic).unwrap();
+        assert_eq!(code, "clef G | key 2# | -2 clef:F -1 | bl | -1");
+    }
+}
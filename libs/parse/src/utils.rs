@@ -2,7 +2,6 @@ use core::{context::CoreContext, part::PartType, sysitem::VecPartNotes, voice::V
 use std::error::Error;
 
 #[allow(dead_code, unused_variables, unused_imports)]
-
 pub fn create_part_notes_vecs(cx: &CoreContext, partscount: usize) -> Result<Vec<(VecPartNotes, VecPartNotes)>, Box<dyn Error>> {
     let parts = cx.parts.borrow();
     let sysitems = cx.sysitems.borrow();
@@ -0,0 +1,302 @@
+use core::{
+    accidental::Accidental,
+    barline::BarlineType,
+    context::CoreContext,
+    duration::NoteDuration,
+    head::{HeadId, HeadItem},
+    hpart::{HPartItemsColumnType, HPartMusicType, HPartType, VoiceType2},
+    key::KeySignature,
+    note::{NoteItem, NoteType},
+    ties::{TieFrom, TieTo},
+};
+use std::{collections::BTreeMap, error::Error};
+
+/// Writes a parsed context back to the notation accepted by `Parse2::sysitemlist2`
+pub struct Write2;
+
+impl Write2 {
+    pub fn sysitemlist2(cx: &CoreContext) -> Result<String, Box<dyn Error>> {
+        Write2::sysitemlist2_with(cx, &BTreeMap::new(), &BTreeMap::new())
+    }
+
+    /// Same as `sysitemlist2`, with head levels/accidentals replaced per head id and key signatures replaced per hpart id
+    pub fn sysitemlist2_with(cx: &CoreContext, map_headid_head: &BTreeMap<HeadId, (i8, Accidental)>, map_hpartid_key: &BTreeMap<usize, KeySignature>) -> Result<String, Box<dyn Error>> {
+        let columns = cx.columns.borrow();
+        let hparts = cx.hparts.borrow();
+
+        let mut items: Vec<String> = Vec::new();
+        let groups = cx.staff_groups.borrow();
+        if !groups.is_empty() {
+            items.push(format!("group {}", groups.iter().map(|group| group.get_code()).collect::<Vec<_>>().join(" ")));
+        }
+        for (part_idx, header) in cx.map_partidx_header.borrow().iter() {
+            items.push(header.get_code(*part_idx));
+        }
+        let mut current_keys: Vec<KeySignature> = Vec::new();
+        for (col_idx, column) in columns.iter().enumerate() {
+            items.extend(Write2::repeats(cx, col_idx));
+            match &column.hptype {
+                HPartItemsColumnType::Clefs(ids) => {
+                    let mut segments: Vec<&str> = Vec::new();
+                    for id in ids.iter() {
+                        match &hparts.get(*id).ok_or("HPart not found")?.hptype {
+                            HPartType::Clef(clef) => segments.push(clef.get_code()),
+                            _ => return Err("Expected clef in clefs column".into()),
+                        }
+                    }
+                    items.push(format!("clef {}", segments.join(" ")));
+                }
+                HPartItemsColumnType::Barlines(ids) => {
+                    let mut barlines: Vec<&BarlineType> = Vec::new();
+                    for id in ids.iter() {
+                        match &hparts.get(*id).ok_or("HPart not found")?.hptype {
+                            HPartType::Barline(barline) => barlines.push(barline),
+                            _ => return Err("Expected barline in barlines column".into()),
+                        }
+                    }
+                    if barlines.iter().all(|barline| *barline == barlines[0]) {
+                        items.push(barlines[0].get_code().to_string());
+                    } else {
+                        items.push(barlines.iter().map(|barline| barline.get_code()).collect::<Vec<_>>().join(" "));
+                    }
+                }
+                HPartItemsColumnType::Musics(ids) => {
+                    let mut keys: Vec<KeySignature> = Vec::new();
+                    let mut parts: Vec<String> = Vec::new();
+                    for id in ids.iter() {
+                        match &hparts.get(*id).ok_or("HPart not found")?.hptype {
+                            HPartType::Music { mtype, attr, .. } => {
+                                keys.push(map_hpartid_key.get(id).copied().unwrap_or(attr.key));
+                                let part = match mtype {
+                                    HPartMusicType::OneVoice { voice } => Write2::voice(cx, voice, map_headid_head)?,
+                                    HPartMusicType::TwoVoices { upper, lower } => {
+                                        format!("{} % {}", Write2::voice(cx, upper, map_headid_head)?, Write2::voice(cx, lower, map_headid_head)?)
+                                    }
+                                };
+                                parts.push(part);
+                            }
+                            _ => return Err("Expected music in musics column".into()),
+                        }
+                    }
+
+                    if keys != current_keys && (!current_keys.is_empty() || keys.iter().any(|key| *key != KeySignature::Neutral)) {
+                        let segments = if keys.iter().all(|key| *key == keys[0]) {
+                            vec![key_code(&keys[0])]
+                        } else {
+                            keys.iter().map(key_code).collect()
+                        };
+                        items.push(format!("key {}", segments.join(" ")));
+                    }
+                    current_keys = keys;
+                    items.push(parts.join(" / "));
+                }
+            }
+        }
+
+        items.extend(Write2::repeats(cx, columns.len()));
+
+        Ok(items.join(" | "))
+    }
+
+    /// Volta and navigation items placed before a column
+    fn repeats(cx: &CoreContext, col_idx: usize) -> Vec<String> {
+        let voltas = cx.voltas.borrow();
+        let mut items: Vec<String> = Vec::new();
+        let starts = voltas.iter().find(|volta| volta.from_col_idx == col_idx);
+        let ends = voltas.iter().any(|volta| volta.to_col_idx == col_idx && volta.from_col_idx < col_idx);
+        if let Some(volta) = starts {
+            items.push(format!("volta {}", volta.numbers.iter().map(|number| number.to_string()).collect::<Vec<_>>().join(",")));
+        } else if ends && col_idx < cx.columns.borrow().len() {
+            items.push("volta".to_string());
+        }
+        if let Some(marks) = cx.map_colidx_navigations.borrow().get(&col_idx) {
+            items.push(format!("nav {}", marks.iter().map(|mark| mark.get_code()).collect::<Vec<_>>().join(" ")));
+        }
+        items
+    }
+
+    fn voice(cx: &CoreContext, voice: &VoiceType2, map_headid_head: &BTreeMap<HeadId, (i8, Accidental)>) -> Result<String, Box<dyn Error>> {
+        match voice {
+            VoiceType2::Barpause(_) => Ok("bp".to_string()),
+            VoiceType2::NoteIds { note_ids, .. } => {
+                let notes = cx.notes.borrow();
+                // parse_notes starts every voice with quarter notes
+                let mut duration = NoteDuration::D4;
+                let mut staff_offset: i8 = 0;
+                let mut tokens: Vec<String> = Vec::new();
+                for note_id in note_ids.iter() {
+                    let note = notes.get(*note_id).ok_or("Note not found")?;
+                    if let Some(clef) = cx.map_noteid_clef.borrow().get(note_id) {
+                        tokens.push(format!("clef:{}", clef.get_code()));
+                    }
+                    if let Some(color) = cx.map_noteid_color.borrow().get(note_id) {
+                        tokens.push(format!("color:{}", color.get_code()));
+                    }
+                    let note_staff_offset = cx.map_noteid_staffoffset.borrow().get(note_id).cloned().unwrap_or(0);
+                    if note_staff_offset != staff_offset {
+                        staff_offset = note_staff_offset;
+                        tokens.push(if staff_offset > 0 {
+                            format!("staff:+{}", staff_offset)
+                        } else {
+                            format!("staff:{}", staff_offset)
+                        });
+                    }
+                    if note.duration != duration {
+                        duration = note.duration;
+                        tokens.push(duration_code(duration));
+                    }
+                    let token = match &note.ntype {
+                        NoteType::Heads(heads) => heads.iter().map(|head| Write2::head(cx, note, head, map_headid_head)).collect::<Vec<_>>().join(","),
+                        NoteType::Rest => match cx.map_noteid_restlevel.borrow().get(note_id) {
+                            Some(level) => format!("r{}", level),
+                            None => "r".to_string(),
+                        },
+                        NoteType::Space | NoteType::LyricItem => "s".to_string(),
+                    };
+                    tokens.push(token);
+                }
+                Ok(tokens.join(" "))
+            }
+        }
+    }
+
+    fn head(cx: &CoreContext, note: &NoteItem, head: &HeadItem, map_headid_head: &BTreeMap<HeadId, (i8, Accidental)>) -> String {
+        let tie_to = cx
+            .map_noteid_tiesto
+            .borrow()
+            .get(&note.id)
+            .is_some_and(|ties| ties.iter().any(|tie| matches!(tie, TieTo::Level(level) if *level == head.level)));
+        let tie_from = cx
+            .map_noteid_tiesfrom
+            .borrow()
+            .get(&note.id)
+            .is_some_and(|ties| ties.iter().any(|tie| matches!(tie, TieFrom::Level(level) if *level == head.level)));
+
+        let (level, accidental) = map_headid_head.get(&head.id).cloned().unwrap_or((head.level, head.accidental.clone()));
+        format!("{}{}{}{}", if tie_to { "_" } else { "" }, accidental_code(&accidental), level, if tie_from { "_" } else { "" })
+    }
+}
+
+fn key_code(key: &KeySignature) -> String {
+    match key.fifths() {
+        0 => "0".to_string(),
+        fifths if fifths > 0 => format!("{}#", fifths),
+        fifths => format!("{}b", -fifths),
+    }
+}
+
+fn accidental_code(accidental: &Accidental) -> &'static str {
+    match accidental {
+        Accidental::DoubleSharp => "##",
+        Accidental::Sharp => "#",
+        Accidental::None => "",
+        Accidental::Natural => "n",
+        Accidental::Flat => "b",
+        Accidental::DoubleFlat => "bb",
+    }
+}
+
+fn duration_code(duration: NoteDuration) -> String {
+    let value = match duration {
+        NoteDuration::D1Dot => "1.",
+        NoteDuration::D1 => "1",
+        NoteDuration::D2Dot => "2.",
+        NoteDuration::D2 => "2",
+        NoteDuration::D2Tri => "2Tri",
+        NoteDuration::D4Dot => "4.",
+        NoteDuration::D4 => "4",
+        NoteDuration::D8Dot => "8.",
+        NoteDuration::D4Tri => "4Tri",
+        NoteDuration::D8 => "8",
+        NoteDuration::D16Dot => "16.",
+        NoteDuration::D8Tri => "8Tri",
+        NoteDuration::D16 => "16",
+        NoteDuration::D16Tri => "16Tri",
+        NoteDuration::D32 => "32",
+    };
+    format!("d{}", value)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{parse2::Parse2, parse2utils::Parse2Utils};
+    use core::{repeat::RepeatUtils, transpose::Interval};
+
+    #[test]
+    fn test_barlines() {
+        let items = Parse2Utils::split_sysitems("|bl | 0 | bl||| 1 | bl|.|");
+        assert_eq!(items.iter().map(|item| item.trim()).collect::<Vec<_>>(), vec!["bl", "0", "bl||", "1", "bl|."]);
+
+        let cx = CoreContext::new();
+        Parse2::sysitemlist2(cx, "bl|| | 0 / 1 | bl- bl: | 2 / 3 | bl|.", false).unwrap();
+        assert_eq!(Write2::sysitemlist2(cx).unwrap(), "bl|| | 0 / 1 | bl- bl: | 2 / 3 | bl|.");
+
+        let cx = CoreContext::new();
+        Parse2::sysitemlist2(cx, "r-4 r % r3 r", false).unwrap();
+        assert_eq!(Write2::sysitemlist2(cx).unwrap(), "r-4 r % r3 r");
+
+        let cx = CoreContext::new();
+        Parse2::sysitemlist2(cx, "d16 2 0 staff:+1 -3 -5 staff:0 0 / d4 staff:-1 0 staff:0 d2 2", false).unwrap();
+        assert_eq!(Write2::sysitemlist2(cx).unwrap(), "d16 2 0 staff:+1 -3 -5 staff:0 0 / staff:-1 0 staff:0 d2 2");
+
+        let cx = CoreContext::new();
+        // colours are written back as given, by name or by value
+        let code = "0 color:red 1 color:highlight 2,4 color:#20a0ff 3";
+        Parse2::sysitemlist2(cx, code, false).unwrap();
+        assert_eq!(Write2::sysitemlist2(cx).unwrap(), code);
+        assert!(Parse2::sysitemlist2(CoreContext::new(), "0 color:pink 1", false).is_err());
+        assert!(Parse2::sysitemlist2(CoreContext::new(), "0 1 color:red", false).is_err());
+    }
+
+    #[test]
+    fn test_groups() {
+        let cx = CoreContext::new();
+        Parse2::sysitemlist2(cx, "group bracket 1-2 brace 3-4 | clef G F G F | 0 / 1 / 2 / 3 | bl", false).unwrap();
+        assert_eq!(Write2::sysitemlist2(cx).unwrap(), "group bracket 1-2 brace 3-4 | clef G F G F | 0 / 1 / 2 / 3 | bl");
+        assert!(Parse2::sysitemlist2(CoreContext::new(), "group bracket 1-9 | 0 / 1", false).is_err());
+    }
+
+    #[test]
+    fn test_part_headers() {
+        let cx = CoreContext::new();
+        let code = "part 1 name=\"Clarinet in Bb\" short=\"Cl.\" transp=M2 program=71 | part 2 name=Piano | 0 / 1";
+        Parse2::sysitemlist2(cx, code, false).unwrap();
+        assert_eq!(Write2::sysitemlist2(cx).unwrap(), code.replace("name=Piano", "name=\"Piano\""));
+        let headers = cx.map_partidx_header.borrow();
+        assert_eq!(headers[&0].short_name, "Cl.");
+        assert_eq!(headers[&0].transposition, Some(Interval::MAJOR_SECOND));
+        assert_eq!(headers[&1].program, None);
+    }
+    #[test]
+    fn test_repeats() {
+        let cx = CoreContext::new();
+        let code = "bl|: | 0 | volta 1 | 1 | bl:| | volta 2 | 2 | bl|.";
+        Parse2::sysitemlist2(cx, code, false).unwrap();
+        assert_eq!(Write2::sysitemlist2(cx).unwrap(), code);
+        assert!(cx.voltas.borrow()[0].closed);
+        assert_eq!(RepeatUtils::expand_notes(cx, 0, 0).unwrap(), vec![0, 1, 0, 2]);
+
+        let cx = CoreContext::new();
+        let code = "0 | bl | nav segno | 1 | bl | nav fine | 2 | bl | nav ds-fine";
+        Parse2::sysitemlist2(cx, code, false).unwrap();
+        assert_eq!(Write2::sysitemlist2(cx).unwrap(), code);
+        assert_eq!(RepeatUtils::expand_notes(cx, 0, 0).unwrap(), vec![0, 1, 2, 1]);
+
+        // the second repeat goes back to the end of the first one
+        let cx = CoreContext::new();
+        Parse2::sysitemlist2(cx, "0 | bl:| | 1 | bl:| | 2", false).unwrap();
+        assert_eq!(RepeatUtils::expand_notes(cx, 0, 0).unwrap(), vec![0, 0, 1, 1, 2]);
+
+        // To Coda is taken once, after the D.C.
+        let cx = CoreContext::new();
+        Parse2::sysitemlist2(cx, "0 | nav tocoda | 1 | nav dc | nav coda | 2", false).unwrap();
+        assert_eq!(RepeatUtils::expand_notes(cx, 0, 0).unwrap(), vec![0, 1, 0, 2]);
+
+        // a Coda before its To Coda would loop
+        let cx = CoreContext::new();
+        Parse2::sysitemlist2(cx, "0 | nav coda | 1 | nav tocoda | 2 | nav dc", false).unwrap();
+        assert!(RepeatUtils::expand_notes(cx, 0, 0).is_err());
+    }
+}
@@ -0,0 +1,450 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use fonts::fontcontext::{FontContext, SANSSERIF_FONT_DATA};
+use graphics::backend::{RenderBackend, render_page};
+use graphics::color::Color;
+use graphics::fill::Fill;
+use graphics::graphicitem::{GraphicItems, PathCache, TEXT_SIZE, TextMetrics};
+use graphics::path::PathSegment;
+use graphics::stroke::{LineCap, LineJoin, Stroke};
+use graphics::transform::Transform;
+
+// graphic item units are css pixels, 96 to the inch, and pdf units are points, 72 to the inch
+const POINTS_PER_UNIT: f32 = 0.75;
+const PDF_BORDER_MARGIN: f32 = 3.0;
+// space kept free around the drawing on pages of a fixed size, in points
+const PAGE_MARGIN: f32 = 36.0;
+// control point distance for a quarter ellipse drawn as a cubic curve
+const KAPPA: f32 = 0.552_284_8;
+// postscript name of the embedded sans-serif font
+const FONT_NAME: &str = "OpenSans-Regular";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageSize {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl PageSize {
+    pub const A4: PageSize = PageSize { width: 595.28, height: 841.89 };
+    pub const LETTER: PageSize = PageSize { width: 612.0, height: 792.0 };
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PdfOptions {
+    /// Page size in points, with the drawing scaled down to fit if needed. Without it each page is the size of its drawing
+    pub page_size: Option<PageSize>,
+    pub title: Option<String>,
+    pub composer: Option<String>,
+}
+
+struct Page {
+    width: f32,
+    height: f32,
+    content: String,
+}
+
+/// Writes graphic items as a PDF document with a page per drawing. Nothing time or machine dependent is written,
+/// so the same items give the same bytes
+pub struct PdfBuilder {
+    options: PdfOptions,
+    pages: Vec<Page>,
+    graphic_states: Vec<String>,
+    // text is measured in the sans-serif font, which is embedded when any text is drawn
+    fonts: Option<&'static FontContext<'static>>,
+    // glyph ids drawn, with their characters and widths in thousandths of the em
+    glyphs: BTreeMap<u16, (char, f32)>,
+    background: Option<Color>,
+}
+
+impl PdfBuilder {
+    pub fn new(options: PdfOptions) -> PdfBuilder {
+        PdfBuilder {
+            options,
+            pages: Vec::new(),
+            graphic_states: Vec::new(),
+            fonts: None,
+            glyphs: BTreeMap::new(),
+            background: None,
+        }
+    }
+
+    /// Colour the pages are filled with, none leaves them blank
+    pub fn with_background(mut self, background: Option<Color>) -> PdfBuilder {
+        self.background = background;
+        self
+    }
+
+    pub fn build(&mut self, pages: Vec<GraphicItems>) -> Vec<u8> {
+        for items in pages {
+            render_page(self, items, PDF_BORDER_MARGIN);
+        }
+        self.finish()
+    }
+
+    fn content(&mut self) -> &mut String {
+        &mut self.pages.last_mut().expect("Drawing on a pdf page before begin").content
+    }
+
+    // name of a shared graphics state setting the fill (ca) or stroke (CA) opacity
+    fn graphic_state(&mut self, key: &str, alpha: f32) -> String {
+        let entry = format!("/{} {}", key, num(alpha));
+        let idx = match self.graphic_states.iter().position(|state| *state == entry) {
+            Some(idx) => idx,
+            None => {
+                self.graphic_states.push(entry);
+                self.graphic_states.len() - 1
+            }
+        };
+        format!("/GS{}", idx)
+    }
+
+    fn write_color(&mut self, color: &Color, opacity: f32, stroking: bool) {
+        let (r, g, b, a) = color_rgb(color);
+        let a = a * opacity;
+        let (operator, key) = if stroking { ("RG", "CA") } else { ("rg", "ca") };
+        let mut code = format!("{} {} {} {}\n", num(r), num(g), num(b), operator);
+        if a < 1.0 {
+            let _ = writeln!(code, "{} gs", self.graphic_state(key, a));
+        }
+        self.content().push_str(&code);
+    }
+
+    // paints the path written by `write_path` with the stroke and fill, in a graphics state of its own
+    fn paint(&mut self, stroke: &Stroke, fill: &Fill, write_path: impl FnOnce(&mut String)) {
+        // gradients are painted in their first colour
+        let stroke = stroke.get_style();
+        let fill = fill.get_color();
+        let operator = match (&stroke, &fill) {
+            (None, None) => return,
+            (Some(_), None) => "S",
+            (None, Some(_)) => "f",
+            (Some(_), Some(_)) => "B",
+        };
+        self.content().push_str("q\n");
+        if let Some((width, color, style)) = stroke {
+            self.write_color(&color, style.opacity, true);
+            let mut code = format!("{} w\n", num(width));
+            if !style.dash.is_empty() {
+                let dash: Vec<String> = style.dash.iter().map(|length| num(*length)).collect();
+                let _ = writeln!(code, "[{}] 0 d", dash.join(" "));
+            }
+            let cap = match style.cap {
+                LineCap::Butt => 0,
+                LineCap::Round => 1,
+                LineCap::Square => 2,
+            };
+            let join = match style.join {
+                LineJoin::Miter => 0,
+                LineJoin::Round => 1,
+                LineJoin::Bevel => 2,
+            };
+            if cap != 0 {
+                let _ = writeln!(code, "{} J", cap);
+            }
+            if join != 0 {
+                let _ = writeln!(code, "{} j", join);
+            }
+            self.content().push_str(&code);
+        }
+        if let Some(color) = fill {
+            self.write_color(&color, 1.0, false);
+        }
+        let content = self.content();
+        write_path(content);
+        content.push_str(operator);
+        content.push_str("\nQ\n");
+    }
+}
+
+impl RenderBackend for PdfBuilder {
+    type Output = Vec<u8>;
+
+    fn begin(&mut self, width: f32, height: f32) {
+        let (page_width, page_height, scale, offset) = match self.options.page_size {
+            Some(size) => {
+                let scale = POINTS_PER_UNIT.min((size.width - 2.0 * PAGE_MARGIN) / width).min((size.height - 2.0 * PAGE_MARGIN) / height);
+                (size.width, size.height, scale, PAGE_MARGIN)
+            }
+            None => (width * POINTS_PER_UNIT, height * POINTS_PER_UNIT, POINTS_PER_UNIT, 0.0),
+        };
+        self.pages.push(Page {
+            width: page_width,
+            height: page_height,
+            content: String::new(),
+        });
+        if let Some(background) = self.background {
+            self.content().push_str("q\n");
+            self.write_color(&background, 1.0, false);
+            let _ = writeln!(self.content(), "0 0 {} {} re\nf\nQ", num(page_width), num(page_height));
+        }
+        // pdf has the origin at the bottom left, so the drawing is flipped onto the page
+        let _ = writeln!(self.content(), "{} 0 0 {} {} {} cm", num(scale), num(-scale), num(offset), num(page_height - offset));
+    }
+
+    fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, stroke: &Stroke, _id: Option<&str>) {
+        self.paint(stroke, &Fill::None, |content| {
+            let _ = writeln!(content, "{} {} m {} {} l", num(x1), num(y1), num(x2), num(y2));
+        });
+    }
+
+    fn rect(&mut self, x: f32, y: f32, w: f32, h: f32, stroke: &Stroke, fill: &Fill, _id: Option<&str>) {
+        self.paint(stroke, fill, |content| {
+            let _ = writeln!(content, "{} {} {} {} re", num(x), num(y), num(w), num(h));
+        });
+    }
+
+    fn ellipse(&mut self, cx: f32, cy: f32, rx: f32, ry: f32, stroke: &Stroke, fill: &Fill, _id: Option<&str>) {
+        let (kx, ky) = (rx * KAPPA, ry * KAPPA);
+        let segments = [
+            PathSegment::M(cx + rx, cy),
+            PathSegment::C(cx + rx, cy + ky, cx + kx, cy + ry, cx, cy + ry),
+            PathSegment::C(cx - kx, cy + ry, cx - rx, cy + ky, cx - rx, cy),
+            PathSegment::C(cx - rx, cy - ky, cx - kx, cy - ry, cx, cy - ry),
+            PathSegment::C(cx + kx, cy - ry, cx + rx, cy - ky, cx + rx, cy),
+            PathSegment::Z,
+        ];
+        self.paint(stroke, fill, |content| write_segments(content, &segments, 0.0, 0.0));
+    }
+
+    fn path(&mut self, segments: &[PathSegment], x: f32, y: f32, stroke: &Stroke, fill: &Fill, _cache: Option<&PathCache>, _id: Option<&str>) {
+        self.paint(stroke, fill, |content| write_segments(content, segments, x, y));
+    }
+
+    fn text(&mut self, x: f32, y: f32, text: &str, color: &Color, _id: Option<&str>) {
+        let fonts = *self.fonts.get_or_insert_with(FontContext::new);
+        let size = fonts.get_sansserif_em_size(TEXT_SIZE);
+        // the font is identity encoded, so the text is written as two byte glyph ids
+        let mut codes = String::new();
+        for (id, ch, advance) in fonts.get_sansserif_glyphs(text) {
+            let _ = write!(codes, "{:04X}", id);
+            self.glyphs.entry(id).or_insert((ch, advance * 1000.0));
+        }
+        self.content().push_str("q\n");
+        self.write_color(color, 1.0, false);
+        // the text matrix flips the glyphs back upright on the flipped page
+        let _ = writeln!(self.content(), "BT\n/F1 {} Tf\n1 0 0 -1 {} {} Tm\n<{}> Tj\nET\nQ", num(size), num(x), num(y), codes);
+    }
+
+    fn begin_group(&mut self, _id: Option<&str>, _class: Option<&str>, transform: &Transform) {
+        let Transform { a, b, c, d, e, f } = *transform;
+        let _ = writeln!(self.content(), "q\n{} {} {} {} {} {} cm", num(a), num(b), num(c), num(d), num(e), num(f));
+    }
+
+    fn end_group(&mut self) {
+        self.content().push_str("Q\n");
+    }
+
+    fn finish(&mut self) -> Vec<u8> {
+        let pages = std::mem::take(&mut self.pages);
+        let graphic_states = std::mem::take(&mut self.graphic_states);
+        let glyphs = std::mem::take(&mut self.glyphs);
+
+        // catalog, page tree, info and resources come first, then each page and its content
+        let mut objects: Vec<Vec<u8>> = Vec::new();
+        objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+        let kids: Vec<String> = (0..pages.len()).map(|idx| format!("{} 0 R", 5 + 2 * idx)).collect();
+        objects.push(format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()).into_bytes());
+        let mut info = String::from("<< /Producer (score)");
+        if let Some(title) = &self.options.title {
+            let _ = write!(info, " /Title {}", info_string(title));
+        }
+        if let Some(composer) = &self.options.composer {
+            let _ = write!(info, " /Author {}", info_string(composer));
+        }
+        info.push_str(" >>");
+        objects.push(info.into_bytes());
+        let states: String = graphic_states.iter().enumerate().map(|(idx, state)| format!(" /GS{} << {} >>", idx, state)).collect();
+        // the font objects follow the pages
+        let font = 5 + 2 * pages.len();
+        let fonts = if glyphs.is_empty() { String::new() } else { format!(" /Font << /F1 {} 0 R >>", font) };
+        objects.push(format!("<< /ExtGState <<{} >>{} >>", states, fonts).into_bytes());
+        for (idx, page) in pages.iter().enumerate() {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources 4 0 R /Contents {} 0 R >>",
+                    num(page.width),
+                    num(page.height),
+                    6 + 2 * idx
+                )
+                .into_bytes(),
+            );
+            objects.push(format!("<< /Length {} >>\nstream\n{}\nendstream", page.content.len(), page.content).into_bytes());
+        }
+        if !glyphs.is_empty() {
+            objects.extend(font_objects(self.fonts.expect("Glyphs drawn without fonts"), &glyphs, font));
+        }
+
+        let mut pdf: Vec<u8> = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::new();
+        for (idx, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend(format!("{} 0 obj\n", idx + 1).as_bytes());
+            pdf.extend(object);
+            pdf.extend(b"\nendobj\n");
+        }
+        let xref = pdf.len();
+        let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(trailer, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(trailer, "trailer\n<< /Size {} /Root 1 0 R /Info 3 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref);
+        pdf.extend(trailer.as_bytes());
+        pdf
+    }
+
+    fn text_metrics(&mut self) -> &dyn TextMetrics {
+        *self.fonts.get_or_insert_with(FontContext::new)
+    }
+}
+
+// the sans-serif font as a composite font of its glyph ids, numbered from `first`: the font, its descendant,
+// the descriptor, the font file and the map back to unicode for searching and copying text
+fn font_objects(fonts: &FontContext, glyphs: &BTreeMap<u16, (char, f32)>, first: usize) -> Vec<Vec<u8>> {
+    let widths: String = glyphs.iter().map(|(id, (_, width))| format!(" {} [{}]", id, num(*width))).collect();
+    // ascent and descent in thousandths of the em, the height between them being the scale
+    let em = fonts.get_sansserif_em_size(1.0);
+    let ascent = fonts.get_sansserif_ascent(1.0) / em * 1000.0;
+    let descent = ascent - 1000.0 / em;
+    let bbox: Vec<String> = font_bbox(SANSSERIF_FONT_DATA).unwrap_or(vec![0.0, descent, 1000.0, ascent]).into_iter().map(num).collect();
+
+    let mut file = format!("<< /Length {0} /Length1 {0} >>\nstream\n", SANSSERIF_FONT_DATA.len()).into_bytes();
+    file.extend(SANSSERIF_FONT_DATA);
+    file.extend(b"\nendstream");
+
+    let mut cmap = String::from(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n/CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n\
+         /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
+    );
+    // characters missing from the font share glyph 0 and map to none
+    let mappings: Vec<(&u16, &(char, f32))> = glyphs.iter().filter(|(id, _)| **id != 0).collect();
+    // a block holds at most a hundred mappings
+    for block in mappings.chunks(100) {
+        let _ = writeln!(cmap, "{} beginbfchar", block.len());
+        for (id, (ch, _)) in block {
+            let unicode: String = ch.encode_utf16(&mut [0; 2]).iter().map(|unit| format!("{:04X}", unit)).collect();
+            let _ = writeln!(cmap, "<{:04X}> <{}>", id, unicode);
+        }
+        cmap.push_str("endbfchar\n");
+    }
+    cmap.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend");
+
+    vec![
+        format!(
+            "<< /Type /Font /Subtype /Type0 /BaseFont /{} /Encoding /Identity-H /DescendantFonts [{} 0 R] /ToUnicode {} 0 R >>",
+            FONT_NAME,
+            first + 1,
+            first + 4
+        )
+        .into_bytes(),
+        format!(
+            "<< /Type /Font /Subtype /CIDFontType2 /BaseFont /{} /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> \
+             /FontDescriptor {} 0 R /CIDToGIDMap /Identity /W [{} ] >>",
+            FONT_NAME,
+            first + 2,
+            widths
+        )
+        .into_bytes(),
+        format!(
+            "<< /Type /FontDescriptor /FontName /{} /Flags 32 /FontBBox [{}] /ItalicAngle 0 /Ascent {} /Descent {} /CapHeight {} /StemV 80 /FontFile2 {} 0 R >>",
+            FONT_NAME,
+            bbox.join(" "),
+            num(ascent),
+            num(descent),
+            num(ascent),
+            first + 3
+        )
+        .into_bytes(),
+        file,
+        format!("<< /Length {} >>\nstream\n{}\nendstream", cmap.len(), cmap).into_bytes(),
+    ]
+}
+
+// glyph bounds of a truetype font in thousandths of the em, from its head table
+fn font_bbox(data: &[u8]) -> Option<Vec<f32>> {
+    let u16_at = |offset: usize| data.get(offset..offset + 2).map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]));
+    let tables = u16_at(4)? as usize;
+    let head = (0..tables).map(|idx| 12 + 16 * idx).find(|record| data.get(*record..record + 4) == Some(b"head"))?;
+    let offset = u32::from_be_bytes(data.get(head + 8..head + 12)?.try_into().ok()?) as usize;
+    let units_per_em = u16_at(offset + 18)? as f32;
+    (0..4).map(|idx| u16_at(offset + 36 + 2 * idx).map(|value| value as i16 as f32 * 1000.0 / units_per_em)).collect()
+}
+
+fn write_segments(content: &mut String, segments: &[PathSegment], x: f32, y: f32) {
+    // quadratic curves become cubic ones, which needs the current point
+    let mut current = (x, y);
+    let mut start = (x, y);
+    for segment in segments {
+        match *segment {
+            PathSegment::M(px, py) => {
+                current = (px + x, py + y);
+                start = current;
+                let _ = writeln!(content, "{} {} m", num(current.0), num(current.1));
+            }
+            PathSegment::L(px, py) => {
+                current = (px + x, py + y);
+                let _ = writeln!(content, "{} {} l", num(current.0), num(current.1));
+            }
+            PathSegment::Q(x1, y1, x2, y2) => {
+                let (qx, qy, ex, ey) = (x1 + x, y1 + y, x2 + x, y2 + y);
+                let c1 = (current.0 + 2.0 / 3.0 * (qx - current.0), current.1 + 2.0 / 3.0 * (qy - current.1));
+                let c2 = (ex + 2.0 / 3.0 * (qx - ex), ey + 2.0 / 3.0 * (qy - ey));
+                current = (ex, ey);
+                let _ = writeln!(content, "{} {} {} {} {} {} c", num(c1.0), num(c1.1), num(c2.0), num(c2.1), num(ex), num(ey));
+            }
+            PathSegment::C(x1, y1, x2, y2, x3, y3) => {
+                current = (x3 + x, y3 + y);
+                let _ = writeln!(content, "{} {} {} {} {} {} c", num(x1 + x), num(y1 + y), num(x2 + x), num(y2 + y), num(current.0), num(current.1));
+            }
+            PathSegment::Z => {
+                current = start;
+                content.push_str("h\n");
+            }
+        }
+    }
+}
+
+// shortest fixed precision form, so that the output does not depend on float printing details
+fn num(value: f32) -> String {
+    let text = format!("{:.3}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" { "0".to_string() } else { text.to_string() }
+}
+
+fn color_rgb(color: &Color) -> (f32, f32, f32, f32) {
+    let rgb = |r: u8, g: u8, b: u8| (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0);
+    match color {
+        Color::Black => rgb(0, 0, 0),
+        Color::Gray => rgb(128, 128, 128),
+        Color::LightGray => rgb(211, 211, 211),
+        Color::White => rgb(255, 255, 255),
+        Color::Red => rgb(255, 0, 0),
+        Color::Blue => rgb(0, 0, 255),
+        Color::Green => rgb(0, 128, 0),
+        Color::Tomato => rgb(255, 99, 71),
+        Color::DodgerBlue => rgb(30, 144, 255),
+        Color::Orange => rgb(255, 165, 0),
+        Color::Yellow => rgb(255, 255, 0),
+        Color::Lime => rgb(0, 255, 0),
+        Color::Purple => rgb(128, 0, 128),
+        Color::RGBA(r, g, b, a) => {
+            let (r, g, b, _) = rgb(*r, *g, *b);
+            (r, g, b, a.clamp(0.0, 1.0))
+        }
+    }
+}
+
+// metadata strings are UTF-16 with a byte order mark unless plain ascii
+fn info_string(text: &str) -> String {
+    if text.chars().all(|ch| (' '..='~').contains(&ch)) {
+        let escaped: String = text.chars().flat_map(|ch| if matches!(ch, '(' | ')' | '\\') { vec!['\\', ch] } else { vec![ch] }).collect();
+        return format!("({})", escaped);
+    }
+    let mut string = String::from("<FEFF");
+    for unit in text.encode_utf16() {
+        let _ = write!(string, "{:04X}", unit);
+    }
+    string.push('>');
+    string
+}
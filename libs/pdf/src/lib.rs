@@ -0,0 +1,62 @@
+pub mod builder;
+
+#[cfg(test)]
+mod tests {
+    use graphics::{
+        color::Color,
+        fill::Fill,
+        graphicitem::{GraphicItem, GraphicItems},
+        path::PathSegment,
+        stroke::Stroke,
+    };
+
+    use crate::builder::{PageSize, PdfBuilder, PdfOptions};
+
+    #[test]
+    fn test_pdf() {
+        let page: GraphicItems = vec![
+            GraphicItem::Rect(0., 0., 20., 20., Stroke::None, Fill::Solid(Color::Black), None),
+            GraphicItem::Path(
+                vec![PathSegment::M(30., 0.), PathSegment::Q(40., 30., 60., 0.), PathSegment::Z],
+                0.,
+                0.,
+                Stroke::Solid(1., Color::RGBA(0, 0, 0, 0.5)),
+                Fill::None,
+                None,
+                None,
+            ),
+            GraphicItem::Text(0., 40., "Fine (D.C.)".to_string(), Color::Black, None),
+        ];
+        let options = PdfOptions {
+            page_size: Some(PageSize::A4),
+            title: Some("Étude".to_string()),
+            composer: Some("Anon".to_string()),
+        };
+        let pdf = PdfBuilder::new(options.clone()).build(vec![page.clone(), page.clone()]);
+        assert_eq!(pdf, PdfBuilder::new(options).build(vec![page.clone(), page.clone()]));
+
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.contains("/Kids [5 0 R 7 0 R] /Count 2"));
+        assert!(text.contains("/Title <FEFF00C90074007500640065> /Author (Anon)"));
+        assert!(text.contains("/MediaBox [0 0 595.28 841.89]"));
+        assert!(text.contains("/GS0 << /CA 0.5 >>"));
+        // items are moved by the border margin
+        assert!(text.contains("33 3 m\n39.667 23 49.667 23 63 3 c\nh\nS"));
+        // text is written in the embedded font it is measured in, by glyph id, upright on the flipped page
+        assert!(text.contains("/Resources 4 0 R") && text.contains("/Font << /F1 9 0 R >>"));
+        assert!(text.contains("BT\n/F1 11.749 Tf\n1 0 0 -1 3 43 Tm\n<0029004C005100480003000B0027001100260011000C> Tj\nET"));
+        assert!(text.contains("/Subtype /Type0 /BaseFont /OpenSans-Regular /Encoding /Identity-H /DescendantFonts [10 0 R] /ToUnicode 13 0 R"));
+        assert!(text.contains("/FontFile2 12 0 R"));
+        // the widths and the map back to the characters cover the glyphs used
+        assert!(text.contains("/W [ 3 [259.766] 11 [295.898] 12 [295.898] 17 [266.113] 38 [630.859] 39 [729.004] 41 [516.113] 72 [561.035] 76 [252.93] 81 [613.77] ]"));
+        assert!(text.contains("10 beginbfchar\n<0003> <0020>\n<000B> <0028>\n"));
+        // the cross reference table is where the trailer says it is
+        let startxref: usize = text.rsplit("startxref\n").next().unwrap().trim_end_matches("\n%%EOF\n").parse().unwrap();
+        assert!(pdf[startxref..].starts_with(b"xref\n0 14\n"));
+
+        let pdf = PdfBuilder::new(PdfOptions::default()).with_background(Some(Color::White)).build(vec![page]);
+        // the page is filled before the drawing is flipped onto it
+        assert!(String::from_utf8_lossy(&pdf).contains("stream\nq\n1 1 1 rg\n0 0 49.5 "));
+    }
+}
@@ -0,0 +1,300 @@
+use std::f32::consts::PI;
+
+use fonts::fontcontext::FontContext;
+use graphics::backend::{RenderBackend, render_items};
+use graphics::color::Color;
+use graphics::fill::Fill;
+use graphics::graphicitem::{GraphicItems, PathCache, TEXT_SIZE, TextMetrics, items_bounding_box};
+use graphics::path::PathSegment;
+use graphics::stroke::Stroke;
+use graphics::transform::Transform;
+use image::RgbaImage;
+use image::codecs::png::PngEncoder;
+use image::{ColorType, ImageEncoder};
+
+use crate::canvas::{Canvas, Point, Polygon, color_rgba};
+
+// graphic item units are css pixels, 96 to the inch
+const UNITS_PER_INCH: f32 = 96.0;
+const RASTER_BORDER_MARGIN: f32 = 3.0;
+// longest straight piece of a flattened curve, in device pixels
+const FLATTEN_STEP: f32 = 1.0;
+// largest image png_bytes will allocate, 4 bytes each
+const MAX_PIXELS: f32 = 100_000_000.0;
+
+/// Draws graphic items into an RGBA image at the given resolution
+pub struct RasterBuilder {
+    dpi: f32,
+    transforms: Vec<Transform>,
+    canvas: Canvas,
+    fonts: Option<&'static FontContext<'static>>,
+    // painted under the drawing, transparent when none
+    background: Option<Color>,
+}
+
+struct Subpath {
+    points: Vec<Point>,
+    closed: bool,
+}
+
+impl RasterBuilder {
+    pub fn new(dpi: f32) -> RasterBuilder {
+        RasterBuilder {
+            dpi,
+            transforms: Vec::new(),
+            canvas: Canvas::new(0, 0, None),
+            fonts: None,
+            background: Some(Color::White),
+        }
+    }
+
+    pub fn with_background(mut self, background: Option<Color>) -> RasterBuilder {
+        self.background = background;
+        self
+    }
+
+    pub fn build(&mut self, items: GraphicItems) -> RgbaImage {
+        render_items(self, items, RASTER_BORDER_MARGIN)
+    }
+
+    pub fn png_bytes(&mut self, items: GraphicItems) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        if !(self.dpi.is_finite() && self.dpi > 0.0) {
+            return Err(format!("dpi must be positive, got {}", self.dpi).into());
+        }
+        let bbox = items_bounding_box(&items, self.text_metrics());
+        let scale = self.dpi / UNITS_PER_INCH;
+        let pixels = |size: f32| ((size + 2.0 * RASTER_BORDER_MARGIN) * scale).ceil().max(1.0);
+        let (width, height) = (pixels(bbox.2 - bbox.0), pixels(bbox.3 - bbox.1));
+        if width * height > MAX_PIXELS {
+            return Err(format!("image of {} x {} pixels is too large, lower the dpi", width, height).into());
+        }
+        let image = self.build(items);
+        let mut bytes = Vec::new();
+        PngEncoder::new(&mut bytes).write_image(image.as_raw(), image.width(), image.height(), ColorType::Rgba8)?;
+        Ok(bytes)
+    }
+
+    fn transform(&self) -> Transform {
+        self.transforms.last().copied().unwrap_or_default()
+    }
+
+    // device pixels per unit, for stroke widths and curve flattening
+    fn unit_scale(&self) -> f32 {
+        let t = self.transform();
+        (t.a * t.d - t.b * t.c).abs().sqrt()
+    }
+
+    fn fill_subpaths(&mut self, subpaths: &[Subpath], fill: &Fill) {
+        // gradients are painted in their first colour
+        if let Some(color) = fill.get_color() {
+            let polygons: Vec<Polygon> = subpaths.iter().map(|subpath| subpath.points.clone()).collect();
+            self.canvas.fill_polygons(&polygons, &color);
+        }
+    }
+
+    fn stroke_subpaths(&mut self, subpaths: &[Subpath], stroke: &Stroke) {
+        // dashes, caps and joins are drawn as a continuous line
+        if let Some((width, color, style)) = stroke.get_style() {
+            let half_width = width * self.unit_scale() / 2.0;
+            let mut polygons = Vec::new();
+            for subpath in subpaths {
+                stroke_polygons(subpath, half_width, &mut polygons);
+            }
+            let color = if style.opacity < 1.0 {
+                let (r, g, b, a) = color_rgba(&color);
+                Color::RGBA(r, g, b, a * style.opacity)
+            } else {
+                color
+            };
+            self.canvas.fill_polygons(&polygons, &color);
+        }
+    }
+
+    fn flatten(&self, segments: &[PathSegment], x: f32, y: f32) -> Vec<Subpath> {
+        let t = self.transform();
+        let point = |px: f32, py: f32| t.apply(px + x, py + y);
+        let mut subpaths: Vec<Subpath> = Vec::new();
+        let mut current = (0.0, 0.0);
+        for segment in segments {
+            match segment {
+                PathSegment::M(px, py) => {
+                    current = point(*px, *py);
+                    subpaths.push(Subpath { points: vec![current], closed: false });
+                    continue;
+                }
+                PathSegment::Z => {
+                    if let Some(subpath) = subpaths.last_mut() {
+                        subpath.closed = true;
+                        current = subpath.points[0];
+                        subpaths.push(Subpath { points: vec![current], closed: false });
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            if subpaths.is_empty() {
+                subpaths.push(Subpath { points: vec![current], closed: false });
+            }
+            let points = &mut subpaths.last_mut().unwrap().points;
+            match segment {
+                PathSegment::L(px, py) => points.push(point(*px, *py)),
+                PathSegment::Q(x1, y1, x2, y2) => {
+                    let (c1, end) = (point(*x1, *y1), point(*x2, *y2));
+                    let steps = curve_steps(&[current, c1, end]);
+                    for i in 1..=steps {
+                        let s = i as f32 / steps as f32;
+                        let r = 1.0 - s;
+                        points.push((r * r * current.0 + 2.0 * r * s * c1.0 + s * s * end.0, r * r * current.1 + 2.0 * r * s * c1.1 + s * s * end.1));
+                    }
+                }
+                PathSegment::C(x1, y1, x2, y2, x3, y3) => {
+                    let (c1, c2, end) = (point(*x1, *y1), point(*x2, *y2), point(*x3, *y3));
+                    let steps = curve_steps(&[current, c1, c2, end]);
+                    for i in 1..=steps {
+                        let s = i as f32 / steps as f32;
+                        let r = 1.0 - s;
+                        let (w0, w1, w2, w3) = (r * r * r, 3.0 * r * r * s, 3.0 * r * s * s, s * s * s);
+                        points.push((w0 * current.0 + w1 * c1.0 + w2 * c2.0 + w3 * end.0, w0 * current.1 + w1 * c1.1 + w2 * c2.1 + w3 * end.1));
+                    }
+                }
+                PathSegment::M(..) | PathSegment::Z => {}
+            }
+            current = *points.last().unwrap();
+        }
+        subpaths.retain(|subpath| subpath.points.len() > 1);
+        subpaths
+    }
+
+    fn ellipse_subpath(&self, cx: f32, cy: f32, rx: f32, ry: f32) -> Subpath {
+        let t = self.transform();
+        let steps = curve_steps(&[t.apply(cx + rx, cy), t.apply(cx, cy + ry), t.apply(cx - rx, cy), t.apply(cx, cy - ry), t.apply(cx + rx, cy)]).max(8);
+        let points = (0..steps)
+            .map(|i| {
+                let angle = 2.0 * PI * i as f32 / steps as f32;
+                t.apply(cx + rx * angle.cos(), cy + ry * angle.sin())
+            })
+            .collect();
+        Subpath { points, closed: true }
+    }
+}
+
+impl RenderBackend for RasterBuilder {
+    type Output = RgbaImage;
+
+    fn begin(&mut self, width: f32, height: f32) {
+        let scale = self.dpi / UNITS_PER_INCH;
+        let pixels = |size: f32| (size * scale).ceil().max(1.0) as u32;
+        self.canvas = Canvas::new(pixels(width), pixels(height), self.background.as_ref());
+        self.transforms = vec![Transform::scale(scale, scale)];
+    }
+
+    fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, stroke: &Stroke, _id: Option<&str>) {
+        let t = self.transform();
+        let subpath = Subpath {
+            points: vec![t.apply(x1, y1), t.apply(x2, y2)],
+            closed: false,
+        };
+        self.stroke_subpaths(&[subpath], stroke);
+    }
+
+    fn rect(&mut self, x: f32, y: f32, w: f32, h: f32, stroke: &Stroke, fill: &Fill, _id: Option<&str>) {
+        let t = self.transform();
+        let subpath = Subpath {
+            points: vec![t.apply(x, y), t.apply(x + w, y), t.apply(x + w, y + h), t.apply(x, y + h)],
+            closed: true,
+        };
+        self.fill_subpaths(std::slice::from_ref(&subpath), fill);
+        self.stroke_subpaths(&[subpath], stroke);
+    }
+
+    fn ellipse(&mut self, cx: f32, cy: f32, rx: f32, ry: f32, stroke: &Stroke, fill: &Fill, _id: Option<&str>) {
+        let subpath = self.ellipse_subpath(cx, cy, rx, ry);
+        self.fill_subpaths(std::slice::from_ref(&subpath), fill);
+        self.stroke_subpaths(&[subpath], stroke);
+    }
+
+    fn path(&mut self, segments: &[PathSegment], x: f32, y: f32, stroke: &Stroke, fill: &Fill, _cache: Option<&PathCache>, _id: Option<&str>) {
+        let subpaths = self.flatten(segments, x, y);
+        self.fill_subpaths(&subpaths, fill);
+        self.stroke_subpaths(&subpaths, stroke);
+    }
+
+    fn text(&mut self, x: f32, y: f32, text: &str, color: &Color, _id: Option<&str>) {
+        let fonts = *self.fonts.get_or_insert_with(FontContext::new);
+        // the segments hang from the top of the line, the text position is on the baseline
+        let top = y - fonts.get_sansserif_ascent(TEXT_SIZE);
+        let subpaths: Vec<Subpath> = fonts
+            .get_sansserif_string_segments(TEXT_SIZE, text)
+            .iter()
+            .flat_map(|segments| self.flatten(segments, x, top))
+            .collect();
+        self.fill_subpaths(&subpaths, &Fill::Solid(*color));
+    }
+
+    fn begin_group(&mut self, _id: Option<&str>, _class: Option<&str>, transform: &Transform) {
+        let transform = transform.then(&self.transform());
+        self.transforms.push(transform);
+    }
+
+    fn end_group(&mut self) {
+        if self.transforms.len() > 1 {
+            self.transforms.pop();
+        }
+    }
+
+    fn finish(&mut self) -> RgbaImage {
+        self.transforms.clear();
+        std::mem::replace(&mut self.canvas, Canvas::new(0, 0, None)).image
+    }
+
+    fn text_metrics(&mut self) -> &dyn TextMetrics {
+        *self.fonts.get_or_insert_with(FontContext::new)
+    }
+}
+
+// number of straight pieces for a curve, from the length of its control polygon
+fn curve_steps(points: &[Point]) -> usize {
+    let length: f32 = points.windows(2).map(|w| (w[1].0 - w[0].0).hypot(w[1].1 - w[0].1)).sum();
+    ((length / FLATTEN_STEP).ceil() as usize).clamp(1, 256)
+}
+
+// a quad per segment with round joins, all wound the same way so that overlaps add up under the nonzero rule
+fn stroke_polygons(subpath: &Subpath, half_width: f32, polygons: &mut Vec<Polygon>) {
+    let points = &subpath.points;
+    let count = if subpath.closed { points.len() } else { points.len() - 1 };
+    for i in 0..count {
+        let (x0, y0) = points[i];
+        let (x1, y1) = points[(i + 1) % points.len()];
+        let length = (x1 - x0).hypot(y1 - y0);
+        if length == 0.0 {
+            continue;
+        }
+        let (nx, ny) = (-(y1 - y0) / length * half_width, (x1 - x0) / length * half_width);
+        polygons.push(oriented(vec![(x0 + nx, y0 + ny), (x1 + nx, y1 + ny), (x1 - nx, y1 - ny), (x0 - nx, y0 - ny)]));
+    }
+    let joins = if subpath.closed { 0..points.len() } else { 1..points.len() - 1 };
+    for (x, y) in points[joins].iter() {
+        let steps = ((2.0 * PI * half_width / FLATTEN_STEP).ceil() as usize).clamp(8, 64);
+        polygons.push(oriented(
+            (0..steps)
+                .map(|i| {
+                    let angle = 2.0 * PI * i as f32 / steps as f32;
+                    (x + half_width * angle.cos(), y + half_width * angle.sin())
+                })
+                .collect(),
+        ));
+    }
+}
+
+fn oriented(mut polygon: Polygon) -> Polygon {
+    let area: f32 = (0..polygon.len())
+        .map(|i| {
+            let ((x0, y0), (x1, y1)) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+            x0 * y1 - x1 * y0
+        })
+        .sum();
+    if area < 0.0 {
+        polygon.reverse();
+    }
+    polygon
+}
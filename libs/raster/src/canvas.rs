@@ -0,0 +1,158 @@
+use graphics::color::Color;
+use image::{Rgba, RgbaImage};
+
+pub type Point = (f32, f32);
+pub type Polygon = Vec<Point>;
+
+// vertical samples per pixel row, horizontal coverage is exact
+const SUBSAMPLES: usize = 5;
+
+/// RGBA pixel buffer that polygons are filled into with anti-aliasing, using the nonzero winding rule
+pub struct Canvas {
+    pub image: RgbaImage,
+    coverage: Vec<f32>,
+}
+
+struct Edge {
+    x0: f32,
+    y0: f32,
+    x1: f32,
+    y1: f32,
+    winding: i32,
+}
+
+impl Canvas {
+    /// A canvas filled with the background colour, transparent without one
+    pub fn new(width: u32, height: u32, background: Option<&Color>) -> Canvas {
+        let pixel = match background.map(color_rgba) {
+            Some((r, g, b, a)) => Rgba([r, g, b, (a.clamp(0.0, 1.0) * 255.0).round() as u8]),
+            None => Rgba([0, 0, 0, 0]),
+        };
+        Canvas {
+            image: RgbaImage::from_pixel(width, height, pixel),
+            coverage: vec![0.0; width as usize + 1],
+        }
+    }
+
+    pub fn fill_polygons(&mut self, polygons: &[Polygon], color: &Color) {
+        let (width, height) = self.image.dimensions();
+        let mut edges = Vec::new();
+        let mut min_y = f32::MAX;
+        let mut max_y = f32::MIN;
+        for polygon in polygons {
+            for (i, &(x0, y0)) in polygon.iter().enumerate() {
+                let (x1, y1) = polygon[(i + 1) % polygon.len()];
+                if y0 == y1 || !(x0.is_finite() && y0.is_finite() && x1.is_finite() && y1.is_finite()) {
+                    continue;
+                }
+                min_y = min_y.min(y0).min(y1);
+                max_y = max_y.max(y0).max(y1);
+                edges.push(match y0 < y1 {
+                    true => Edge { x0, y0, x1, y1, winding: 1 },
+                    false => Edge {
+                        x0: x1,
+                        y0: y1,
+                        x1: x0,
+                        y1: y0,
+                        winding: -1,
+                    },
+                });
+            }
+        }
+        if edges.is_empty() {
+            return;
+        }
+
+        let row_top = (min_y.floor().max(0.0) as u32).min(height);
+        let row_bottom = (max_y.ceil().max(0.0) as u32).min(height);
+        let mut crossings: Vec<(f32, i32)> = Vec::new();
+        for row in row_top..row_bottom {
+            self.coverage.iter_mut().for_each(|c| *c = 0.0);
+            let mut span_left = width as usize;
+            let mut span_right = 0;
+            for sample in 0..SUBSAMPLES {
+                let y = row as f32 + (sample as f32 + 0.5) / SUBSAMPLES as f32;
+                crossings.clear();
+                for edge in edges.iter().filter(|edge| edge.y0 <= y && y < edge.y1) {
+                    crossings.push((edge.x0 + (y - edge.y0) * (edge.x1 - edge.x0) / (edge.y1 - edge.y0), edge.winding));
+                }
+                crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+                let mut winding = 0;
+                let mut start = 0.0;
+                for &(x, edge_winding) in crossings.iter() {
+                    if winding == 0 {
+                        start = x;
+                    }
+                    winding += edge_winding;
+                    if winding == 0
+                        && let Some((left, right)) = self.add_span(start, x, 1.0 / SUBSAMPLES as f32)
+                    {
+                        span_left = span_left.min(left);
+                        span_right = span_right.max(right);
+                    }
+                }
+            }
+            for col in span_left..span_right.min(width as usize) {
+                let alpha = self.coverage[col].min(1.0);
+                if alpha > 0.0 {
+                    blend(self.image.get_pixel_mut(col as u32, row), color, alpha);
+                }
+            }
+        }
+    }
+
+    // adds coverage for the horizontal span, returning the touched pixel range
+    fn add_span(&mut self, x0: f32, x1: f32, weight: f32) -> Option<(usize, usize)> {
+        let width = (self.coverage.len() - 1) as f32;
+        let (x0, x1) = (x0.clamp(0.0, width), x1.clamp(0.0, width));
+        if x1 <= x0 {
+            return None;
+        }
+        let (left, right) = (x0.floor() as usize, x1.floor() as usize);
+        if left == right {
+            self.coverage[left] += (x1 - x0) * weight;
+        } else {
+            self.coverage[left] += (left as f32 + 1.0 - x0) * weight;
+            for c in self.coverage[left + 1..right].iter_mut() {
+                *c += weight;
+            }
+            self.coverage[right] += (x1 - right as f32) * weight;
+        }
+        Some((left, right + 1))
+    }
+}
+
+pub fn color_rgba(color: &Color) -> (u8, u8, u8, f32) {
+    match color {
+        Color::Black => (0, 0, 0, 1.0),
+        Color::Gray => (128, 128, 128, 1.0),
+        Color::LightGray => (211, 211, 211, 1.0),
+        Color::White => (255, 255, 255, 1.0),
+        Color::Red => (255, 0, 0, 1.0),
+        Color::Blue => (0, 0, 255, 1.0),
+        Color::Green => (0, 128, 0, 1.0),
+        Color::Tomato => (255, 99, 71, 1.0),
+        Color::DodgerBlue => (30, 144, 255, 1.0),
+        Color::Orange => (255, 165, 0, 1.0),
+        Color::Yellow => (255, 255, 0, 1.0),
+        Color::Lime => (0, 255, 0, 1.0),
+        Color::Purple => (128, 0, 128, 1.0),
+        Color::RGBA(r, g, b, a) => (*r, *g, *b, a.clamp(0.0, 1.0)),
+    }
+}
+
+// source-over compositing of the color at the given coverage
+fn blend(pixel: &mut Rgba<u8>, color: &Color, coverage: f32) {
+    let (r, g, b, a) = color_rgba(color);
+    let src_alpha = a * coverage;
+    let dst_alpha = pixel[3] as f32 / 255.0;
+    let out_alpha = src_alpha + dst_alpha * (1.0 - src_alpha);
+    if out_alpha <= 0.0 {
+        return;
+    }
+    for (channel, src) in [r, g, b].into_iter().enumerate() {
+        let value = (src as f32 * src_alpha + pixel[channel] as f32 * dst_alpha * (1.0 - src_alpha)) / out_alpha;
+        pixel[channel] = value.round().clamp(0.0, 255.0) as u8;
+    }
+    pixel[3] = (out_alpha * 255.0).round() as u8;
+}
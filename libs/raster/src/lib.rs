@@ -0,0 +1,56 @@
+pub mod builder;
+pub mod canvas;
+
+#[cfg(test)]
+mod tests {
+    use graphics::{
+        color::Color,
+        fill::Fill,
+        graphicitem::{GraphicItem, GraphicItems},
+        path::PathSegment,
+        stroke::Stroke,
+        transform::Transform,
+    };
+
+    use crate::builder::RasterBuilder;
+
+    #[test]
+    fn test_raster() {
+        let items: GraphicItems = vec![
+            GraphicItem::Rect(0., 0., 20., 20., Stroke::None, Fill::Solid(Color::Black), None),
+            GraphicItem::Path(
+                vec![PathSegment::M(30., 0.), PathSegment::Q(40., 20., 50., 0.), PathSegment::Z],
+                0.,
+                0.,
+                Stroke::Solid(1., Color::Red),
+                Fill::None,
+                None,
+                None,
+            ),
+        ];
+        // 3 units of margin around 50 x 20 units, at twice the resolution
+        let image = RasterBuilder::new(192.).build(items.clone());
+        assert_eq!(image.dimensions(), (112, 52));
+        assert_eq!(image.get_pixel(26, 26).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(2, 2).0, [255, 255, 255, 255]);
+        // the rect edge falls on a pixel edge, the curve is anti-aliased
+        assert_eq!(image.get_pixel(5, 26).0, [255, 255, 255, 255]);
+        assert!(image.pixels().any(|pixel| pixel[0] == 255 && pixel[1] > 0 && pixel[1] < 255));
+
+        // a group moves its children
+        let grouped = vec![items[0].clone(), GraphicItem::Group(vec![items[0].clone()], Transform::translate(40., 0.), None, None)];
+        let image = RasterBuilder::new(96.).build(grouped);
+        assert_eq!(image.dimensions(), (66, 26));
+        assert_eq!(image.get_pixel(48, 13).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(33, 13).0, [255, 255, 255, 255]);
+
+        let bytes = RasterBuilder::new(96.).png_bytes(items.clone()).unwrap();
+        assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+
+        // resolutions that give no image or one too large to allocate are errors
+        assert!(RasterBuilder::new(0.).png_bytes(items.clone()).is_err());
+        assert!(RasterBuilder::new(-96.).png_bytes(items.clone()).is_err());
+        assert!(RasterBuilder::new(f32::NAN).png_bytes(items.clone()).is_err());
+        assert!(RasterBuilder::new(1_000_000.).png_bytes(items).is_err());
+    }
+}
@@ -1,18 +1,33 @@
-use crate::items::get_graphic_items_from_glyph;
+use crate::items::{get_graphic_items_from_glyph, get_music_glyph_item};
+use core::{
+    barline::BarlineType,
+    context::CoreContext,
+    group::{StaffGroup, StaffGroupType},
+    hpart::HPartItemsColumnType,
+    repeat::NavigationMark,
+};
+use fonts::fontcontext::FontContext;
 use graphics::{
     color::Color,
     fill::Fill,
-    graphicitem::{GraphicItem, GraphicItems},
+    graphicitem::{GraphicItem, GraphicItems, TEXT_SIZE, items_move},
+    path::PathSegment::{C, L, M, Q, Z},
     stroke::Stroke,
+    theme::Theme,
 };
 use grid::{gridcontext::GridContext, griditem::GridItemType};
-use score::{constants::SPACE, glyphitem::GlyphItem};
+use score::{
+    constants::{BRACE_WIDTH, BRACKET_THICK, MULTIREST_THICK, NAVIGATION_DISTANCE, PART_NAME_GAP, PART_NAME_SIZE, SPACE, SPACE_HALF, SPACE2, SPACE3, STAFF_GROUP_GAP, VOLTA_DISTANCE, VOLTA_HEIGHT},
+    glyphitem::GlyphItem,
+    scorecontext::ScoreContext,
+};
 use svg::builder::SvgBuilder;
 
 pub struct Render;
 impl Render {
-    pub fn render_notelines(gcx: &'static GridContext<GlyphItem>) -> GraphicItems {
+    pub fn render_notelines(scx: &ScoreContext, gcx: &'static GridContext<GlyphItem>, theme: &Theme) -> GraphicItems {
         let mut graphic_items = GraphicItems::new();
+        let staff_line = scx.glyph_widths.borrow().staff_line;
         let cx_rows = &gcx.rows.borrow();
         let cols_widths = &gcx.cols_widths.borrow();
         let row_heights = &gcx.rows_heights.borrow();
@@ -21,11 +36,11 @@ impl Render {
         for (row_idx, _row) in cx_rows.iter().enumerate() {
             move_y += row_heights[row_idx];
             let mut left_x = cols_widths[0];
-            for (_colidx, widths) in cols_widths.windows(2).enumerate() {
+            for widths in cols_widths.windows(2) {
                 let width = widths[1];
                 for i in -2..=2 {
                     let line_y = move_y + SPACE * i as f32;
-                    graphic_items.push(GraphicItem::Line(left_x, line_y, left_x + width, line_y, Stroke::Solid(1.0, Color::Black), None));
+                    graphic_items.push(GraphicItem::Line(left_x, line_y, left_x + width, line_y, Stroke::Solid(staff_line, theme.staff_lines), None));
                 }
                 left_x += width;
             }
@@ -33,11 +48,269 @@ impl Render {
         graphic_items
     }
 
-    pub fn render_music_glyphitems(gcx: &'static GridContext<GlyphItem>) -> GraphicItems {
+    /// Volta brackets and navigation marks over the top staff
+    pub fn render_repeats(cx: &CoreContext, scx: &ScoreContext, gcx: &'static GridContext<GlyphItem>, fcx: &FontContext, theme: &Theme) -> GraphicItems {
+        let mut graphic_items = GraphicItems::new();
+        let cols_widths = &gcx.cols_widths.borrow();
+        let row_heights = &gcx.rows_heights.borrow();
+        let Some(top_y) = row_heights.first().map(|height| height - SPACE2) else {
+            return graphic_items;
+        };
+        let map_colidx_gridcolidx = scx.map_colidx_gridcolidx.borrow();
+        let col_x = |col_idx: usize| {
+            let grid_col_idx = map_colidx_gridcolidx.get(&col_idx).cloned().unwrap_or(cols_widths.len());
+            cols_widths.iter().take(grid_col_idx + 1).sum::<f32>()
+        };
+        let stroke = Stroke::Solid(1.0, theme.foreground);
+
+        for volta in cx.voltas.borrow().iter() {
+            let left_x = col_x(volta.from_col_idx);
+            let right_x = col_x(volta.to_col_idx.saturating_sub(1)).max(left_x + SPACE2);
+            let line_y = top_y - VOLTA_DISTANCE - VOLTA_HEIGHT;
+            graphic_items.push(GraphicItem::Line(left_x, line_y, right_x, line_y, stroke.clone(), None));
+            graphic_items.push(GraphicItem::Line(left_x, line_y, left_x, line_y + VOLTA_HEIGHT, stroke.clone(), None));
+            if volta.closed {
+                graphic_items.push(GraphicItem::Line(right_x, line_y, right_x, line_y + VOLTA_HEIGHT, stroke.clone(), None));
+            }
+            graphic_items.push(GraphicItem::Text(left_x + SPACE_HALF, line_y + SPACE * 1.5, volta.get_label(), theme.foreground, None));
+        }
+
+        // words as text and the segno and coda signs as music glyphs, one after the other on the same baseline
+        let line_y = top_y - NAVIGATION_DISTANCE;
+        for (col_idx, marks) in cx.map_colidx_navigations.borrow().iter() {
+            let mut x = col_x(*col_idx);
+            for mark in marks {
+                if let Some(label) = mark.get_label() {
+                    graphic_items.push(GraphicItem::Text(x, line_y, label.to_string(), theme.foreground, None));
+                    let rect = fcx.get_sansserif_text_rectangle(TEXT_SIZE, label);
+                    x += rect.0 + rect.2 + SPACE_HALF;
+                }
+                let name = match mark {
+                    NavigationMark::Segno => "segno",
+                    NavigationMark::Coda | NavigationMark::ToCoda => "coda",
+                    _ => continue,
+                };
+                match fcx.get_music_glyph_segments(name, SPACE) {
+                    Some(segments) => {
+                        graphic_items.push(GraphicItem::Path(segments, x, line_y, Stroke::None, Fill::Solid(theme.foreground), None, None));
+                        x += fcx.get_music_glyph_width(name, SPACE).unwrap_or(SPACE3) + SPACE_HALF;
+                    }
+                    None => {
+                        // the built-in font has no coda: a ring crossed by two lines
+                        let (center_x, center_y) = (x + SPACE * 1.5, line_y - SPACE * 1.5);
+                        let stroke = Stroke::Solid(2.0, theme.foreground);
+                        graphic_items.push(GraphicItem::Ellipse(center_x, center_y, SPACE, SPACE * 1.2, stroke.clone(), Fill::None, None));
+                        graphic_items.push(GraphicItem::Line(center_x, line_y - SPACE3, center_x, line_y, stroke.clone(), None));
+                        graphic_items.push(GraphicItem::Line(x, center_y, x + SPACE3, center_y, stroke, None));
+                        x += SPACE3 + SPACE_HALF;
+                    }
+                }
+            }
+        }
+
+        graphic_items
+    }
+
+    /// System start line, braces and brackets at the system start, and barlines drawn through grouped staves
+    pub fn render_staff_groups(cx: &CoreContext, scx: &ScoreContext, gcx: &'static GridContext<GlyphItem>, fcx: &FontContext, theme: &Theme) -> GraphicItems {
+        let mut graphic_items = GraphicItems::new();
+        let cx_rows = &gcx.rows.borrow();
+        let cols_widths = &gcx.cols_widths.borrow();
+        let row_heights = &gcx.rows_heights.borrow();
+        let groups = cx.staff_groups.borrow();
+        let widths = scx.glyph_widths.borrow();
+        let thin = widths.barline_thin;
+        if cx_rows.len() < 2 {
+            return graphic_items;
+        }
+
+        let rows_y = get_rows_y(row_heights);
+        let staff_top = |part_idx: usize| rows_y[part_idx.min(rows_y.len() - 1)] - SPACE2;
+        let staff_bottom = |part_idx: usize| rows_y[part_idx.min(rows_y.len() - 1)] + SPACE2;
+        let solid = |x: f32, y: f32, w: f32, h: f32| GraphicItem::Rect(x, y, w, h, Stroke::None, Fill::Solid(theme.foreground), None);
+
+        let left_x = cols_widths[0];
+        graphic_items.push(solid(left_x, staff_top(0), thin, staff_bottom(cx_rows.len() - 1) - staff_top(0)));
+
+        for group in groups.iter() {
+            let (top, bottom) = (staff_top(group.from_part_idx), staff_bottom(group.to_part_idx));
+            match group.gtype {
+                StaffGroupType::Brace => {
+                    let (x, w, h, t) = (left_x - STAFF_GROUP_GAP - BRACE_WIDTH, BRACE_WIDTH, bottom - top, BRACE_WIDTH * 0.3);
+                    let segments = vec![
+                        M(x + w, top),
+                        C(x + w * 0.3, top + h * 0.05, x + w * 0.7, top + h * 0.45, x, top + h * 0.5),
+                        C(x + w * 0.7, top + h * 0.55, x + w * 0.3, top + h * 0.95, x + w, bottom),
+                        C(x + w * 0.3 + t, top + h * 0.95, x + w * 0.7 + t, top + h * 0.55, x, top + h * 0.5),
+                        C(x + w * 0.7 + t, top + h * 0.45, x + w * 0.3 + t, top + h * 0.05, x + w, top),
+                        Z,
+                    ];
+                    graphic_items.push(GraphicItem::Path(segments, 0.0, 0.0, Stroke::None, Fill::Solid(theme.foreground), None, None));
+                }
+                StaffGroupType::Bracket => {
+                    let x = left_x - STAFF_GROUP_GAP * 2.0 - BRACKET_THICK;
+                    graphic_items.push(solid(x, top - SPACE_HALF, BRACKET_THICK, bottom - top + SPACE));
+                    // hooks curving away from the staves at both ends
+                    for (end_y, dir) in [(top - SPACE_HALF, -1.0), (bottom + SPACE_HALF, 1.0)] {
+                        let segments = vec![
+                            M(x, end_y),
+                            Q(x + SPACE * 1.2, end_y, x + SPACE * 1.8, end_y + SPACE * dir),
+                            Q(x + SPACE * 1.2, end_y - SPACE_HALF * dir, x + BRACKET_THICK, end_y - SPACE_HALF * dir),
+                            L(x, end_y - SPACE_HALF * dir),
+                            Z,
+                        ];
+                        graphic_items.push(GraphicItem::Path(segments, 0.0, 0.0, Stroke::None, Fill::Solid(theme.foreground), None, None));
+                    }
+                }
+                StaffGroupType::SubBracket => {
+                    let x = left_x - STAFF_GROUP_GAP;
+                    graphic_items.push(solid(x, top, thin, bottom - top));
+                    graphic_items.push(solid(x, top, SPACE_HALF, thin));
+                    graphic_items.push(solid(x, bottom - thin, SPACE_HALF, thin));
+                }
+            }
+        }
+
+        // barlines through the gap below each grouped staff, without repeat dots
+        for (part_idx, row) in cx_rows.iter().enumerate().take(cx_rows.len() - 1) {
+            if !StaffGroup::connects(&groups, part_idx) {
+                continue;
+            }
+            let (gap_top, gap_bottom) = (staff_bottom(part_idx), staff_top(part_idx + 1));
+            let mut move_x = 0.0;
+            for (colidx, item_id) in row.item_ids.iter().enumerate() {
+                move_x += cols_widths[colidx];
+                let item = &gcx.items.borrow()[*item_id];
+                let GridItemType::Rectangles(ref glyph_items) = item.gitype else { continue };
+                for (rect, glyph_item) in glyph_items.iter() {
+                    if let GlyphItem::Barline(btype) = glyph_item.untagged()
+                        && !matches!(btype, BarlineType::Tick | BarlineType::Dotted | BarlineType::Invisible)
+                    {
+                        let gap_rect = (rect.0, gap_top, rect.2, gap_bottom - gap_top);
+                        let items = get_graphic_items_from_glyph(fcx, &widths, theme, move_x, 0.0, &gap_rect, glyph_item);
+                        graphic_items.extend(items.into_iter().filter(|item| !matches!(item, GraphicItem::Ellipse(..))));
+                    }
+                }
+            }
+        }
+
+        graphic_items
+    }
+
+    /// Part names right aligned before the brackets and braces, full names on the first system and abbreviations on later ones.
+    /// The first part of a braced group is named once for the whole group.
+    pub fn render_part_names(cx: &CoreContext, gcx: &'static GridContext<GlyphItem>, fcx: &FontContext, theme: &Theme, first_system: bool) -> GraphicItems {
+        let mut graphic_items = GraphicItems::new();
+        let cols_widths = &gcx.cols_widths.borrow();
+        let row_heights = &gcx.rows_heights.borrow();
+        let groups = cx.staff_groups.borrow();
+        let Some(left_x) = cols_widths.first() else {
+            return graphic_items;
+        };
+        let rows_y = get_rows_y(row_heights);
+
+        let groups_width = groups
+            .iter()
+            .map(|group| match group.gtype {
+                StaffGroupType::Brace => STAFF_GROUP_GAP + BRACE_WIDTH,
+                StaffGroupType::Bracket => STAFF_GROUP_GAP * 2.0 + BRACKET_THICK,
+                StaffGroupType::SubBracket => STAFF_GROUP_GAP,
+            })
+            .fold(0.0, f32::max);
+        let right_x = left_x - groups_width - PART_NAME_GAP;
+
+        for (part_idx, header) in cx.map_partidx_header.borrow().iter() {
+            let name = if first_system { &header.name } else { &header.short_name };
+            let Some(mut center_y) = rows_y.get(*part_idx).cloned() else { continue };
+            if name.is_empty() {
+                continue;
+            }
+            if let Some(group) = groups.iter().find(|group| group.gtype == StaffGroupType::Brace && group.from_part_idx == *part_idx) {
+                center_y = (center_y + rows_y[group.to_part_idx.min(rows_y.len() - 1)]) / 2.0;
+            }
+
+            let (width, height) = fcx.get_sansserif_string_dimensions(PART_NAME_SIZE, name);
+            let items = fcx
+                .get_sansserif_string_items(PART_NAME_SIZE, name)
+                .into_iter()
+                .map(|item| match item {
+                    GraphicItem::Path(segments, x, y, stroke, _, cache, id) => GraphicItem::Path(segments, x, y, stroke, Fill::Solid(theme.foreground), cache, id),
+                    item => item,
+                })
+                .collect::<GraphicItems>();
+            graphic_items.extend(items_move(items, right_x - width, center_y - height / 2.0));
+        }
+
+        graphic_items
+    }
+
+    /// Whole rests centred in bars without notes, and multi-measure rests with their bar count
+    pub fn render_bar_rests(cx: &CoreContext, scx: &ScoreContext, gcx: &'static GridContext<GlyphItem>, fcx: &FontContext, theme: &Theme) -> GraphicItems {
+        let mut graphic_items = GraphicItems::new();
+        let cols_widths = &gcx.cols_widths.borrow();
+        let rows_y = get_rows_y(&gcx.rows_heights.borrow());
+        let map_colidx_gridcolidx = scx.map_colidx_gridcolidx.borrow();
+        let map_colidx_multirest = scx.map_colidx_multirest.borrow();
+        let thin = scx.glyph_widths.borrow().barline_thin;
+        let rest_whole = scx.glyph_widths.borrow().rest_whole;
+        let hparts = cx.hparts.borrow();
+        let col_x = |col_idx: usize| {
+            let grid_col_idx = map_colidx_gridcolidx.get(&col_idx).cloned().unwrap_or(cols_widths.len());
+            cols_widths.iter().take(grid_col_idx + 1).sum::<f32>()
+        };
+        let solid = |x: f32, y: f32, w: f32, h: f32| GraphicItem::Rect(x, y, w, h, Stroke::None, Fill::Solid(theme.foreground), None);
+
+        // bars condensed into a multi-measure rest
+        let condensed = map_colidx_multirest.iter().flat_map(|(col_idx, bars)| col_idx + 1..col_idx + bars * 2 - 1).collect::<Vec<_>>();
+
+        for (col_idx, column) in cx.columns.borrow().iter().enumerate() {
+            let HPartItemsColumnType::Musics(ids) = &column.hptype else { continue };
+            if condensed.contains(&col_idx) {
+                continue;
+            }
+            let (left_x, right_x) = (col_x(col_idx), col_x(col_idx + 1));
+            for (part_idx, id) in ids.iter().enumerate() {
+                let Some(move_y) = rows_y.get(part_idx).cloned() else { continue };
+                if !hparts[*id].hptype.is_bar_rest() {
+                    continue;
+                }
+                match map_colidx_multirest.get(&col_idx) {
+                    Some(bars) => {
+                        // H-bar between serifs on the middle line, with the count above the staff
+                        let (left_x, right_x) = (left_x + SPACE, right_x - SPACE);
+                        graphic_items.push(solid(left_x, move_y - MULTIREST_THICK / 2.0, right_x - left_x, MULTIREST_THICK));
+                        graphic_items.push(solid(left_x, move_y - SPACE, thin, SPACE2));
+                        graphic_items.push(solid(right_x - thin, move_y - SPACE, thin, SPACE2));
+                        let label = bars.to_string();
+                        graphic_items.push(GraphicItem::Text(
+                            (left_x + right_x) / 2.0 - label.len() as f32 * SPACE * 0.3,
+                            move_y - SPACE * 3.0,
+                            label,
+                            theme.foreground,
+                            None,
+                        ));
+                    }
+                    None => {
+                        // whole rest hanging from the second line from the top
+                        let rest_x = (left_x + right_x - rest_whole) / 2.0;
+                        match get_music_glyph_item(fcx, theme.foreground, "restWhole", rest_x, move_y - SPACE, 1.0, None) {
+                            Some(item) => graphic_items.push(item),
+                            None => graphic_items.push(solid(rest_x, move_y - SPACE, rest_whole, SPACE_HALF)),
+                        }
+                    }
+                }
+            }
+        }
+
+        graphic_items
+    }
+
+    pub fn render_music_glyphitems(scx: &ScoreContext, gcx: &'static GridContext<GlyphItem>, fcx: &FontContext, theme: &Theme) -> GraphicItems {
         let mut graphic_items = GraphicItems::new();
         let cx_rows = &gcx.rows.borrow();
         let cx_cols_overlaps = &gcx.cols_widths.borrow();
         let row_heights = &gcx.rows_heights.borrow();
+        let widths = scx.glyph_widths.borrow();
 
         let mut move_y = 0.0;
 
@@ -50,8 +323,11 @@ impl Render {
                 let item = &gcx.items.borrow()[*item_id];
                 match item.gitype {
                     GridItemType::Rectangles(ref glyph_items) => {
-                        for (rect, glyph_item) in glyph_items.iter() {
-                            graphic_items.extend(get_graphic_items_from_glyph(move_x, move_y, &rect, &glyph_item));
+                        // the glyphs of each note are drawn one after the other, so that they can be grouped
+                        let mut glyph_items: Vec<_> = glyph_items.iter().collect();
+                        glyph_items.sort_by_key(|(_, glyph_item)| glyph_item.get_tag().and_then(|tag| tag.note_id));
+                        for (rect, glyph_item) in glyph_items {
+                            graphic_items.extend(get_graphic_items_from_glyph(fcx, &widths, theme, move_x, move_y, rect, glyph_item));
                         }
                     }
                     GridItemType::Empty => {
@@ -69,11 +345,12 @@ impl Render {
         graphic_items
     }
 
-    pub fn render_music_stembeams(gcx: &'static GridContext<GlyphItem>) -> GraphicItems {
+    pub fn render_music_stembeams(scx: &ScoreContext, gcx: &'static GridContext<GlyphItem>, fcx: &FontContext, theme: &Theme) -> GraphicItems {
         let mut graphic_items = GraphicItems::new();
         let cx_rows = &gcx.rows.borrow();
         let cx_cols_overlaps = &gcx.cols_widths.borrow();
         let row_heights = &gcx.rows_heights.borrow();
+        let widths = scx.glyph_widths.borrow();
 
         let mut move_y = 0.0;
 
@@ -87,7 +364,7 @@ impl Render {
                 match item.gitype {
                     GridItemType::Rectangles(ref glyph_items) => {
                         for (rect, glyph_item) in glyph_items.iter() {
-                            graphic_items.extend(get_graphic_items_from_glyph(move_x, move_y, &rect, &glyph_item));
+                            graphic_items.extend(get_graphic_items_from_glyph(fcx, &widths, theme, move_x, move_y, rect, glyph_item));
                         }
                     }
                     GridItemType::Empty => {
@@ -127,16 +404,26 @@ impl Render {
         }
 
         let mut move_x = 0.0;
-        for (_colidx, overlap) in cx_cols_overlaps.iter().enumerate() {
-            move_x = move_x + *overlap;
+        for overlap in cx_cols_overlaps.iter() {
+            move_x += *overlap;
             graphic_items.push(GraphicItem::Line(move_x - 0.1, 0.0, move_x, move_y, Stroke::Solid(0.2, Color::RGBA(0, 0, 0, 0.2)), None));
         }
 
         // let graphic_items = items_scale(graphic_items, 3.0, 3.0);
 
         let mut svg = SvgBuilder::new();
-        let svg_string = svg.build(graphic_items, None);
 
-        svg_string
+        svg.build(graphic_items, None)
     }
 }
+
+/// Middle line y of each row
+fn get_rows_y(row_heights: &[f32]) -> Vec<f32> {
+    row_heights
+        .iter()
+        .scan(0.0, |move_y, height| {
+            *move_y += height;
+            Some(*move_y)
+        })
+        .collect()
+}
@@ -0,0 +1,147 @@
+use core::{context::CoreContext, head::HeadId, note::NoteId};
+use graphics::rectangle::Rectangle;
+use grid::{gridcontext::GridContext, griditem::GridItemType};
+use score::{constants::SPACE, glyphitem::GlyphItem};
+
+// how far above or below a staff middle line a point still belongs to the staff
+const HIT_ROW_DISTANCE: f32 = SPACE * 6.0;
+
+/// The musical objects found under a point of the output
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Hit {
+    pub part_idx: usize,
+    pub bar_idx: Option<usize>,
+    pub position: Option<usize>,
+    pub note_id: Option<NoteId>,
+    pub head_id: Option<HeadId>,
+}
+
+/// Spatial index from output coordinates back to parts, bars, notes and heads, built from the laid out grid
+#[derive(Debug, Clone)]
+pub struct HitIndex {
+    // origins of the grid columns and rows, in output coordinates
+    cols_x: Vec<f32>,
+    rows_y: Vec<f32>,
+    // rectangles of the tagged glyphs, in output coordinates
+    entries: Vec<(Rectangle, Hit)>,
+    // the entries overlapping each grid column
+    cols_entries: Vec<Vec<usize>>,
+    // bar and first note position of each grid column
+    cols_hits: Vec<(Option<usize>, Option<usize>)>,
+}
+
+impl HitIndex {
+    /// `offset` is the move from grid coordinates to output coordinates
+    pub fn new(cx: &CoreContext, gcx: &GridContext<GlyphItem>, offset: (f32, f32)) -> HitIndex {
+        let cols_widths = gcx.cols_widths.borrow();
+        let rows_heights = gcx.rows_heights.borrow();
+        let origins = |sizes: &[f32], offset: f32| -> Vec<f32> {
+            sizes
+                .iter()
+                .scan(offset, |origin, size| {
+                    *origin += size;
+                    Some(*origin)
+                })
+                .collect()
+        };
+        let cols_x = origins(&cols_widths, offset.0);
+        let rows_y = origins(&rows_heights, offset.1);
+
+        let notes = cx.notes.borrow();
+        let mut entries: Vec<(Rectangle, Hit)> = Vec::new();
+        let mut cols_hits: Vec<(Option<usize>, Option<usize>)> = vec![(None, None); cols_x.len()];
+        for (row_idx, row) in gcx.rows.borrow().iter().enumerate() {
+            for (colidx, item_id) in row.item_ids.iter().enumerate() {
+                let GridItemType::Rectangles(ref glyph_items) = gcx.items.borrow()[*item_id].gitype else {
+                    continue;
+                };
+                for (rect, glyph_item) in glyph_items.iter() {
+                    let Some(tag) = glyph_item.get_tag() else {
+                        continue;
+                    };
+                    let hit = Hit {
+                        part_idx: tag.part_idx.unwrap_or(row_idx),
+                        bar_idx: tag.bar_idx,
+                        position: tag.note_id.and_then(|note_id| notes.get(note_id)).map(|note| note.position),
+                        note_id: tag.note_id,
+                        head_id: tag.head_id,
+                    };
+                    let col_hit = &mut cols_hits[colidx];
+                    col_hit.0 = col_hit.0.or(hit.bar_idx);
+                    col_hit.1 = col_hit.1.or(hit.position);
+                    entries.push(((rect.0 + cols_x[colidx], rect.1 + rows_y[row_idx], rect.2, rect.3), hit));
+                }
+            }
+        }
+
+        let mut cols_entries: Vec<Vec<usize>> = vec![Vec::new(); cols_x.len()];
+        for (entry_idx, (rect, _)) in entries.iter().enumerate() {
+            let (first, last) = (column_at(&cols_x, rect.0).unwrap_or(0), column_at(&cols_x, rect.0 + rect.2).unwrap_or(0));
+            for col_entries in cols_entries[first..=last].iter_mut() {
+                col_entries.push(entry_idx);
+            }
+        }
+
+        HitIndex {
+            cols_x,
+            rows_y,
+            entries,
+            cols_entries,
+            cols_hits,
+        }
+    }
+
+    /// The smallest tagged glyph under the point, or else the part and bar of the staff around it
+    pub fn hit(&self, x: f32, y: f32) -> Option<Hit> {
+        let colidx = column_at(&self.cols_x, x)?;
+        let contains = |rect: &Rectangle| x >= rect.0 && x <= rect.0 + rect.2 && y >= rect.1 && y <= rect.1 + rect.3;
+        let glyph_hit = self.cols_entries[colidx]
+            .iter()
+            .map(|entry_idx| &self.entries[*entry_idx])
+            .filter(|(rect, _)| contains(rect))
+            .min_by(|(a, _), (b, _)| (a.2 * a.3).total_cmp(&(b.2 * b.3)));
+        if let Some((_, hit)) = glyph_hit {
+            return Some(hit.clone());
+        }
+
+        let (row_idx, row_y) = self.rows_y.iter().enumerate().min_by(|(_, a), (_, b)| (*a - y).abs().total_cmp(&(*b - y).abs()))?;
+        if (row_y - y).abs() > HIT_ROW_DISTANCE {
+            return None;
+        }
+        let (bar_idx, position) = self.cols_hits[colidx];
+        Some(Hit {
+            part_idx: row_idx,
+            bar_idx,
+            position,
+            ..Default::default()
+        })
+    }
+}
+
+// the last column starting at or before x
+fn column_at(cols_x: &[f32], x: f32) -> Option<usize> {
+    cols_x.partition_point(|col_x| *col_x <= x).checked_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::output::Generate;
+
+    #[test]
+    fn test_hit() {
+        let (_, hits) = Generate::svg_string_with_hits("clef G | 0 1 | bl | 2,4 3", &Default::default()).unwrap();
+        // the centre of every tagged head finds the head back
+        let heads: Vec<_> = hits.entries.iter().filter(|(_, hit)| hit.head_id.is_some()).collect();
+        assert_eq!(heads.len(), 5);
+        for (rect, hit) in heads {
+            assert_eq!(hits.hit(rect.0 + rect.2 / 2.0, rect.1 + rect.3 / 2.0).as_ref(), Some(hit));
+        }
+        let chord = hits.entries.iter().find(|(_, hit)| hit.note_id == Some(2)).unwrap();
+        assert_eq!(chord.1.bar_idx, Some(1));
+        assert_eq!(chord.1.position, Some(0));
+
+        // far outside the score there is nothing
+        assert_eq!(hits.hit(-100.0, -100.0), None);
+        assert_eq!(hits.hit(hits.cols_x[1], 1000.0), None);
+    }
+}
@@ -1,140 +1,198 @@
-use core::{accidental::Accidental, clef::ClefSignature, head::HeadType, rest::RestType};
+use core::{accidental::Accidental, barline::BarlineType, clef::ClefSignature, head::HeadType, note::NoteColor, rest::RestType};
 
+use fonts::fontcontext::FontContext;
 use graphics::{
     color::Color,
     fill::Fill,
     graphicitem::{GraphicItem, PathCache},
     rectangle::Rectangle,
     stroke::Stroke,
+    theme::Theme,
 };
 use score::{
-    constants::{SPACE, SPACE2, SPACE3, SPACE4, SPACE_HALF},
-    glyphitem::GlyphItem,
+    constants::{BARLINE_DASH, BARLINE_REPEAT_DOT, CLEF_CHANGE_SCALE, SPACE, SPACE_HALF, SPACE2},
+    glyphitem::{GlyphItem, GlyphTag},
+    scorecontext::GlyphWidths,
 };
 
-use crate::music_glyphs::{
-    GLYPH_ACCIDENTAL_FLAT, GLYPH_ACCIDENTAL_NATURAL, GLYPH_ACCIDENTAL_SHARP, GLYPH_CLEF_BASS, GLYPH_CLEF_TREBLE, GLYPH_FLAG_8TH_UP, GLYPH_NOTEHEAD_WHITE, GLYPH_NOTEHEAD_WHOLE, GLYPH_REST_EIGHTH,
-    GLYPH_REST_HALF, GLYPH_REST_QUARTER, GLYPH_REST_SIXTEENTH,
-};
-use crate::music_glyphs::{GLYPH_FLAG_8TH_DOWN, GLYPH_NOTEHEAD_BLACK};
+pub fn get_graphic_items_from_glyph(fcx: &FontContext, widths: &GlyphWidths, theme: &Theme, movex: f32, movey: f32, rect: &Rectangle, glyph: &GlyphItem) -> Vec<GraphicItem> {
+    get_glyph_items(fcx, widths, theme, theme.foreground, movex, movey, rect, glyph)
+}
 
-pub fn get_graphic_items_from_glyph(movex: f32, movey: f32, rect: &Rectangle, glyph: &GlyphItem) -> Vec<GraphicItem> {
-    let y_zero = -SPACE2 * 1.0;
+/// Colour of a note marked in the input
+pub fn get_note_color(theme: &Theme, color: &NoteColor) -> Color {
+    match color.get_rgb() {
+        Some((r, g, b)) => Color::RGBA(r, g, b, 1.0),
+        None => theme.highlight,
+    }
+}
 
+// the items of a glyph in `color`, unless the glyph is tagged with a note colour
+#[allow(clippy::too_many_arguments)]
+fn get_glyph_items(fcx: &FontContext, widths: &GlyphWidths, theme: &Theme, color: Color, movex: f32, movey: f32, rect: &Rectangle, glyph: &GlyphItem) -> Vec<GraphicItem> {
     let mut graphic_items = Vec::new();
 
     match glyph {
         GlyphItem::XRect(color) => {
             graphic_items.push(GraphicItem::Rect(rect.0 + movex, rect.1 + movey, rect.2, rect.3, Stroke::None, Fill::Solid(*color), None));
         }
-        GlyphItem::Barline(_btype) => {
-            graphic_items.push(GraphicItem::Rect(rect.0 + movex, rect.1 + movey, rect.2, rect.3, Stroke::None, Fill::Solid(Color::Black), None));
+        GlyphItem::Barline(btype) => {
+            let (x, y, w, h) = (rect.0 + movex, rect.1 + movey, rect.2, rect.3);
+            let (thin, thick, gap) = (widths.barline_thin, widths.barline_thick, widths.barline_gap);
+            let line = |x: f32, y: f32, w: f32, h: f32| GraphicItem::Rect(x, y, w, h, Stroke::None, Fill::Solid(color), None);
+            // dots in the two middle spaces of the staff
+            let repeat_dots = |center_x: f32, top_y: f32| {
+                [SPACE * 1.5, SPACE * 2.5].map(|dot_y| GraphicItem::Ellipse(center_x, top_y + dot_y, BARLINE_REPEAT_DOT, BARLINE_REPEAT_DOT, Stroke::None, Fill::Solid(color), None))
+            };
+            match btype {
+                BarlineType::Invisible => {}
+                BarlineType::Single | BarlineType::Tick => graphic_items.push(line(x, y, thin, h)),
+                BarlineType::Heavy => graphic_items.push(line(x, y, thick, h)),
+                BarlineType::Double => {
+                    graphic_items.push(line(x, y, thin, h));
+                    graphic_items.push(line(x + w - thin, y, thin, h));
+                }
+                BarlineType::Final => {
+                    graphic_items.push(line(x, y, thin, h));
+                    graphic_items.push(line(x + w - thick, y, thick, h));
+                }
+                BarlineType::Dashed => {
+                    let mut dash_y = y;
+                    while dash_y < y + h {
+                        graphic_items.push(line(x, dash_y, thin, BARLINE_DASH.min(y + h - dash_y)));
+                        dash_y += BARLINE_DASH * 2.0;
+                    }
+                }
+                BarlineType::RepeatStart => {
+                    graphic_items.push(line(x, y, thick, h));
+                    graphic_items.push(line(x + thick + gap, y, thin, h));
+                    graphic_items.extend(repeat_dots(x + w - BARLINE_REPEAT_DOT, y));
+                }
+                BarlineType::RepeatEnd => {
+                    graphic_items.extend(repeat_dots(x + BARLINE_REPEAT_DOT, y));
+                    graphic_items.push(line(x + w - thick - gap - thin, y, thin, h));
+                    graphic_items.push(line(x + w - thick, y, thick, h));
+                }
+                BarlineType::RepeatBoth => {
+                    let center_x = x + w / 2.0;
+                    graphic_items.extend(repeat_dots(x + BARLINE_REPEAT_DOT, y));
+                    graphic_items.push(line(center_x - thick / 2.0 - gap - thin, y, thin, h));
+                    graphic_items.push(line(center_x - thick / 2.0, y, thick, h));
+                    graphic_items.push(line(center_x + thick / 2.0 + gap, y, thin, h));
+                    graphic_items.extend(repeat_dots(x + w - BARLINE_REPEAT_DOT, y));
+                }
+                BarlineType::Dotted => {
+                    // one dot in each space of the staff
+                    let mut dot_y = y + SPACE_HALF;
+                    while dot_y < y + h {
+                        graphic_items.push(GraphicItem::Ellipse(x + thin, dot_y, thin * 1.5, thin * 1.5, Stroke::None, Fill::Solid(color), None));
+                        dot_y += SPACE;
+                    }
+                }
+            }
+        }
+        GlyphItem::Stem => {
+            graphic_items.push(GraphicItem::Rect(rect.0 + movex, rect.1 + movey, rect.2, rect.3, Stroke::None, Fill::Solid(color), None));
+        }
+        GlyphItem::Beam => {
+            graphic_items.push(GraphicItem::Rect(rect.0 + movex, rect.1 + movey, rect.2, rect.3, Stroke::None, Fill::Solid(color), None));
+        }
+        GlyphItem::LedgerLine => {
+            graphic_items.push(GraphicItem::Rect(rect.0 + movex, rect.1 + movey, rect.2, rect.3, Stroke::None, Fill::Solid(color), None));
+        }
+        GlyphItem::Dot => {
+            graphic_items.push(GraphicItem::Ellipse(
+                rect.0 + movex + rect.2 / 2.0,
+                rect.1 + movey + rect.3 / 2.0,
+                rect.2 / 2.0,
+                rect.3 / 2.0,
+                Stroke::None,
+                Fill::Solid(color),
+                None,
+            ));
         }
         GlyphItem::Notehead(_htype, _hvariant) => {
-            let path = match _htype {
-                HeadType::Whole => GLYPH_NOTEHEAD_WHOLE,
-                HeadType::Brevis => GLYPH_NOTEHEAD_WHOLE,
-                HeadType::White => GLYPH_NOTEHEAD_WHITE,
-                _ => GLYPH_NOTEHEAD_BLACK,
+            let name = match _htype {
+                HeadType::Whole => "noteheadWhole",
+                HeadType::Brevis => "noteheadWhole",
+                HeadType::White => "noteheadHalf",
+                _ => "noteheadBlack",
             };
 
             // graphic_items.push(GraphicItem::Rect(rect.0 + movex, rect.1 + movey, rect.2, rect.3, Stroke::None, Fill::Solid(Color::LightGray), None));
 
-            graphic_items.push(GraphicItem::Path(
-                path.to_vec(),
-                rect.0 + movex,
-                rect.1 + movey + y_zero - SPACE3 - SPACE_HALF,
-                Stroke::None,
-                Fill::Solid(Color::Black),
-                Some(PathCache::UseCache),
-            ));
+            // heads have their origin on the middle of their level
+            graphic_items.extend(get_music_glyph_item(fcx, color, name, rect.0 + movex, rect.1 + movey + rect.3 / 2.0, 1.0, Some(PathCache::UseCache)));
         }
         GlyphItem::Accidental(_atype) => {
-            let path = match _atype {
-                Accidental::Flat => GLYPH_ACCIDENTAL_FLAT,
-                Accidental::Natural => GLYPH_ACCIDENTAL_NATURAL,
-                _ => GLYPH_ACCIDENTAL_SHARP,
+            let name = match _atype {
+                Accidental::Flat => "accidentalFlat",
+                Accidental::Natural => "accidentalNatural",
+                Accidental::DoubleSharp => "accidentalDoubleSharp",
+                Accidental::DoubleFlat => "accidentalDoubleFlat",
+                _ => "accidentalSharp",
             };
 
             // graphic_items.push(GraphicItem::Rect(rect.0 + movex, rect.1 + movey, rect.2, rect.3, Stroke::None, Fill::Solid(Color::LightGray), None));
 
-            graphic_items.push(GraphicItem::Path(
-                path.to_vec(),
-                rect.0 + movex,
-                rect.1 + movey + y_zero - SPACE2 - SPACE_HALF,
-                Stroke::None,
-                Fill::Solid(Color::Black),
-                Some(PathCache::UseCache),
-            ));
+            let y = rect.1 + movey + rect.3 / 2.0;
+            match get_music_glyph_item(fcx, color, name, rect.0 + movex, y, 1.0, Some(PathCache::UseCache)) {
+                Some(item) => graphic_items.push(item),
+                // without a double flat glyph, two flats share the rect, see GlyphWidths::from_font
+                None if *_atype == Accidental::DoubleFlat => {
+                    for flat_x in [rect.0, rect.0 + rect.2 / 2.0] {
+                        graphic_items.extend(get_music_glyph_item(fcx, color, "accidentalFlat", flat_x + movex, y, 1.0, Some(PathCache::UseCache)));
+                    }
+                }
+                None => {}
+            }
         }
 
-        GlyphItem::Clef(_ctype) => {
-            // graphic_items.push(GraphicItem::Rect(rect.0 + movex, rect.1 + movey, rect.2, rect.3, Stroke::None, Fill::Solid(Color::LightGray), None));
-
-            // graphic_items.push(GraphicItem::Path(
-            //     GLYPH_FIVELINES.to_vec(),
-            //     rect.0 + movex,
-            //     rect.1 + movey + y_zero,
-            //     Stroke::None,
-            //     Fill::Solid(Color::Red),
-            //     None,
-            // ));
-
-            let curve = match _ctype {
-                ClefSignature::Treble => GLYPH_CLEF_TREBLE,
-                ClefSignature::Bass => GLYPH_CLEF_BASS,
-                _ => GLYPH_CLEF_TREBLE,
-            };
-
-            let y = match _ctype {
-                ClefSignature::Bass => -SPACE3,
-                _ => -SPACE,
-            };
-
-            graphic_items.push(GraphicItem::Path(
-                curve.to_vec(),
-                rect.0 + movex,
-                rect.1 + movey + y_zero + y,
-                Stroke::None,
-                Fill::Solid(Color::Black),
-                Some(PathCache::UseCache),
-            ));
+        GlyphItem::Clef(clef) => {
+            graphic_items.extend(get_clef_items(fcx, color, clef, rect.0 + movex, movey + clef.get_line_level() as f32 * SPACE_HALF, 1.0));
+        }
+        GlyphItem::ClefChange(clef) => {
+            // scaled around the clef line, so it stays on its line
+            graphic_items.extend(get_clef_items(fcx, color, clef, rect.0 + movex, movey + clef.get_line_level() as f32 * SPACE_HALF, CLEF_CHANGE_SCALE));
         }
         GlyphItem::Rest(rtype) => {
-            let path = match rtype {
-                // score::rest::RestType::Brevis => GLYPH_NOTEHEAD_WHOLE,
-                RestType::Whole => GLYPH_REST_HALF,
-                RestType::Half => GLYPH_REST_HALF,
-                RestType::Eighth => GLYPH_REST_EIGHTH,
-                RestType::Sixteenth => GLYPH_REST_SIXTEENTH,
-                _ => GLYPH_REST_QUARTER,
+            let name = match rtype {
+                // score::rest::RestType::Brevis => "restDoubleWhole",
+                RestType::Whole => "restWhole",
+                RestType::Half => "restHalf",
+                RestType::Eighth => "rest8th",
+                RestType::Sixteenth => "rest16th",
+                _ => "restQuarter",
             };
 
-            graphic_items.push(GraphicItem::Path(
-                path.to_vec(),
-                rect.0 + movex,
-                rect.1 + movey + y_zero - SPACE3,
-                Stroke::None,
-                Fill::Solid(Color::Black),
-                None,
-            ));
+            // the glyphs are drawn from the rest level, the rect only carries their extents
+            let level_y = rect.1 - rtype.get_extents().0 as f32 * SPACE_HALF;
+            // the whole rest hangs from the line above its level, the others have their origin on it
+            let glyph_y = if *rtype == RestType::Whole { -SPACE } else { 0.0 };
+
+            graphic_items.extend(get_music_glyph_item(fcx, color, name, rect.0 + movex, level_y + glyph_y + movey, 1.0, None));
         }
 
         GlyphItem::FlagEightDown => {
-            let path = GLYPH_FLAG_8TH_DOWN;
-            graphic_items.push(GraphicItem::Path(
-                path.to_vec(),
-                rect.0 + movex,
-                rect.1 + movey + y_zero - SPACE4,
-                Stroke::None,
-                Fill::Solid(Color::Black),
-                None,
-            ));
+            // flags have their origin at the end of the stem, the up stem flag hangs down from it
+            graphic_items.extend(get_music_glyph_item(fcx, color, "flag8thUp", rect.0 + movex, rect.1 + movey, 1.0, None));
         }
 
         GlyphItem::FlagEightUp => {
-            let path = GLYPH_FLAG_8TH_UP;
-            graphic_items.push(GraphicItem::Path(path.to_vec(), rect.0 + movex, rect.1 + movey + y_zero, Stroke::None, Fill::Solid(Color::Black), None));
+            graphic_items.extend(get_music_glyph_item(fcx, color, "flag8thDown", rect.0 + movex, rect.1 + movey + rect.3, 1.0, None));
+        }
+
+        GlyphItem::Space => {}
+
+        GlyphItem::BarRest => {
+            // drawn centred in the bar by Render::render_bar_rests
+        }
+
+        GlyphItem::Tagged(tag, glyph) => {
+            let id = get_glyph_id(tag, glyph);
+            let color = tag.color.map(|note_color| get_note_color(theme, &note_color)).unwrap_or(color);
+            let items = get_glyph_items(fcx, widths, theme, color, movex, movey, rect, glyph);
+            graphic_items.extend(items.into_iter().map(|item| item.with_id(Some(id.clone()))));
         }
 
         _ => {
@@ -144,3 +202,92 @@ pub fn get_graphic_items_from_glyph(movex: f32, movey: f32, rect: &Rectangle, gl
 
     graphic_items
 }
+
+/// Id of the graphic items of a glyph, see `GraphicItem`. Heads are the elements identifiable on their own
+fn get_glyph_id(tag: &GlyphTag, glyph: &GlyphItem) -> String {
+    let mut id = match (glyph, tag.head_id) {
+        (GlyphItem::Notehead(..), Some(head_id)) => format!("head-{}", head_id),
+        (GlyphItem::Notehead(..), None) => "head".to_string(),
+        (GlyphItem::Stem, _) => "stem".to_string(),
+        (GlyphItem::Beam, _) => "beam".to_string(),
+        (GlyphItem::FlagEightUp | GlyphItem::FlagEightDown, _) => "flag".to_string(),
+        (GlyphItem::LedgerLine, _) => "ledger".to_string(),
+        (GlyphItem::Dot, _) => "dot".to_string(),
+        (GlyphItem::Accidental(_), _) => "accidental".to_string(),
+        (GlyphItem::Rest(_), _) => "rest".to_string(),
+        (GlyphItem::Barline(_), _) => "barline".to_string(),
+        (GlyphItem::Clef(_) | GlyphItem::ClefChange(_), _) => "clef".to_string(),
+        _ => "mark".to_string(),
+    };
+    let head_id = if matches!(glyph, GlyphItem::Notehead(..)) { None } else { tag.head_id };
+    for (name, value) in [("head", head_id), ("stemitem", tag.stemitem_id), ("note", tag.note_id), ("part", tag.part_idx), ("bar", tag.bar_idx)] {
+        if let Some(value) = value {
+            id.push_str(&format!(" {}-{}", name, value));
+        }
+    }
+    id
+}
+
+/// A music glyph by its SMuFL name with its origin at `x`, `y`, nothing when the music font lacks it
+pub fn get_music_glyph_item(fcx: &FontContext, color: Color, name: &str, x: f32, y: f32, scale: f32, cache: Option<PathCache>) -> Option<GraphicItem> {
+    let segments = fcx.get_music_glyph_segments(name, SPACE * scale)?;
+    Some(GraphicItem::Path(segments, x, y, Stroke::None, Fill::Solid(color), cache, None))
+}
+
+/// Clef items with the left edge at `x` and the clef line at `line_y`.
+/// Clefs the music font has no glyph for are drawn from rects and text.
+fn get_clef_items(fcx: &FontContext, color: Color, clef: &ClefSignature, x: f32, line_y: f32, scale: f32) -> Vec<GraphicItem> {
+    let text = |text_x: f32, text_y: f32, text: &str| GraphicItem::Text(x + text_x * scale, line_y + text_y * scale, text.to_string(), color, None);
+    let mut graphic_items = Vec::new();
+    // the glyphs have their origin on their clef line
+    let glyph = |name: &str| get_music_glyph_item(fcx, color, name, x, line_y, scale, Some(PathCache::UseCache));
+    let glyph_name = match clef {
+        ClefSignature::Percussion => Some("unpitchedPercussionClef1"),
+        ClefSignature::Tab => Some("6stringTabClef"),
+        ClefSignature::Treble8vb => Some("gClef8vb"),
+        ClefSignature::Treble8va => Some("gClef8va"),
+        ClefSignature::Bass8vb => Some("fClef8vb"),
+        _ => None,
+    };
+    if let Some(item) = glyph_name.and_then(glyph) {
+        return vec![item];
+    }
+    match clef {
+        ClefSignature::None => {}
+        ClefSignature::Percussion => {
+            for bar_x in [SPACE * 0.8, SPACE * 1.7] {
+                graphic_items.push(GraphicItem::Rect(
+                    x + bar_x * scale,
+                    line_y - SPACE * scale,
+                    SPACE_HALF * scale,
+                    SPACE2 * scale,
+                    Stroke::None,
+                    Fill::Solid(color),
+                    None,
+                ));
+            }
+        }
+        ClefSignature::Tab => {
+            for (letter, letter_y) in [("T", -SPACE * 0.6), ("A", SPACE * 0.8), ("B", SPACE * 2.2)] {
+                graphic_items.push(text(SPACE * 0.8, letter_y, letter));
+            }
+        }
+        clef => {
+            let name = match clef {
+                ClefSignature::Bass | ClefSignature::Bass8vb => "fClef",
+                ClefSignature::Soprano | ClefSignature::MezzoSoprano | ClefSignature::Alto | ClefSignature::Tenor | ClefSignature::Baritone => "cClef",
+                _ => "gClef",
+            };
+
+            graphic_items.extend(glyph(name));
+
+            match clef {
+                ClefSignature::Treble8vb => graphic_items.push(text(SPACE, SPACE * 3.8, "8")),
+                ClefSignature::Treble8va => graphic_items.push(text(SPACE * 1.2, -SPACE * 4.6, "8")),
+                ClefSignature::Bass8vb => graphic_items.push(text(SPACE * 0.6, SPACE * 3.6, "8")),
+                _ => {}
+            }
+        }
+    }
+    graphic_items
+}
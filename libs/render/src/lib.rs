@@ -1,4 +1,4 @@
 pub mod gridrender;
+pub mod hittest;
 pub mod items;
-pub mod music_glyphs;
 pub mod output;
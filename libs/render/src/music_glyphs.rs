@@ -1,33 +0,0 @@
-use graphics::path::PathSegment::{self, L, M, Q, Z};
-
-pub const GLYPH_CLEF_TREBLE: &[PathSegment] = &[M(9.896009, 37.469166), Q(8.978234, 34.55623, 8.619105, 32.281742), Q(8.299879, 30.366386, 8.299879, 27.812576), Q(8.299879, 26.934704, 8.339783, 26.056831), Q(8.499395, 22.266022, 9.896009, 19.093712), Q(11.2926235, 15.921402, 14.085853, 14.245468), Q(14.285369, 14.125755, 14.604595, 14.125755), Q(14.883918, 14.125755, 15.083434, 14.325272), Q(17.318016, 16.559856, 18.515114, 20.03144), Q(19.712214, 23.503025, 19.712214, 27.054413), Q(19.712214, 31.164452, 17.956469, 35.114876), Q(16.280533, 39.0653, 13.247884, 42.097946), Q(13.008464, 42.337364, 13.088271, 42.736397), L(14.6844015, 49.12092), Q(14.8041115, 49.759373, 15.482467, 49.679565), Q(16.120918, 49.59976, 16.719467, 49.59976), Q(19.033857, 49.59976, 20.909311, 50.517532), Q(22.784763, 51.43531, 23.94196, 52.911728), Q(25.099154, 54.38815, 25.717653, 56.163845), Q(26.336155, 57.93954, 26.336155, 59.814995), Q(26.336155, 63.246674, 24.420797, 66.01995), Q(22.505442, 68.79323, 19.831923, 69.6711), Q(19.273277, 69.87062, 19.392986, 70.50907), Q(20.390568, 75.21765, 20.430471, 78.44982), Q(20.430471, 79.68682, 20.270859, 80.88392), Q(19.951632, 83.637245, 17.876663, 85.71221), Q(15.801693, 87.787186, 12.928658, 87.787186), Q(9.417171, 87.787186, 6.9830713, 85.67231), Q(4.548972, 83.557434, 4.548972, 80.125755), Q(4.548972, 78.37001, 5.7859735, 77.09311), Q(7.0229745, 75.81621, 8.698912, 75.81621), Q(10.414752, 75.81621, 11.591898, 77.03325), Q(12.769045, 78.250305, 12.769045, 79.92624), Q(12.769045, 81.362755, 11.811366, 82.44015), Q(10.853688, 83.557434, 9.496977, 83.67715), Q(9.018138, 83.71705, 8.858524, 84.15599), Q(8.698912, 84.594925, 9.177751, 84.954056), Q(10.614268, 86.07134, 12.689239, 86.07134), Q(14.963724, 86.07134, 16.619709, 84.43531), Q(18.275696, 82.79927, 18.555017, 80.724304), Q(18.754534, 79.28779, 18.754534, 77.85127), Q(18.754534, 74.738815, 17.796856, 70.948006), Q(17.637243, 70.26965, 16.918983, 70.34946), Q(15.322853, 70.54897, 13.966143, 70.54897), Q(8.060459, 70.54897, 4.0302296, 66.83797), Q(0.0, 63.126965, 0.0, 56.742443), Q(0.0, 54.78718, 0.45888755, 52.891777), Q(0.9177751, 50.996372, 1.4963725, 49.619713), Q(2.0749698, 48.24305, 3.2920194, 46.42745), Q(4.509069, 44.61185, 5.247279, 43.654175), Q(5.98549, 42.696495, 7.58162, 40.801086), Q(9.177751, 38.905685, 9.776299, 38.147522), Q(10.015719, 37.8682, 9.896009, 37.469166), Z, M(16.120918, 55.70496), L(18.71463, 67.476425), Q(18.834341, 67.99516, 19.43289, 67.99516), Q(19.592503, 67.99516, 19.712214, 67.95526), Q(21.348246, 67.2769, 22.425634, 65.32164), Q(23.503023, 63.366386, 23.503023, 61.451027), Q(23.503023, 58.6578, 21.86699, 56.742443), Q(20.191051, 54.78718, 16.839178, 54.78718), Q(16.559855, 54.78718, 16.340387, 55.006653), Q(16.120918, 55.22612, 16.120918, 55.505444), Q(16.081017, 55.625153, 16.120918, 55.70496), Z, M(10.614268, 44.61185), Q(9.975816, 45.33011, 8.7188635, 46.66687), Q(7.4619102, 48.003628, 6.84341, 48.701935), Q(6.2249093, 49.40024, 5.327086, 50.677147), Q(4.429262, 51.954052, 3.9903264, 53.03144), Q(3.5513906, 54.10883, 3.2122128, 55.665054), Q(2.873035, 57.221283, 2.873035, 58.977028), Q(2.873035, 61.411125, 4.3694077, 63.68561), Q(5.86578, 65.9601, 8.479444, 67.396614), Q(11.093107, 68.83313, 14.085853, 68.83313), Q(15.16324, 68.83313, 16.48005, 68.713425), Q(16.799274, 68.67352, 16.99879, 68.41415), Q(17.198307, 68.15478, 17.1185, 67.835556), L(14.524788, 55.904476), Q(14.405079, 55.34583, 13.766626, 55.34583), Q(13.646916, 55.34583, 13.487304, 55.385735), Q(11.651753, 56.064087, 10.893591, 57.30109), Q(10.135429, 58.53809, 10.135429, 60.413544), Q(10.135429, 62.608223, 12.529625, 64.20435), Q(12.808948, 64.40387, 12.808948, 64.8029), Q(12.808948, 65.12213, 12.549577, 65.40145), Q(12.290205, 65.68077, 11.931076, 65.68077), Q(11.73156, 65.68077, 11.571947, 65.60097), Q(9.297461, 64.40387, 8.200121, 62.348854), Q(7.1027813, 60.293835, 7.1027813, 58.01935), Q(7.1027813, 55.82467, 8.67896, 53.490326), Q(10.255139, 51.155987, 12.609431, 50.278114), Q(13.20798, 50.038696, 13.088271, 49.440147), L(11.891172, 44.931076), Q(11.811366, 44.49214, 11.332527, 44.412334), Q(10.853688, 44.332527, 10.614268, 44.61185), Z, M(17.318016, 21.38815), Q(16.99879, 20.350666, 16.24063, 20.350666), Q(16.00121, 20.350666, 15.801693, 20.470375), Q(13.247884, 21.986698, 11.751512, 24.740025), Q(10.255139, 27.493351, 10.255139, 30.565903), Q(10.255139, 32.561066, 11.093107, 35.474003), Q(11.212817, 35.91294, 11.691656, 35.992744), Q(12.090689, 36.03265, 12.370012, 35.713425), Q(12.489722, 35.55381, 13.507255, 34.29686), Q(14.524788, 33.0399, 14.844014, 32.62092), Q(15.16324, 32.201935, 15.921403, 31.02479), Q(16.679565, 29.847643, 16.97884, 29.069529), Q(17.278114, 28.291416, 17.557436, 27.13422), Q(17.83676, 25.977028, 17.83676, 24.819832), Q(17.83676, 23.143894, 17.318016, 21.38815), Z];
-
-pub const GLYPH_NOTEHEAD_BLACK: &[PathSegment] = &[M(0.0, 61.371223), Q(0.0, 58.977028, 2.6336155, 56.762394), Q(5.267231, 54.547764, 8.020556, 54.547764), Q(9.137848, 54.547764, 10.155381, 54.827087), Q(11.1729145, 55.10641, 12.090689, 56.103992), Q(13.008464, 57.101574, 13.008464, 58.6578), Q(13.008464, 60.852478, 10.334946, 63.16687), Q(7.6614265, 65.48126, 4.987908, 65.48126), Q(3.8706167, 65.48126, 2.8530834, 65.201935), Q(1.8355502, 64.922615, 0.9177751, 63.92503), Q(0.0, 62.92745, 0.0, 61.371223), Z];
-
-pub const GLYPH_REST_EIGHTH: &[PathSegment] = &[M(6.2648125, 57.4208), Q(4.5888753, 57.859734, 3.471584, 57.859734), Q(2.0749698, 57.859734, 1.0374849, 56.96191), Q(0.0, 56.064087, 0.0, 54.70738), Q(0.0, 53.51028, 0.85792017, 52.67231), Q(1.7158403, 51.834343, 2.9129384, 51.834343), Q(3.6711004, 51.834343, 4.289601, 52.253326), Q(4.9081016, 52.67231, 5.147521, 53.390568), Q(5.227328, 53.62999, 5.3869405, 54.408104), Q(5.5465536, 55.186214, 5.7859735, 55.465538), Q(6.025393, 55.74486, 6.5441356, 55.74486), Q(6.9830713, 55.74486, 7.8409915, 54.76723), Q(8.698912, 53.7896, 8.978234, 53.230957), Q(9.137848, 52.871826, 9.5368805, 52.871826), Q(9.935913, 52.871826, 10.095526, 53.230957), L(5.187424, 69.990326), Q(4.7484884, 70.38936, 4.1100364, 70.38936), Q(3.4316807, 70.38936, 2.992745, 69.990326), L(7.1426845, 58.37848), Q(7.3022976, 57.93954, 7.0229745, 57.660217), Q(6.703748, 57.261185, 6.2648125, 57.4208), Z];
-
-pub const GLYPH_FLAG_8TH_UP: &[PathSegment] = &[M(2.3542926, 49.36034), Q(10.334946, 44.372433, 10.334946, 39.70375), Q(10.334946, 36.55139, 8.698912, 33.199516), Q(8.619105, 32.760582, 8.91838, 32.441353), Q(9.217654, 32.12213, 9.616687, 32.12213), Q(10.135429, 32.12213, 10.414752, 32.561066), Q(12.010882, 36.15236, 12.010882, 39.70375), Q(12.010882, 41.339783, 11.4123335, 42.935913), Q(10.813785, 44.532043, 9.876058, 45.868805), Q(8.938332, 47.205563, 7.8010883, 48.52237), Q(6.663845, 49.83918, 5.5066504, 51.175938), Q(4.349456, 52.512695, 3.411729, 53.849457), Q(2.4740024, 55.18622, 1.8754535, 56.78235), Q(1.2769045, 58.37848, 1.2769045, 60.01451), L(0.63845223, 60.01451), L(0.63845223, 47.24547), L(1.2769045, 47.24547), L(1.2769045, 48.721886), Q(1.2769045, 49.041115, 1.4963725, 49.24063), Q(1.7158403, 49.440147, 1.9951632, 49.440147), Q(2.1946795, 49.440147, 2.3542926, 49.36034), Z];
-
-pub const GLYPH_ACCIDENTAL_SHARP: &[PathSegment] = &[M(8.619105, 56.1838), L(8.619105, 61.051994), Q(8.619105, 61.371223, 8.818622, 61.57074), Q(9.018138, 61.770256, 9.337364, 61.770256), Q(9.457073, 61.770256, 9.576783, 61.73035), L(9.896009, 61.61064), Q(10.015719, 61.57074, 10.175332, 61.57074), Q(10.494558, 61.57074, 10.733978, 61.810158), Q(10.973398, 62.049576, 10.973398, 62.368805), L(10.973398, 64.763), Q(10.973398, 65.28174, 10.454656, 65.48126), L(9.097944, 66.0), Q(8.619105, 66.19952, 8.619105, 66.63845), L(8.619105, 72.464325), Q(8.619105, 72.783554, 8.399637, 73.00302), Q(8.180169, 73.22249, 7.9008465, 73.22249), Q(7.58162, 73.22249, 7.362152, 73.00302), Q(7.1426845, 72.783554, 7.1426845, 72.464325), L(7.1426845, 67.71584), Q(7.1426845, 67.396614, 6.9232163, 67.1971), Q(6.703748, 66.99758, 6.4244256, 66.99758), Q(6.2648125, 66.99758, 6.1451025, 67.03748), L(4.3095527, 67.755745), Q(3.8307133, 67.91536, 3.8307133, 68.394196), L(3.8307133, 74.22008), Q(3.8307133, 74.5393, 3.5912938, 74.758766), Q(3.351874, 74.97823, 3.0725513, 74.97823), Q(2.7932286, 74.97823, 2.5737605, 74.758766), Q(2.3542926, 74.5393, 2.3542926, 74.22008), L(2.3542926, 69.47159), Q(2.3542926, 69.15236, 2.1348248, 68.95284), Q(1.9153566, 68.75333, 1.6360339, 68.75333), Q(1.516324, 68.75333, 1.3966143, 68.79323), L(1.0773882, 68.91294), Q(0.9975816, 68.95284, 0.7980653, 68.95284), Q(0.47883916, 68.95284, 0.23941958, 68.713425), Q(0.0, 68.474, 0.0, 68.15478), L(0.0, 65.76058), Q(0.0, 65.201935, 0.51874244, 65.00242), L(1.8754535, 64.52358), Q(2.3542926, 64.40387, 2.3542926, 63.845222), L(2.3542926, 58.977028), Q(2.3542926, 58.6578, 2.1348248, 58.458282), Q(1.9153566, 58.258766, 1.6360339, 58.258766), Q(1.516324, 58.258766, 1.4365175, 58.29867), L(1.0773882, 58.41838), Q(0.9975816, 58.458282, 0.7980653, 58.458282), Q(0.47883916, 58.458282, 0.23941958, 58.218864), Q(0.0, 57.979446, 0.0, 57.660217), L(0.0, 55.26602), Q(0.0, 54.74728, 0.51874244, 54.547764), L(1.9153566, 54.029022), Q(2.3542926, 53.829506, 2.3542926, 53.39057), L(2.3542926, 47.564693), Q(2.3542926, 47.24547, 2.5737605, 47.025997), Q(2.7932286, 46.80653, 3.0725513, 46.80653), Q(3.351874, 46.80653, 3.5912938, 47.025997), Q(3.8307133, 47.24547, 3.8307133, 47.564693), L(3.8307133, 52.31318), Q(3.8307133, 52.63241, 4.0501814, 52.831924), Q(4.2696495, 53.03144, 4.5888753, 53.03144), Q(4.7085853, 53.03144, 4.828295, 52.991535), L(6.663845, 52.273277), Q(7.1426845, 52.07376, 7.1426845, 51.59492), L(7.1426845, 45.80895), Q(7.1426845, 45.489723, 7.362152, 45.270256), Q(7.58162, 45.05079, 7.9008465, 45.05079), Q(8.180169, 45.05079, 8.399637, 45.270256), Q(8.619105, 45.489723, 8.619105, 45.80895), L(8.619105, 50.59734), Q(8.619105, 50.876663, 8.818622, 51.07618), Q(9.018138, 51.275696, 9.297461, 51.275696), Q(9.417171, 51.275696, 9.576783, 51.235794), L(9.896009, 51.11608), Q(9.975816, 51.07618, 10.175332, 51.07618), Q(10.494558, 51.07618, 10.733978, 51.315598), Q(10.973398, 51.55502, 10.973398, 51.874245), L(10.973398, 54.26844), Q(10.973398, 54.827087, 10.454656, 55.026604), L(9.097944, 55.50544), Q(8.619105, 55.625153, 8.619105, 56.1838), Z, M(7.1426845, 62.08948), L(7.1426845, 57.261185), Q(7.1426845, 56.981865, 6.9232163, 56.762394), Q(6.703748, 56.542927, 6.4244256, 56.542927), Q(6.3047156, 56.542927, 6.185006, 56.582832), L(4.3095527, 57.261185), Q(3.8307133, 57.4607, 3.8307133, 57.93954), L(3.8307133, 62.767838), Q(3.8307133, 63.087063, 4.0302296, 63.28658), Q(4.229746, 63.486095, 4.548972, 63.486095), Q(4.668682, 63.486095, 4.7883916, 63.44619), L(6.663845, 62.767838), Q(7.1426845, 62.56832, 7.1426845, 62.08948), Z];
-
-pub const GLYPH_REST_QUARTER: &[PathSegment] = &[M(4.1898427, 63.16687), Q(4.469166, 63.16687, 4.6487303, 62.987305), Q(4.828295, 62.80774, 4.828295, 62.56832), Q(4.828295, 62.3289, 4.668682, 62.16929), L(1.7956469, 58.937122), Q(0.59854895, 57.580414, 0.59854895, 56.423218), Q(0.59854895, 55.425636, 3.4316807, 52.951633), Q(4.9081016, 51.634827, 4.9081016, 49.639664), Q(4.9081016, 48.083435, 3.9105198, 46.886337), L(2.5139058, 45.2104), Q(2.3542926, 45.010883, 2.3542926, 44.811367), Q(2.3542926, 44.21282, 2.9129384, 44.21282), Q(3.2720678, 44.21282, 3.5114872, 44.49214), L(9.496977, 51.59492), Q(9.896009, 52.07376, 9.896009, 52.592506), Q(9.896009, 53.03144, 9.576783, 53.510277), Q(9.257557, 53.98912, 8.898428, 54.328297), Q(8.539299, 54.667473, 7.8409915, 55.24607), Q(7.1426845, 55.82467, 6.8633614, 56.103992), Q(5.187424, 57.660217, 5.187424, 59.53567), Q(5.187424, 60.97219, 6.823458, 62.727936), L(10.175332, 66.35913), Q(10.334946, 66.518745, 10.334946, 66.75816), Q(10.334946, 66.99758, 10.155381, 67.17715), Q(9.975816, 67.35671, 9.776299, 67.35671), Q(9.496977, 67.35671, 8.938332, 66.91778), Q(7.4619102, 65.80048, 5.7859735, 65.80048), Q(4.6287785, 65.80048, 3.7708585, 66.6983), Q(2.9129384, 67.59613, 2.9129384, 68.75333), Q(2.9129384, 69.272064, 2.992745, 69.691055), Q(3.0725513, 70.11004, 3.2920194, 70.54897), Q(3.5114872, 70.98791, 3.5912938, 71.14752), Q(3.6711004, 71.30714, 4.0302296, 71.80592), Q(4.389359, 72.30472, 4.429262, 72.34462), Q(4.548972, 72.504234, 4.509069, 72.70375), Q(4.469166, 72.90327, 4.289601, 73.00302), Q(4.1100364, 73.10278, 3.9105198, 73.06288), Q(3.7110035, 73.02298, 3.3917775, 72.70375), Q(2.0350666, 71.30714, 1.0175333, 69.57134), Q(0.0, 67.83555, 0.0, 66.55865), Q(0.0, 65.00242, 1.0973397, 64.08464), Q(2.1946795, 63.16687, 4.1898427, 63.16687), Z];
-
-pub const GLYPH_ACCIDENTAL_FLAT: &[PathSegment] = &[M(1.8355502, 63.44619), Q(1.8355502, 63.925034, 2.234583, 64.08464), Q(2.713422, 64.284164, 3.032648, 63.964935), Q(5.6662636, 61.57074, 5.6662636, 58.53809), Q(5.6662636, 57.4607, 5.207376, 56.662636), Q(4.7484884, 55.86457, 3.9504232, 55.86457), Q(3.0725513, 55.86457, 2.5139058, 56.622734), Q(1.9552599, 57.380898, 1.9153566, 58.37848), L(1.8355502, 63.44619), Z, M(2.9528415, 54.62757), Q(4.6287785, 53.789604, 5.7061667, 53.789604), Q(7.222491, 53.789604, 8.140266, 54.986702), Q(9.058041, 56.1838, 9.058041, 57.859734), Q(9.058041, 59.01693, 8.7188635, 59.95466), Q(8.379685, 60.892384, 7.6614265, 61.670494), Q(6.943168, 62.44861, 6.3246675, 62.967354), Q(5.7061667, 63.486095, 4.7085853, 64.224304), Q(3.7110035, 64.96252, 3.1922612, 65.40145), Q(2.992745, 65.561066, 2.6735187, 66.0), Q(2.3542926, 66.438934, 2.0550182, 66.67836), Q(1.7557436, 66.91778, 1.3966143, 66.91778), Q(0.9975816, 66.91778, 0.7382104, 66.63845), Q(0.47883916, 66.35913, 0.47883916, 65.9202), L(0.0, 41.778717), Q(0.51874244, 41.459496, 1.0773882, 41.459496), Q(1.6360339, 41.459496, 2.1547763, 41.778717), L(1.9153566, 53.98912), Q(1.9153566, 54.388153, 2.234583, 54.58767), Q(2.593712, 54.827087, 2.9528415, 54.62757), Z];
-
-pub const GLYPH_CLEF_BASS: &[PathSegment] = &[M(22.744862, 65.00242), Q(22.744862, 64.16445, 23.34341, 63.565903), Q(23.94196, 62.967354, 24.779926, 62.967354), Q(25.617895, 62.967354, 26.216444, 63.565903), Q(26.814993, 64.16445, 26.814993, 65.00242), Q(26.814993, 65.840385, 26.216444, 66.438934), Q(25.617895, 67.03748, 24.779926, 67.03748), Q(23.94196, 67.03748, 23.34341, 66.438934), Q(22.744862, 65.840385, 22.744862, 65.00242), Z, M(22.744862, 55.026604), Q(22.744862, 54.188633, 23.34341, 53.590084), Q(23.94196, 52.991535, 24.779926, 52.991535), Q(25.617895, 52.991535, 26.216444, 53.590084), Q(26.814993, 54.188633, 26.814993, 55.026604), Q(26.814993, 55.86457, 26.216444, 56.46312), Q(25.617895, 57.06167, 24.779926, 57.06167), Q(23.94196, 57.06167, 23.34341, 56.46312), Q(22.744862, 55.86457, 22.744862, 55.026604), Z, M(4.8681984, 55.26602), Q(6.663845, 55.26602, 7.8210397, 56.423218), Q(8.978234, 57.580414, 8.978234, 59.256348), Q(8.978234, 61.012093, 7.8210397, 62.249092), Q(6.663845, 63.486095, 4.987908, 63.486095), Q(3.1922612, 63.486095, 1.8555018, 62.249092), Q(0.51874244, 61.012093, 0.51874244, 59.256348), Q(0.51874244, 54.946796, 3.3917775, 52.233376), Q(6.2648125, 49.51995, 10.534462, 49.51995), Q(15.362757, 49.51995, 18.415356, 52.29323), Q(21.467957, 55.066505, 21.467957, 59.53567), Q(21.467957, 73.66142, 0.9576783, 82.71947), Q(0.6783555, 82.83918, 0.51874244, 82.83918), Q(0.0, 82.83918, 0.0, 82.320435), Q(0.0, 82.041115, 0.51874244, 81.721886), Q(3.7110035, 79.846436, 5.805925, 78.40991), Q(7.9008465, 76.9734, 10.135429, 74.93833), Q(12.370012, 72.90327, 13.646916, 70.84825), Q(14.9238205, 68.79323, 15.761789, 66.0), Q(16.599758, 63.20677, 16.599758, 59.934704), Q(16.599758, 55.784767, 14.78416, 53.27086), Q(12.968561, 50.756954, 9.816203, 50.756954), Q(7.9008465, 50.756954, 6.3446193, 51.654778), Q(4.7883916, 52.5526, 4.1898427, 54.10883), Q(4.0302296, 54.62757, 4.229746, 54.906895), Q(4.509069, 55.26602, 4.8681984, 55.26602), Z];
-
-pub const GLYPH_ACCIDENTAL_NATURAL: &[PathSegment] = &[M(2.7533252, 52.831924), L(7.0628777, 51.954052), L(7.1825876, 51.954052), Q(7.9008465, 51.954052, 7.9008465, 52.63241), L(8.180169, 74.97823), Q(7.6215234, 75.25755, 7.1825876, 75.25755), Q(6.743652, 75.25755, 6.185006, 74.97823), L(6.3047156, 67.91536), Q(6.3047156, 67.63603, 6.1051993, 67.416565), Q(5.905683, 67.1971, 5.5864573, 67.1971), Q(5.5066504, 67.1971, 5.4667473, 67.1971), L(1.1571946, 68.114876), L(1.0374849, 68.114876), Q(0.31922612, 68.114876, 0.31922612, 67.436516), L(0.0, 45.05079), Q(0.5586457, 44.771465, 1.0374849, 44.771465), Q(1.4764208, 44.771465, 2.0350666, 45.05079), L(1.9153566, 52.07376), Q(1.9153566, 52.39299, 2.114873, 52.612457), Q(2.3143892, 52.831924, 2.6336155, 52.831924), Q(2.6735187, 52.831924, 2.7533252, 52.831924), Z, M(2.593712, 64.00484), L(5.74607, 63.28658), Q(6.3446193, 63.16687, 6.3446193, 62.56832), L(6.464329, 56.78235), Q(6.464329, 56.46312, 6.2249093, 56.2237), Q(5.98549, 55.984283, 5.6662636, 56.064087), L(2.4740024, 56.742443), Q(1.8754535, 56.862152, 1.8754535, 57.4208), L(1.7557436, 63.326485), Q(1.7557436, 63.645706, 2.0151148, 63.865177), Q(2.274486, 64.08464, 2.593712, 64.00484), Z];
-
-pub const GLYPH_FIVELINES: &[PathSegment] = &[M(0.0, 60.49335), L(0.0, 59.49577), L(39.903263, 59.49577), L(39.903263, 60.49335), L(0.0, 60.49335), Z, M(0.0, 50.517532), L(0.0, 49.51995), L(39.903263, 49.51995), L(39.903263, 50.517532), L(0.0, 50.517532), Z, M(0.0, 40.541718), L(0.0, 39.544136), L(39.903263, 39.544136), L(39.903263, 40.541718), L(0.0, 40.541718), Z, M(0.0, 30.565903), L(0.0, 29.568321), L(39.903263, 29.568321), L(39.903263, 30.565903), L(0.0, 30.565903), Z, M(0.0, 20.590084), L(0.0, 19.592503), L(39.903263, 19.592503), L(39.903263, 20.590084), L(0.0, 20.590084), Z];
-
-pub const GLYPH_FLAG_8TH_DOWN: &[PathSegment] = &[M(0.63845223, 60.01451), L(1.2769045, 60.01451), Q(1.2769045, 62.16929, 2.1348248, 64.44377), Q(2.992745, 66.71826, 4.2097945, 68.67352), Q(5.426844, 70.62878, 6.663845, 72.60399), Q(7.9008465, 74.5792, 8.758766, 76.85369), Q(9.616687, 79.128174, 9.616687, 81.28295), Q(9.616687, 85.79202, 7.9407496, 89.54292), Q(7.6614265, 90.02177, 7.1825876, 90.02177), Q(6.783555, 90.02177, 6.4842806, 89.70254), Q(6.185006, 89.383316, 6.2648125, 88.94438), Q(7.9407496, 85.59251, 7.9407496, 81.442566), Q(7.9407496, 76.89359, 2.5139058, 71.30714), Q(2.274486, 71.06772, 1.9951632, 71.06772), Q(1.7158403, 71.06772, 1.4963725, 71.287186), Q(1.2769045, 71.50665, 1.2769045, 71.825874), L(1.2769045, 73.142685), L(0.63845223, 73.142685), L(0.63845223, 60.01451), Z];
-
-pub const GLYPH_REST_HALF: &[PathSegment] = &[M(14.564692, 55.86457), L(0.39903265, 55.86457), Q(0.0, 55.86457, 0.0, 55.465538), L(0.0, 50.43773), Q(0.0, 50.038696, 0.39903265, 50.038696), L(14.564692, 50.038696), Q(14.963724, 50.038696, 14.963724, 50.43773), L(14.963724, 55.465538), Q(14.963724, 55.86457, 14.564692, 55.86457), Z];
-
-pub const GLYPH_NOTEHEAD_WHITE: &[PathSegment] = &[M(9.935913, 55.984283), Q(8.778718, 55.984283, 5.047763, 58.597946), Q(1.3168077, 61.21161, 1.3168077, 62.408707), Q(1.3168077, 63.246674, 2.0350666, 63.645706), Q(2.7533252, 64.04474, 3.750907, 64.04474), Q(4.9081016, 64.04474, 8.639057, 61.431076), Q(12.370012, 58.817413, 12.370012, 57.620316), Q(12.370012, 56.78235, 11.651753, 56.383316), Q(10.933495, 55.984283, 9.935913, 55.984283), Z, M(0.0, 61.530834), Q(0.0, 58.6578, 2.4740024, 56.602783), Q(4.9480047, 54.547764, 8.459492, 54.547764), Q(9.65659, 54.547764, 10.694075, 54.78718), Q(11.73156, 55.026604, 12.689239, 55.984283), Q(13.646916, 56.94196, 13.646916, 58.498188), Q(13.646916, 61.371223, 11.1729145, 63.42624), Q(8.698912, 65.48126, 5.187424, 65.48126), Q(3.9903264, 65.48126, 2.9528415, 65.24184), Q(1.9153566, 65.00242, 0.9576783, 64.04474), Q(0.0, 63.087063, 0.0, 61.530834), Z];
-
-pub const GLYPH_CLEF_ALTO: &[PathSegment] = &[M(13.128174, 60.01451), Q(13.128174, 62.927452, 14.9238205, 62.927452), Q(15.482467, 62.927452, 16.420193, 62.30895), Q(17.35792, 61.69045, 17.996372, 61.69045), Q(21.747278, 61.69045, 24.44075, 64.08464), Q(27.13422, 66.478836, 27.13422, 69.990326), Q(27.13422, 79.96614, 16.559855, 79.96614), Q(14.045949, 79.96614, 12.270254, 78.33011), Q(10.494558, 76.69408, 10.494558, 74.29988), Q(10.494558, 73.02298, 11.392382, 72.12515), Q(12.290205, 71.227325, 13.527206, 71.227325), Q(14.764208, 71.227325, 15.662031, 72.12515), Q(16.559855, 73.02298, 16.559855, 74.29988), Q(16.559855, 75.097946, 15.582225, 75.81621), Q(14.604595, 76.53446, 14.604595, 77.33253), Q(14.604595, 78.01088, 15.183192, 78.42987), Q(15.761789, 78.848854, 16.559855, 78.848854), Q(19.792019, 78.848854, 20.969166, 76.61427), Q(22.146313, 74.379684, 22.146313, 69.990326), Q(22.146313, 68.19468, 22.00665, 67.03748), Q(21.86699, 65.880295, 21.448004, 64.842804), Q(21.02902, 63.80532, 20.1711, 63.30653), Q(19.31318, 62.80774, 17.996372, 62.80774), Q(15.921403, 62.80774, 14.764208, 64.54353), Q(13.607013, 66.27933, 13.607013, 68.75333), Q(13.607013, 69.31197, 13.0483675, 69.31197), Q(12.529625, 69.31197, 12.529625, 68.75333), Q(12.529625, 67.03748, 11.831318, 64.98247), Q(11.133011, 62.927452, 10.135429, 61.490932), Q(9.856107, 61.051994, 9.297461, 61.21161), Q(8.818622, 61.331318, 8.818622, 61.889965), L(8.818622, 79.56711), Q(8.818622, 79.96614, 8.419589, 79.96614), L(7.58162, 79.96614), Q(7.1825876, 79.96614, 7.1825876, 79.56711), L(7.1825876, 40.461914), Q(7.1825876, 40.06288, 7.58162, 40.06288), L(8.419589, 40.06288), Q(8.818622, 40.06288, 8.818622, 40.461914), L(8.818622, 58.05925), Q(8.818622, 58.617897, 9.297461, 58.77751), Q(9.816203, 58.977028, 10.135429, 58.498188), Q(11.133011, 57.06167, 11.831318, 55.026604), Q(12.529625, 52.99154, 12.529625, 51.275696), Q(12.529625, 50.71705, 13.0483675, 50.71705), Q(13.607013, 50.71705, 13.607013, 51.275696), Q(13.607013, 53.7497, 14.764208, 55.48549), Q(15.921403, 57.221283, 17.996372, 57.221283), Q(19.31318, 57.221283, 20.1711, 56.722492), Q(21.02902, 56.2237, 21.448004, 55.18622), Q(21.86699, 54.14873, 22.00665, 52.99154), Q(22.146313, 51.834343, 22.146313, 50.038696), Q(22.146313, 45.649338, 20.969166, 43.414753), Q(19.792019, 41.18017, 16.559855, 41.18017), Q(15.761789, 41.18017, 15.183192, 41.599155), Q(14.604595, 42.01814, 14.604595, 42.696495), Q(14.604595, 43.49456, 15.582225, 44.21282), Q(16.559855, 44.931076, 16.559855, 45.72914), Q(16.559855, 47.006046, 15.662031, 47.90387), Q(14.764208, 48.801693, 13.527206, 48.801693), Q(12.290205, 48.801693, 11.392382, 47.90387), Q(10.494558, 47.006046, 10.494558, 45.72914), Q(10.494558, 43.334946, 12.270254, 41.698914), Q(14.045949, 40.06288, 16.559855, 40.06288), Q(27.13422, 40.06288, 27.13422, 50.038696), Q(27.13422, 53.550182, 24.44075, 55.944378), Q(21.747278, 58.338573, 17.996372, 58.338573), Q(17.35792, 58.338573, 16.420193, 57.720074), Q(15.482467, 57.10157, 14.9238205, 57.10157), Q(13.128174, 57.10157, 13.128174, 60.01451), Z, M(5.147521, 79.96614), L(0.39903265, 79.96614), Q(0.0, 79.96614, 0.0, 79.56711), L(0.0, 40.461914), Q(0.0, 40.06288, 0.39903265, 40.06288), L(5.147521, 40.06288), Q(5.5465536, 40.06288, 5.5465536, 40.461914), L(5.5465536, 79.56711), Q(5.5465536, 79.96614, 5.147521, 79.96614), Z];
-
-pub const GLYPH_REST_SIXTEENTH: &[PathSegment] = &[M(6.4244256, 67.35671), Q(4.7883916, 67.83555, 3.471584, 67.83555), Q(2.0749698, 67.83555, 1.0374849, 66.93773), Q(0.0, 66.0399, 0.0, 64.6832), Q(0.0, 63.486095, 0.8379685, 62.648125), Q(1.675937, 61.810158, 2.873035, 61.810158), Q(3.631197, 61.810158, 4.2696495, 62.22914), Q(4.9081016, 62.648125, 5.147521, 63.366386), Q(5.267231, 63.645706, 5.4068923, 64.40387), Q(5.5465536, 65.16203, 5.7660217, 65.44135), Q(5.98549, 65.72068, 6.504232, 65.72068), Q(6.943168, 65.72068, 7.8409915, 64.64329), Q(8.738815, 63.565903, 8.898428, 63.007256), L(10.334946, 58.29867), Q(10.374848, 58.178963, 10.374848, 58.099155), Q(10.374848, 57.81983, 10.155381, 57.600365), Q(9.935913, 57.380898, 9.616687, 57.380898), Q(9.496977, 57.380898, 9.417171, 57.4208), Q(7.7412333, 57.859734, 6.5840387, 57.859734), Q(5.187424, 57.859734, 4.1499395, 56.96191), Q(3.1124547, 56.064087, 3.1124547, 54.70738), Q(3.1124547, 53.51028, 3.9703748, 52.67231), Q(4.828295, 51.834343, 6.025393, 51.834343), Q(6.783555, 51.834343, 7.4020557, 52.253326), Q(8.020556, 52.67231, 8.259975, 53.390568), Q(8.379685, 53.66989, 8.519347, 54.428055), Q(8.659008, 55.186214, 8.878476, 55.465538), Q(9.097944, 55.74486, 9.616687, 55.74486), Q(10.015719, 55.74486, 10.813785, 54.78718), Q(11.61185, 53.829506, 11.891172, 53.230957), Q(12.050786, 52.871826, 12.449819, 52.871826), Q(12.848851, 52.871826, 13.008464, 53.230957), L(5.86578, 79.96614), Q(5.426844, 80.36517, 4.7883916, 80.36517), Q(4.1100364, 80.36517, 3.6711004, 79.96614), L(7.3022976, 68.23459), Q(7.3422008, 68.114876, 7.3422008, 67.99516), Q(7.3422008, 67.71584, 7.1426845, 67.51633), Q(6.943168, 67.31681, 6.623942, 67.31681), Q(6.5441356, 67.31681, 6.4244256, 67.35671), Z];
-
-pub const GLYPH_NOTEHEAD_WHOLE: &[PathSegment] = &[M(0.0, 59.415962), Q(0.0, 57.660217, 1.9153566, 56.103992), Q(3.8307133, 54.547764, 7.422007, 54.547764), Q(9.856107, 54.547764, 11.85127, 55.12636), Q(13.846433, 55.70496, 15.243047, 57.121525), Q(16.639662, 58.53809, 16.639662, 60.61306), Q(16.639662, 62.68803, 14.824062, 64.08464), Q(13.008464, 65.48126, 9.776299, 65.48126), Q(7.8609433, 65.48126, 6.2648125, 65.201935), Q(4.668682, 64.922615, 3.1922612, 64.284164), Q(1.7158403, 63.645706, 0.85792017, 62.408707), Q(0.0, 61.171707, 0.0, 59.415962), Z, M(7.422007, 55.625153), Q(3.9105198, 55.625153, 3.9105198, 56.862152), Q(3.9105198, 58.258766, 6.0453444, 61.331318), Q(8.180169, 64.40387, 9.616687, 64.40387), Q(13.0483675, 64.40387, 13.0483675, 63.16687), Q(13.0483675, 61.650543, 10.833736, 58.637848), Q(8.619105, 55.625153, 7.422007, 55.625153), Z];
\ No newline at end of file
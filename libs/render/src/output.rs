@@ -1,26 +1,106 @@
-use core::context::CoreContext;
-use graphics::graphicitem::GraphicItems;
+use core::{accidental::AccidentalMode, context::CoreContext};
+use fonts::fontcontext::FontContext;
+use graphics::graphicitem::{GraphicItems, items_bounding_box};
+use graphics::theme::Theme;
 use grid::{gridcontext::GridContext, griditem::GridItemType};
 use parse::parse2::Parse2;
-use score::{build::BuildScore, glyphitem::GlyphItem, scorecontext::ScoreContext};
-use svg::builder::SvgBuilder;
+use pdf::builder::{PdfBuilder, PdfOptions};
+use raster::builder::RasterBuilder;
+use score::{
+    build::BuildScore,
+    glyphitem::GlyphItem,
+    scorecontext::{GlyphWidths, ScoreContext, StaffSpacing},
+};
+use svg::builder::{SVG_BORDER_MARGIN, SvgBuilder};
 
-use crate::gridrender::Render;
+use crate::{gridrender::Render, hittest::HitIndex};
+
+#[derive(Debug, Clone, Default)]
+pub struct GenerateOptions {
+    /// Condense runs of empty bars into multi-measure rests, for single part extractions
+    pub multimeasure_rests: bool,
+    /// SMuFL music font (Bravura, Petaluma, Leland...) and its metadata JSON, instead of the built-in one
+    pub music_font: Option<(Vec<u8>, String)>,
+    /// Colours of the output, see `Theme::light` and `Theme::dark`
+    pub theme: Theme,
+    /// Smallest distances between the staves
+    pub staff_spacing: StaffSpacing,
+    /// Whether the heads in the input are read as written or as sounding pitch
+    pub accidental_mode: AccidentalMode,
+    /// The input is a later system of a score, so the parts are named by their abbreviations
+    pub continued_system: bool,
+}
+
+// the contexts of a generated score, kept for mapping the output back to the music
+struct Layout {
+    cx: &'static CoreContext,
+    gcx: &'static GridContext<GlyphItem>,
+    fcx: &'static FontContext<'static>,
+    graphic_items: GraphicItems,
+}
 
 pub struct Generate;
 impl Generate {
     pub fn svg_string(input: &str) -> Result<String, Box<dyn std::error::Error>> {
+        Generate::svg_string_with(input, &GenerateOptions::default())
+    }
+
+    pub fn svg_string_with(input: &str, options: &GenerateOptions) -> Result<String, Box<dyn std::error::Error>> {
+        let layout = Generate::layout(input, options)?;
+        let svg_string = SvgBuilder::new().with_background(options.theme.background).build(layout.graphic_items, None);
+        Ok(svg_string)
+    }
+
+    /// SVG string of the score, with an index for finding the music under a point of the SVG
+    pub fn svg_string_with_hits(input: &str, options: &GenerateOptions) -> Result<(String, HitIndex), Box<dyn std::error::Error>> {
+        let layout = Generate::layout(input, options)?;
+        let (min_x, min_y, _, _) = items_bounding_box(&layout.graphic_items, layout.fcx);
+        let hits = HitIndex::new(layout.cx, layout.gcx, (SVG_BORDER_MARGIN - min_x, SVG_BORDER_MARGIN - min_y));
+        let svg_string = SvgBuilder::new().with_background(options.theme.background).build(layout.graphic_items, None);
+        Ok((svg_string, hits))
+    }
+
+    /// PNG image of the score, `dpi` pixels to the inch
+    pub fn png_bytes(input: &str, dpi: f32) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        Generate::png_bytes_with(input, dpi, &GenerateOptions::default())
+    }
+
+    pub fn png_bytes_with(input: &str, dpi: f32, options: &GenerateOptions) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let graphic_items = Generate::layout(input, options)?.graphic_items;
+        RasterBuilder::new(dpi).with_background(options.theme.background).png_bytes(graphic_items)
+    }
+
+    /// PDF document of the score on a single page, as there is no page breaking yet
+    pub fn pdf_bytes(input: &str, options: &PdfOptions) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        Generate::pdf_bytes_with(input, &GenerateOptions::default(), options)
+    }
+
+    pub fn pdf_bytes_with(input: &str, options: &GenerateOptions, pdf_options: &PdfOptions) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let graphic_items = Generate::layout(input, options)?.graphic_items;
+        Ok(PdfBuilder::new(pdf_options.clone()).with_background(options.theme.background).build(vec![graphic_items]))
+    }
+
+    fn layout(input: &str, options: &GenerateOptions) -> Result<Layout, Box<dyn std::error::Error>> {
         // Placeholder for score generation logic
         let cx = CoreContext::new();
+        *cx.accidental_mode.borrow_mut() = options.accidental_mode;
         // let _ = Parse2::sysitemlist2(cx, "clef G F | D8 -3 n-1 #4 3 r -2 -2 -3 / 0 ", false).unwrap();
-        let _ = Parse2::sysitemlist2(cx, input, false)?;
+        Parse2::sysitemlist2(cx, input, false)?;
 
         // let _ = Parse2::sysitemlist2(cx, "|clef G |0 -1 -2 -3 -4 -5 -6 -7  5 4 3 2 1 0 -1 -2 -3 -4 -5 % 1 1 2 3 4 5 6 7 ", false).unwrap();
 
         // dbg!(&cx.stemitems.borrow());
 
+        let fcx = FontContext::new();
+        if let Some((font_data, metadata_json)) = &options.music_font {
+            fcx.load_smufl_font(font_data.clone(), metadata_json)?;
+        }
+
         let scx = ScoreContext::new();
-        BuildScore::build(&scx, &cx)?;
+        *scx.multimeasure_rests.borrow_mut() = options.multimeasure_rests;
+        *scx.staff_spacing.borrow_mut() = options.staff_spacing.clone();
+        *scx.glyph_widths.borrow_mut() = GlyphWidths::from_font(fcx);
+        BuildScore::build(scx, cx)?;
 
         //-------------------------------------------------
         // Turn 180 degrees...
@@ -29,8 +109,8 @@ impl Generate {
         let rows = items[0].len();
         for row in 0..rows {
             let mut rowitems = Vec::new();
-            for col in 0..items.len() {
-                rowitems.push(items[col][row].clone());
+            for colitems in items.iter() {
+                rowitems.push(colitems[row].clone());
             }
             items2.push(rowitems);
         }
@@ -41,17 +121,237 @@ impl Generate {
         // calculate distances
         let allotments: Vec<f32> = scx.grid_column_allotment.borrow().to_vec();
         gcx.handle_column_spacing(&allotments, 2.3)?;
-        gcx.handle_row_heights()?;
+        let staff_spacing = scx.staff_spacing.borrow();
+        let mut min_distances = staff_spacing.get_min_distances(&cx.staff_groups.borrow(), rows);
+        BuildScore::apply_cross_staff_distances(cx, scx, &mut min_distances);
+        gcx.handle_row_heights(&min_distances, staff_spacing.padding)?;
+        BuildScore::build_cross_staff_beams(cx, scx, gcx)?;
 
         // create graphic items
         let mut graphic_items = GraphicItems::new();
-        let notelines = Render::render_notelines(&gcx);
+        let theme = &options.theme;
+        let notelines = Render::render_notelines(scx, gcx, theme);
         graphic_items.extend(notelines);
-        let glyphitems = Render::render_music_glyphitems(&gcx);
+        let glyphitems = Render::render_music_glyphitems(scx, gcx, fcx, theme);
         graphic_items.extend(glyphitems);
+        let bar_rests = Render::render_bar_rests(cx, scx, gcx, fcx, theme);
+        graphic_items.extend(bar_rests);
+        let repeats = Render::render_repeats(cx, scx, gcx, fcx, theme);
+        graphic_items.extend(repeats);
+        let staff_groups = Render::render_staff_groups(cx, scx, gcx, fcx, theme);
+        graphic_items.extend(staff_groups);
+        let part_names = Render::render_part_names(cx, gcx, fcx, theme, !options.continued_system);
+        graphic_items.extend(part_names);
+        Ok(Layout { cx, gcx, fcx, graphic_items })
+    }
+}
 
-        // save to svg
-        let svg_string = SvgBuilder::new().build(graphic_items, None);
-        Ok(svg_string)
+#[cfg(test)]
+mod tests {
+    use core::accidental::{Accidental, AccidentalMode};
+    use graphics::{
+        graphicitem::{GraphicItem, items_bounding_box},
+        stroke::Stroke,
+        theme::Theme,
+    };
+    use score::{
+        constants::{PART_NAME_SIZE, SPACE, SPACE_HALF, SPACE4},
+        scorecontext::StaffSpacing,
+    };
+
+    use pdf::builder::PdfOptions;
+
+    use crate::{
+        gridrender::Render,
+        output::{Generate, GenerateOptions, Layout},
+    };
+
+    #[test]
+    fn test_theme() {
+        let svg_string = Generate::svg_string("clef G | 0 color:red 1 color:highlight 2").unwrap();
+        assert!(svg_string.contains(r#"fill="white""#));
+        assert!(svg_string.contains(r#"fill="rgba(255,0,0,1)""#));
+        assert!(svg_string.contains(r#"fill="dodgerblue""#));
+
+        let options = GenerateOptions {
+            theme: Theme::dark().transparent(),
+            ..Default::default()
+        };
+        let svg_string = Generate::svg_string_with("clef G | 0 color:highlight 1", &options).unwrap();
+        assert!(!svg_string.contains("<rect x=\"0\" y=\"0\""));
+        assert!(!svg_string.contains("black"));
+        assert!(svg_string.contains(r#"stroke="rgba(160,160,160,1)""#));
+        // spaces kept free in the layout draw nothing
+        assert!(!svg_string.contains("rgba(0,0,0,"));
+        // the highlighted note's head, not the other one
+        let head = |id: &str| {
+            svg_string
+                .split(&format!("id=\"{}\"", id))
+                .nth(1)
+                .and_then(|rest| rest.split("/>").next())
+                .unwrap_or_default()
+                .to_string()
+        };
+        assert!(head("head-1").ends_with(r#"fill="orange""#));
+        assert!(head("head-0").ends_with(r#"fill="rgba(230,230,230,1)""#));
+    }
+
+    #[test]
+    fn test_part_names() {
+        let code = "part 1 name=Clarinet short=Cl. | part 2 name=Piano | 0 / 1";
+        let layout = Generate::layout(code, &GenerateOptions::default()).unwrap();
+        let fcx = layout.fcx;
+        let names = |first_system: bool| Render::render_part_names(layout.cx, layout.gcx, fcx, &Theme::default(), first_system);
+        let glyphs_count = |text: &str| fcx.get_sansserif_string_segments(PART_NAME_SIZE, text).len();
+
+        assert_eq!(names(true).len(), glyphs_count("Clarinet") + glyphs_count("Piano"));
+        // later systems get the abbreviations, parts without one get no name
+        let short_names = names(false);
+        assert_eq!(short_names.len(), glyphs_count("Cl."));
+        assert!(items_bounding_box(&short_names, fcx).2 < items_bounding_box(&names(true), fcx).2);
+
+        // generated as a later system the score carries the abbreviations
+        let options = GenerateOptions {
+            continued_system: true,
+            ..Default::default()
+        };
+        let items_count = |options: &GenerateOptions| Generate::layout(code, options).unwrap().graphic_items.len();
+        assert_eq!(items_count(&GenerateOptions::default()) - items_count(&options), names(true).len() - short_names.len());
+    }
+
+    #[test]
+    fn test_staff_spacing() {
+        let rows_heights = |options: &GenerateOptions| Generate::layout("0 / 1", options).unwrap().gcx.rows_heights.borrow().clone();
+        let default_heights = rows_heights(&GenerateOptions::default());
+        let options = GenerateOptions {
+            staff_spacing: StaffSpacing {
+                ungrouped: StaffSpacing::default().ungrouped + SPACE4,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let wider_heights = rows_heights(&options);
+        assert_eq!(wider_heights[0], default_heights[0]);
+        assert_eq!(wider_heights[1], default_heights[1] + SPACE4);
+    }
+
+    #[test]
+    fn test_cross_staff_beams() {
+        // laid out with the grid, so the stems and beams get ids like the other glyphs
+        let svg_string = Generate::svg_string("clef G F | d16 2 0 staff:+1 -3 -5 / d4 -3").unwrap();
+        assert_eq!(svg_string.matches(r#"class="beam" data-stemitem="0""#).count(), 2);
+        assert_eq!(svg_string.matches(r#"class="stem" data-stemitem="0""#).count(), 4);
+    }
+
+    #[test]
+    fn test_pdf() {
+        let code = "0 | bl | nav segno | 1 | bl | nav ds";
+        let options = GenerateOptions {
+            theme: Theme::dark(),
+            ..Default::default()
+        };
+        let pdf = Generate::pdf_bytes_with(code, &options, &PdfOptions::default()).unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        // the dark background and foreground, with the words written as text in the foreground colour
+        assert!(text.contains("0.118 0.118 0.118 rg"));
+        assert!(text.contains("q\n0.902 0.902 0.902 rg\nBT\n/F1 "));
+        assert!(text.contains(" Tj\nET"));
+    }
+
+    #[test]
+    fn test_navigation_marks() {
+        // the signs are music glyphs, only the words are text
+        let svg_string = Generate::svg_string("0 | bl | nav segno | 1 | bl | nav tocoda | 2").unwrap();
+        assert!(svg_string.contains("To Coda"));
+        assert!(!svg_string.contains('\u{1D10B}') && !svg_string.contains('\u{1D10C}'));
+        // the built-in font has no coda glyph, so it is drawn
+        assert!(svg_string.contains("<ellipse"));
+    }
+
+    #[test]
+    fn test_engraving_defaults() {
+        // line thicknesses come from the music font, in staff spaces
+        let json = r#"{ "engravingDefaults": { "staffLineThickness": 0.15, "stemThickness": 0.2, "thinBarlineThickness": 0.25 } }"#;
+        let options = GenerateOptions {
+            music_font: Some((include_bytes!("../../fonts/assets/MTF-Cadence-Fin.ttf").to_vec(), json.to_string())),
+            ..Default::default()
+        };
+        let items = Generate::layout("0 | bl | 1", &options).unwrap().graphic_items;
+        assert!(items.iter().any(|item| matches!(item, GraphicItem::Line(.., Stroke::Solid(width, _), _) if *width == 1.5)));
+        let rect_widths = items
+            .iter()
+            .filter_map(|item| if let GraphicItem::Rect(_, _, width, ..) = item { Some(*width) } else { None })
+            .collect::<Vec<_>>();
+        assert!(rect_widths.contains(&2.0));
+        assert!(rect_widths.contains(&2.5));
+    }
+    #[test]
+    fn test_accidental_mode() {
+        let accidentals = |options: &GenerateOptions| {
+            Generate::layout("clef G | key # | 3 #3 n3", options)
+                .unwrap()
+                .cx
+                .map_headid_accidental
+                .borrow()
+                .values()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        // as written the F is sharp from the key, and the sharp written out anyway is kept
+        assert_eq!(accidentals(&GenerateOptions::default()), vec![Accidental::None, Accidental::Sharp, Accidental::Natural]);
+        // as sounding pitch the plain F needs a natural, and each change after it an accidental
+        let options = GenerateOptions {
+            accidental_mode: AccidentalMode::Sounding,
+            ..Default::default()
+        };
+        assert_eq!(accidentals(&options), vec![Accidental::Natural, Accidental::Sharp, Accidental::Natural]);
+    }
+    #[test]
+    fn test_clef_glyphs() {
+        let texts = |options: &GenerateOptions| {
+            let items = Generate::layout("clef TAB G8vb | 0 / 0", options).unwrap().graphic_items;
+            items
+                .iter()
+                .filter_map(|item| if let GraphicItem::Text(_, _, text, ..) = item { Some(text.clone()) } else { None })
+                .collect::<Vec<_>>()
+        };
+        // the built-in font has neither glyph, so the letters and the octave mark are text
+        assert_eq!(texts(&GenerateOptions::default()), vec!["T", "A", "B", "8"]);
+
+        // with the glyphs in the music font nothing is text, the G clef codepoint stands in for them here
+        let json = r#"{ "optionalGlyphs": { "6stringTabClef": { "codepoint": "U+0026" }, "gClef8vb": { "codepoint": "U+0026" } } }"#;
+        let options = GenerateOptions {
+            music_font: Some((include_bytes!("../../fonts/assets/MTF-Cadence-Fin.ttf").to_vec(), json.to_string())),
+            ..Default::default()
+        };
+        assert!(texts(&options).is_empty());
+    }
+
+    #[test]
+    fn test_bar_rest_glyph() {
+        let rest_rects = |layout: &Layout| {
+            layout
+                .graphic_items
+                .iter()
+                .filter_map(|item| if let GraphicItem::Rect(_, _, width, SPACE_HALF, ..) = item { Some(*width) } else { None })
+                .collect::<Vec<_>>()
+        };
+        // the whole rest glyph of the music font, centred in the bar
+        let layout = Generate::layout("0 | bl | bp", &GenerateOptions::default()).unwrap();
+        let rest_width = layout.fcx.get_music_glyph_width("restWhole", SPACE).unwrap();
+        assert!(rest_rects(&layout).is_empty());
+        let mut path_widths = layout.graphic_items.iter().filter(|item| matches!(item, GraphicItem::Path(..))).map(|item| {
+            let bbox = items_bounding_box(&vec![item.clone()], layout.fcx);
+            bbox.2 - bbox.0
+        });
+        assert!(path_widths.any(|width| (width - rest_width).abs() < 0.5));
+
+        // a font without the glyph gets a block as wide as the rest it is measured for
+        let json = r#"{ "optionalGlyphs": { "restWhole": { "codepoint": "U+E4E3" } } }"#;
+        let options = GenerateOptions {
+            music_font: Some((include_bytes!("../../fonts/assets/MTF-Cadence-Fin.ttf").to_vec(), json.to_string())),
+            ..Default::default()
+        };
+        assert_eq!(rest_rects(&Generate::layout("0 | bl | bp", &options).unwrap()), vec![SPACE]);
     }
 }
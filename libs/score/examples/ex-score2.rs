@@ -6,12 +6,12 @@ use score::{build::BuildScore, scorecontext::ScoreContext};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cx = CoreContext::new();
-    let _ = Parse2::sysitemlist2(cx, "clef G x | bl", false).unwrap();
+    Parse2::sysitemlist2(cx, "clef G x | bl", false).unwrap();
 
     let scx = ScoreContext::new();
     // scx.build_sysitems(&cx.sysitems.borrow(), &cx.complexes.borrow())?;
 
-    BuildScore::build(&scx, &cx)?;
+    BuildScore::build(scx, cx)?;
     dbg!(&scx.grid_columns.borrow());
     Ok(())
 }
@@ -5,11 +5,11 @@ use core::{
     complex::{self, ComplexConfiguration, ComplexType},
     context::CoreContext,
     direction::DirectionUD,
-    duration::NoteDuration,
     head::{HeadItem, HeadType, HeadVariant},
     hpart::{HPartItemsColumnType, HPartMusicType, HPartType, VoiceType2},
-    note::{NoteItem, NoteType},
-    stems::stemitems::{StemHeadPosition, StemType},
+    note::{NoteId, NoteItem, NoteType},
+    rest::RestType,
+    stems::stemitems::{StemHeadPosition, StemItemUtils, StemType},
     ties::CheckedTieTo,
 };
 
@@ -18,50 +18,98 @@ use graphics::{
     rectangle::{Rectangle, rectangle_overlap_x},
 };
 
-use grid::griditem::GridItemType;
+use grid::{gridcontext::GridContext, griditem::GridItemType};
 
 use utils::f32_ext::{half::F32ExtHalf, round::F32ExtRound2};
 
 use crate::{
     buildutils::BuildUtils,
     constants::*,
-    glyphitem::{GlyphItem, GlyphRectangle},
-    scorecontext::ScoreContext,
+    glyphitem::{GlyphItem, GlyphRectangle, GlyphTag},
+    scorecontext::{GlyphWidths, ScoreContext},
 };
 
 pub struct BuildScore;
 impl BuildScore {
     pub fn build(scx: &ScoreContext, cx: &CoreContext) -> Result<(), Box<dyn std::error::Error>> {
-        for (_column_idx, item) in cx.columns.borrow().iter().enumerate() {
+        // columns condensed into a multi-measure rest share the grid column that follows it
+        let mut skip_until: usize = 0;
+        let mut barlines_before: usize = 0;
+        for (col_idx, item) in cx.columns.borrow().iter().enumerate() {
+            // a barline column belongs to the bar it ends
+            let bar_idx = barlines_before;
+            if matches!(item.hptype, HPartItemsColumnType::Barlines(_)) {
+                barlines_before += 1;
+            }
+            let grid_col_idx = scx.grid_columns.borrow().len();
+            scx.map_colidx_gridcolidx.borrow_mut().insert(col_idx, grid_col_idx);
+            if col_idx < skip_until {
+                continue;
+            }
+            if *scx.multimeasure_rests.borrow() {
+                let bars = BuildScore::get_multirest_bars(cx, col_idx);
+                if bars > 1 {
+                    scx.map_colidx_multirest.borrow_mut().insert(col_idx, bars);
+                    skip_until = col_idx + bars * 2 - 1;
+                }
+            }
             match item.hptype {
                 HPartItemsColumnType::Clefs(ref ids) => {
-                    Self::build_clefs(scx, cx, ids.clone())?;
+                    Self::build_clefs(scx, cx, ids.clone(), bar_idx)?;
                 }
                 HPartItemsColumnType::Barlines(ref ids) => {
-                    Self::build_barlines(scx, cx, ids.clone())?;
+                    Self::build_barlines(scx, cx, ids.clone(), bar_idx)?;
                 }
                 HPartItemsColumnType::Musics(ref ids) => {
-                    Self::build_music_parts(scx, cx, ids.clone(), item.position, item.duration)?;
+                    Self::build_music_parts(scx, cx, ids.clone(), item.position, item.duration, bar_idx)?;
                 }
             }
         }
+        let grid_col_idx = scx.grid_columns.borrow().len();
+        scx.map_colidx_gridcolidx.borrow_mut().insert(cx.columns.borrow().len(), grid_col_idx);
         Ok(())
     }
 
-    fn build_clefs(scx: &ScoreContext, cx: &CoreContext, ids: Vec<usize>) -> Result<(), Box<dyn std::error::Error>> {
+    /// Number of empty bars from a music column on, in a single part score, separated by plain barlines without repeats or marks
+    fn get_multirest_bars(cx: &CoreContext, col_idx: usize) -> usize {
+        let columns = cx.columns.borrow();
+        let hparts = cx.hparts.borrow();
+        let voltas = cx.voltas.borrow();
+        let navigations = cx.map_colidx_navigations.borrow();
+        let is_marked = |col_idx: usize| navigations.contains_key(&col_idx) || voltas.iter().any(|volta| volta.from_col_idx == col_idx || volta.to_col_idx == col_idx);
+        let is_rest_bar = |col_idx: usize| match columns.get(col_idx).map(|column| &column.hptype) {
+            Some(HPartItemsColumnType::Musics(ids)) => ids.len() == 1 && hparts[ids[0]].hptype.is_bar_rest(),
+            _ => false,
+        };
+        let is_plain_barline = |col_idx: usize| match columns.get(col_idx).map(|column| &column.hptype) {
+            Some(HPartItemsColumnType::Barlines(ids)) => ids.iter().all(|id| matches!(hparts[*id].hptype, HPartType::Barline(BarlineType::Single))),
+            _ => false,
+        };
+
+        if !is_rest_bar(col_idx) {
+            return 0;
+        }
+        let mut bars = 1;
+        while is_plain_barline(col_idx + bars * 2 - 1) && is_rest_bar(col_idx + bars * 2) && !is_marked(col_idx + bars * 2 - 1) && !is_marked(col_idx + bars * 2) {
+            bars += 1;
+        }
+        bars
+    }
+
+    fn build_clefs(scx: &ScoreContext, cx: &CoreContext, ids: Vec<usize>, bar_idx: usize) -> Result<(), Box<dyn std::error::Error>> {
         let cx_hparts = cx.hparts.borrow();
         let hparts = ids.iter().map(|id| &cx_hparts[*id]).collect::<Vec<_>>();
 
         let mut column_griditems: Vec<GridItemType<GlyphItem>> = Vec::new();
-        hparts.iter().for_each(|hpart| {
+        hparts.iter().enumerate().for_each(|(part_idx, hpart)| {
             if let HPartType::Clef(clef) = &hpart.hptype {
                 match clef {
                     ClefSignature::None => {
                         column_griditems.push(GridItemType::Empty);
                     }
                     _ => {
-                        let glyph: GlyphItem = GlyphItem::Clef(clef.clone());
-                        let rect = (0.0, -SPACE2, CLEF_WIDTH, SPACE4);
+                        let glyph: GlyphItem = GlyphItem::Clef(clef.clone()).tagged(|tag| tag_part_bar(tag, part_idx, bar_idx));
+                        let rect = (0.0, -SPACE2, scx.glyph_widths.borrow().clef, SPACE4);
                         column_griditems.push(GridItemType::Rectangles(vec![(rect, glyph)]));
                     }
                 }
@@ -75,19 +123,27 @@ impl BuildScore {
         Ok(())
     }
 
-    fn build_barlines(scx: &ScoreContext, cx: &CoreContext, ids: Vec<usize>) -> Result<(), Box<dyn std::error::Error>> {
+    fn build_barlines(scx: &ScoreContext, cx: &CoreContext, ids: Vec<usize>, bar_idx: usize) -> Result<(), Box<dyn std::error::Error>> {
         let cx_hparts = cx.hparts.borrow();
         let hparts = ids.iter().map(|id| &cx_hparts[*id]).collect::<Vec<_>>();
 
+        let widths = scx.glyph_widths.borrow();
+        // a thin line and the dots on each side of the thick line
+        let repeat_side = widths.barline_gap * 2.0 + widths.barline_thin + BARLINE_REPEAT_DOT * 2.0;
         let mut column_griditems: Vec<GridItemType<GlyphItem>> = Vec::new();
-        hparts.iter().for_each(|hpart| {
+        hparts.iter().enumerate().for_each(|(part_idx, hpart)| {
             if let HPartType::Barline(btype) = &hpart.hptype {
                 let rect = match btype {
                     BarlineType::Double => (0.0, -SPACE2, BARLINE_DOUBLE_WIDTH, SPACE4),
                     BarlineType::Final => (0.0, -SPACE2, BARLINE_FINAL_WIDTH, SPACE4),
+                    BarlineType::Heavy => (0.0, -SPACE2, widths.barline_thick, SPACE4),
+                    BarlineType::RepeatStart | BarlineType::RepeatEnd => (0.0, -SPACE2, widths.barline_thick + repeat_side, SPACE4),
+                    BarlineType::RepeatBoth => (0.0, -SPACE2, widths.barline_thick + repeat_side * 2.0, SPACE4),
+                    BarlineType::Tick => (0.0, -SPACE2 - SPACE_HALF, BARLINE_WIDTH, SPACE),
+                    BarlineType::Invisible => (0.0, -SPACE2, 0.0, SPACE4),
                     _ => (0.0, -SPACE2, BARLINE_WIDTH, SPACE4),
                 };
-                let glyph: GlyphItem = GlyphItem::Barline(btype.clone());
+                let glyph: GlyphItem = GlyphItem::Barline(btype.clone()).tagged(|tag| tag_part_bar(tag, part_idx, bar_idx));
                 let item: GlyphRectangle = (rect, glyph);
                 column_griditems.push(GridItemType::Rectangles(vec![item.clone()]));
             } else {
@@ -100,7 +156,7 @@ impl BuildScore {
         Ok(())
     }
 
-    fn build_music_parts(scx: &ScoreContext, cx: &CoreContext, ids: Vec<usize>, _position: usize, duration: usize) -> Result<(), Box<dyn std::error::Error>> {
+    fn build_music_parts(scx: &ScoreContext, cx: &CoreContext, ids: Vec<usize>, _position: usize, duration: usize, bar_idx: usize) -> Result<(), Box<dyn std::error::Error>> {
         let cx_hparts = cx.hparts.borrow();
         let cx_complexes = cx.complexes.borrow();
         let hparts = ids.iter().map(|id| &cx_hparts[*id]).collect::<Vec<_>>();
@@ -108,7 +164,7 @@ impl BuildScore {
         let (positions, _durations, allotments, map_ids) = BuildUtils::get_complexes_positions_allotments(cx, &hparts, duration)?;
 
         //-----------------------------------------
-        for (_hpartidx, hpart) in hparts.iter().enumerate() {
+        for hpart in hparts.iter() {
             match &hpart.hptype {
                 HPartType::Music { complexes: _, mtype, attr: _ } => {
                     match &mtype {
@@ -136,25 +192,68 @@ impl BuildScore {
         }
 
         //------------------------------------------------------
+        // bar rests are drawn centred in the bar at render time, a bar with rests only gets a column to make room for them
+        if positions.is_empty() {
+            let col_idx = hparts.first().map(|hpart| hpart.col_idx).unwrap_or(0);
+            let width = if scx.map_colidx_multirest.borrow().contains_key(&col_idx) {
+                MULTIREST_WIDTH
+            } else {
+                BAR_REST_WIDTH
+            };
+            let column_griditems = (0..parts_count)
+                .map(|part_idx| GridItemType::Rectangles(vec![((0.0, -SPACE, width, SPACE2), GlyphItem::BarRest.tagged(|tag| tag_part_bar(tag, part_idx, bar_idx)))]))
+                .collect::<Vec<_>>();
+            scx.grid_columns.borrow_mut().push(column_griditems);
+            scx.grid_column_allotment.borrow_mut().push(0.);
+        }
 
         //------------------------------------------------------
 
         for (idx, position) in positions.iter().enumerate() {
+            // clef changes get a column of their own before the notes
+            let clef_changes = (0..parts_count)
+                .map(|part_idx| {
+                    let complex = &cx_complexes[*map_ids.get(&(part_idx, *position))?];
+                    let notes = match &complex.ctype {
+                        ComplexType::Upper(note) | ComplexType::Lower(note) => vec![note],
+                        ComplexType::UpperAndLower(upper, lower, _) => vec![upper, lower],
+                    };
+                    notes.iter().find_map(|note| cx.map_noteid_clef.borrow().get(&note.id).cloned())
+                })
+                .collect::<Vec<_>>();
+            if clef_changes.iter().any(|clef| clef.is_some()) {
+                let column_griditems = clef_changes
+                    .into_iter()
+                    .enumerate()
+                    .map(|(part_idx, clef)| match clef {
+                        Some(clef) => GridItemType::Rectangles(vec![(
+                            (0.0, -SPACE2, scx.glyph_widths.borrow().clef * CLEF_CHANGE_SCALE, SPACE4),
+                            GlyphItem::ClefChange(clef).tagged(|tag| tag_part_bar(tag, part_idx, bar_idx)),
+                        )]),
+                        None => GridItemType::Empty,
+                    })
+                    .collect::<Vec<_>>();
+                scx.grid_columns.borrow_mut().push(column_griditems);
+                scx.grid_column_allotment.borrow_mut().push(0.);
+            }
+
             // each position corresponds to a column in the grid
-            let mut column_griditems: Vec<GridItemType<GlyphItem>> = Vec::new();
+            let grid_col_idx = scx.grid_columns.borrow().len();
+            let mut column_griditems: Vec<GridItemType<GlyphItem>> = vec![GridItemType::Empty; parts_count];
 
             for part_idx in 0..parts_count {
-                if map_ids.contains_key(&(part_idx, *position)) {
-                    if let Some(complex_id) = map_ids.get(&(part_idx, *position)) {
-                        let complex = &cx_complexes[*complex_id];
-                        let rects = BuildScore::build_complex(cx, complex, part_idx, *position)?;
-                        column_griditems.push(GridItemType::Rectangles(rects));
-                    } else {
-                        panic!("Complex ID not found for part_idx: {}, position: {}", part_idx, position);
+                if let Some(complex_id) = map_ids.get(&(part_idx, *position)) {
+                    let complex = &cx_complexes[*complex_id];
+                    for (row_idx, note_ids, rects) in BuildScore::build_complex_staves(cx, &scx.glyph_widths.borrow(), complex, part_idx, *position, parts_count)? {
+                        for note_id in note_ids {
+                            scx.map_noteid_gridcell.borrow_mut().insert(note_id, (row_idx, grid_col_idx));
+                        }
+                        let rects = rects.into_iter().map(|(rect, glyph)| (rect, glyph.tagged(|tag| tag_part_bar(tag, part_idx, bar_idx)))).collect();
+                        match &mut column_griditems[row_idx] {
+                            GridItemType::Rectangles(row_rects) => row_rects.extend(rects),
+                            item => *item = GridItemType::Rectangles(rects),
+                        }
                     }
-                } else {
-                    column_griditems.push(GridItemType::Empty);
-                    // column_griditems.push(GridItemType::Rectangles(vec![((0.0, 0.0, 20.0, 10.0), GlyphItem::XRect(Color::Red))]));
                 }
             }
             scx.grid_columns.borrow_mut().push(column_griditems);
@@ -165,21 +264,151 @@ impl BuildScore {
         Ok(())
     }
 
-    fn build_complex(cx: &CoreContext, complex: &complex::Complex, part_idx: usize, position: usize) -> Result<Vec<(Rectangle, GlyphItem)>, Box<dyn std::error::Error>> {
+    /// Rectangles of a complex by the row they are drawn on, a voice moved to a neighbouring staff is built on its own
+    #[allow(clippy::type_complexity)]
+    fn build_complex_staves(
+        cx: &CoreContext,
+        widths: &GlyphWidths,
+        complex: &complex::Complex,
+        part_idx: usize,
+        position: usize,
+        rows_count: usize,
+    ) -> Result<Vec<(usize, Vec<NoteId>, Vec<(Rectangle, GlyphItem)>)>, Box<dyn std::error::Error>> {
+        let get_row_idx = |note: &NoteItem| -> Result<usize, Box<dyn std::error::Error>> {
+            let offset = cx.map_noteid_staffoffset.borrow().get(&note.id).cloned().unwrap_or(0);
+            let row_idx = part_idx.checked_add_signed(offset as isize).filter(|row_idx| *row_idx < rows_count);
+            Ok(row_idx.ok_or(format!("Note {} is moved to a staff that does not exist", note.id))?)
+        };
+
+        match &complex.ctype {
+            ComplexType::Upper(note) | ComplexType::Lower(note) => Ok(vec![(get_row_idx(note)?, vec![note.id], BuildScore::build_complex(cx, widths, complex, part_idx, position)?)]),
+            ComplexType::UpperAndLower(upper, lower, _) => {
+                let (upper_row_idx, lower_row_idx) = (get_row_idx(upper)?, get_row_idx(lower)?);
+                if upper_row_idx == lower_row_idx {
+                    return Ok(vec![(upper_row_idx, vec![upper.id, lower.id], BuildScore::build_complex(cx, widths, complex, part_idx, position)?)]);
+                }
+                let mut staves = Vec::new();
+                for (row_idx, note_id, ctype) in [
+                    (upper_row_idx, upper.id, ComplexType::Upper(upper.clone())),
+                    (lower_row_idx, lower.id, ComplexType::Lower(lower.clone())),
+                ] {
+                    let voice = complex::Complex {
+                        id: complex.id,
+                        part_id: complex.part_id,
+                        position: complex.position,
+                        duration: complex.duration,
+                        position2: complex.position2,
+                        ctype,
+                        offsets: complex::ComplexHeadOffsets::None,
+                    };
+                    staves.push((row_idx, vec![note_id], BuildScore::build_complex(cx, widths, &voice, part_idx, position)?));
+                }
+                Ok(staves)
+            }
+        }
+    }
+
+    /// Smallest distances between staves raised to fit the stems and beams of cross-staff groups between them
+    pub fn apply_cross_staff_distances(cx: &CoreContext, scx: &ScoreContext, min_distances: &mut [f32]) {
+        let map_noteid_gridcell = scx.map_noteid_gridcell.borrow();
+        for stemitem in cx.stemitems.borrow().iter() {
+            let Some((upper, _)) = StemItemUtils::split_cross_staff(cx, stemitem) else { continue };
+            let Some((row_idx, _)) = upper.first().and_then(|item| map_noteid_gridcell.get(&item.note.id)) else {
+                continue;
+            };
+            if let Some(distance) = min_distances.get_mut(*row_idx) {
+                *distance = distance.max(StemItemUtils::get_cross_staff_min_distance(cx, stemitem) * SPACE_HALF);
+            }
+        }
+    }
+
+    /// Stems and beams of groups beamed across two staves, added to the grid once the distance between the staves is known.
+    /// Upper staff notes get stems down to the beams and lower staff notes stems up, the beams go with the first note on the upper staff.
+    pub fn build_cross_staff_beams(cx: &CoreContext, scx: &ScoreContext, gcx: &GridContext<GlyphItem>) -> Result<(), Box<dyn std::error::Error>> {
+        let rows_heights = gcx.rows_heights.borrow();
+        let cols_widths = gcx.cols_widths.borrow();
+        let map_noteid_gridcell = scx.map_noteid_gridcell.borrow();
+        let widths = scx.glyph_widths.borrow();
+        let col_x = |grid_col_idx: usize| cols_widths.iter().take(grid_col_idx + 1).sum::<f32>();
+
+        for stemitem in cx.stemitems.borrow().iter() {
+            let StemType::NotesBeamed(items, base_values) = &stemitem.stype else { continue };
+            let Some((upper, _)) = StemItemUtils::split_cross_staff(cx, stemitem) else { continue };
+            let Some((upper_row_idx, _)) = upper.first().and_then(|item| map_noteid_gridcell.get(&item.note.id)).cloned() else {
+                continue;
+            };
+            let Some(staff_distance) = rows_heights.get(upper_row_idx + 1) else { continue };
+            let beam_level = StemItemUtils::calculate_cross_staff_stem_lengths(cx, stemitem, staff_distance / SPACE_HALF)?;
+
+            // the beams all notes share, stacked towards the lower staff
+            let beams = base_values.iter().map(|value| (value / 4).max(1).ilog2()).min().unwrap_or(1);
+            let beams_height = beams as f32 * (widths.beam + widths.beam_gap) - widths.beam_gap;
+
+            // stems from the heads on either staff to the beams, stems down reach the lowest one
+            let mut stems: Vec<(usize, f32, GlyphTag)> = Vec::new();
+            for item in items.iter() {
+                let (Some((row_idx, grid_col_idx)), Some((direction, top_level, bottom_level))) =
+                    (map_noteid_gridcell.get(&item.note.id).cloned(), cx.map_noteid_stemitemlevels.borrow().get(&item.note.id).cloned())
+                else {
+                    continue;
+                };
+                let head_offset_x = cx.map_noteid_headoffsetx.borrow().get(&item.note.id).cloned().unwrap_or(0.0);
+                let stem_x = head_offset_x
+                    + if direction == DirectionUD::Up {
+                        widths.get_head_width(&item.note.duration) - widths.stem
+                    } else {
+                        0.0
+                    };
+                let extra = if direction == DirectionUD::Down { beams_height - widths.beam / 2.0 } else { 0.0 };
+                // the stem belongs to the note, as its head does
+                let mut tag = get_cell_note_tag(gcx, row_idx, grid_col_idx, item.note.id).unwrap_or_default();
+                tag.stemitem_id = Some(stemitem.id);
+                let rect: Rectangle = (stem_x, top_level * SPACE_HALF, widths.stem, (bottom_level - top_level) * SPACE_HALF + extra);
+                push_to_cell(gcx, row_idx, grid_col_idx, (rect, GlyphItem::Tagged(tag.clone(), Box::new(GlyphItem::Stem))));
+                stems.push((grid_col_idx, stem_x, tag));
+            }
+
+            let (Some((first_col_idx, first_x, first_tag)), Some((last_col_idx, last_x, _))) = (stems.first(), stems.last()) else {
+                continue;
+            };
+            let width = col_x(*last_col_idx) + last_x - col_x(*first_col_idx) - first_x + widths.stem;
+            let tag = GlyphTag {
+                part_idx: first_tag.part_idx,
+                bar_idx: first_tag.bar_idx,
+                stemitem_id: Some(stemitem.id),
+                ..Default::default()
+            };
+            for beam_idx in 0..beams {
+                let beam_y = beam_level * SPACE_HALF - widths.beam / 2.0 + beam_idx as f32 * (widths.beam + widths.beam_gap);
+                let rect: Rectangle = (*first_x, beam_y, width, widths.beam);
+                push_to_cell(gcx, upper_row_idx, *first_col_idx, (rect, GlyphItem::Tagged(tag.clone(), Box::new(GlyphItem::Beam))));
+            }
+        }
+        Ok(())
+    }
+
+    fn build_complex(cx: &CoreContext, widths: &GlyphWidths, complex: &complex::Complex, part_idx: usize, position: usize) -> Result<Vec<(Rectangle, GlyphItem)>, Box<dyn std::error::Error>> {
         let mut rects: Vec<(Rectangle, GlyphItem)> = Vec::new();
 
         match &complex.ctype {
             ComplexType::Upper(note) | ComplexType::Lower(note) => {
-                let rs = BuildScore::build_notetype(cx, note, part_idx, position, None, ComplexConfiguration::OneNote)?;
+                let rs = BuildScore::build_notetype(cx, widths, note, part_idx, position, None, ComplexConfiguration::OneNote)?;
 
                 let leftmost_head_x: f32 = leftmost_x(&rs);
                 rects.extend(rs);
 
                 //------------------------
                 // accidentals
-                let mut accidentals = collect_accidentals(note);
+                let mut accidentals = collect_accidentals(cx, note);
                 sort_accidentals(&mut accidentals);
-                let leftmost_accidental_x = create_glyphsrectangles_accidentals(&accidentals, &mut rects);
+                let first_accidental_idx = rects.len();
+                let leftmost_accidental_x = create_glyphsrectangles_accidentals(widths, &accidentals, &mut rects);
+                tag_accidentals(cx, &mut rects[first_accidental_idx..], &[note]);
+
+                //------------------------
+                // dots, a lower voice on its own still moves them below
+                let direction = if matches!(complex.ctype, ComplexType::Lower(_)) { DirectionUD::Down } else { DirectionUD::Up };
+                create_glyphsrectangles_dots(&[(note, direction)], &mut rects);
 
                 //---------------------------
                 // extra space for first complex
@@ -189,28 +418,30 @@ impl BuildScore {
             }
 
             ComplexType::UpperAndLower(upper, lower, _) => {
-                // get values to avoid collisions with rests
-                let upper_bottom_y = get_upper_bottom_level(upper) * SPACE_HALF;
-                let lower_top_y = get_lower_top_level(lower) * SPACE_HALF;
-
                 //------------------------
                 // upper
-                let rs = BuildScore::build_notetype(cx, upper, part_idx, position, Some(lower_top_y), ComplexConfiguration::TwoNotes(DirectionUD::Up))?;
+                let rs = BuildScore::build_notetype(cx, widths, upper, part_idx, position, Some(lower), ComplexConfiguration::TwoNotes(DirectionUD::Up))?;
                 let leftmost_upper_x: f32 = leftmost_x(&rs);
                 rects.extend(rs);
 
                 //------------------------
                 // lower
-                let rs = BuildScore::build_notetype(cx, lower, part_idx, position, Some(upper_bottom_y), ComplexConfiguration::TwoNotes(DirectionUD::Down))?;
+                let rs = BuildScore::build_notetype(cx, widths, lower, part_idx, position, Some(upper), ComplexConfiguration::TwoNotes(DirectionUD::Down))?;
                 let leftmost_lower_x: f32 = leftmost_x(&rs);
                 rects.extend(rs);
 
                 //------------------------
                 // accidentals
-                let mut accidentals = collect_accidentals(upper);
-                accidentals.extend(collect_accidentals(lower));
+                let mut accidentals = collect_accidentals(cx, upper);
+                accidentals.extend(collect_accidentals(cx, lower));
                 sort_accidentals(&mut accidentals);
-                let leftmost_accidental_x = create_glyphsrectangles_accidentals(&accidentals, &mut rects);
+                let first_accidental_idx = rects.len();
+                let leftmost_accidental_x = create_glyphsrectangles_accidentals(widths, &accidentals, &mut rects);
+                tag_accidentals(cx, &mut rects[first_accidental_idx..], &[upper, lower]);
+
+                //------------------------
+                // dots
+                create_glyphsrectangles_dots(&[(upper, DirectionUD::Up), (lower, DirectionUD::Down)], &mut rects);
 
                 //---------------------------
                 // extra space for first complex
@@ -231,25 +462,27 @@ impl BuildScore {
         Ok(rects)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn build_notetype(
         cx: &CoreContext,
+        widths: &GlyphWidths,
         note: &NoteItem,
         part_idx: usize,
         position: usize,
-        y_rest_offset: Option<f32>,
+        other: Option<&NoteItem>,
         cplx_config: ComplexConfiguration,
     ) -> Result<Vec<(Rectangle, GlyphItem)>, Box<dyn std::error::Error>> {
         let mut rects: Vec<(Rectangle, GlyphItem)> = Vec::new();
 
         match note.ntype {
             NoteType::Heads(ref heads) => {
-                let rs = BuildScore::build_heads(cx, note, heads, part_idx, position, cplx_config.clone())?;
+                let rs = BuildScore::build_heads(cx, widths, note, heads, part_idx, position, cplx_config.clone())?;
                 rects.extend(rs);
-                let rs = BuildScore::build_stem_root(cx, note, part_idx, position, cplx_config)?;
+                let rs = BuildScore::build_stem_root(cx, widths, note, part_idx, position, cplx_config)?;
                 rects.extend(rs);
             }
             NoteType::Rest => {
-                let rs = BuildScore::build_rest(cx, note, part_idx, position, y_rest_offset)?;
+                let rs = BuildScore::build_rest(cx, widths, note, part_idx, position, other, cplx_config)?;
                 rects.extend(rs);
             }
             NoteType::Space => {
@@ -261,11 +494,22 @@ impl BuildScore {
             }
         }
 
-        Ok(rects)
+        let color = cx.map_noteid_color.borrow().get(&note.id).cloned();
+        Ok(rects
+            .into_iter()
+            .map(|(rect, glyph)| {
+                let glyph = glyph.tagged(|tag| {
+                    tag.note_id = Some(note.id);
+                    tag.color = color;
+                });
+                (rect, glyph)
+            })
+            .collect())
     }
 
     fn build_heads(
         cx: &CoreContext,
+        widths: &GlyphWidths,
         note: &NoteItem,
         heads: &[HeadItem],
         part_idx: usize,
@@ -274,66 +518,48 @@ impl BuildScore {
     ) -> Result<Vec<(Rectangle, GlyphItem)>, Box<dyn std::error::Error>> {
         let mut rects: Vec<(Rectangle, GlyphItem)> = Vec::new();
         for head in heads {
-            let rs = BuildScore::build_head(cx, note, head, heads, part_idx, position, cplx_config.clone())?;
+            let rs = BuildScore::build_head(cx, widths, note, head, heads, part_idx, position, cplx_config.clone())?;
             rects.extend(rs);
         }
 
-        //-------------------------------------------------
-        // helper lines
-
-        let head_width = get_head_width(&note.duration);
-
-        let cx_map_head_position = cx.map_head_position.borrow();
-        let heads_above = heads
-            .iter()
-            .filter(|h| h.level <= -6)
-            .map(|head| {
-                //
-                let head_x: f32 = if !cx_map_head_position.contains_key(&head.id) {
-                    0.
-                } else {
-                    match cx_map_head_position.get(&head.id).cloned().unwrap_or(StemHeadPosition::Center) {
-                        StemHeadPosition::Center => 0.,
-                        StemHeadPosition::Left => -head_width,
-                        StemHeadPosition::Right => head_width,
-                    }
-                };
-                let note_x = cx.map_noteid_headoffsetx.borrow().get(&note.id).cloned().unwrap_or(0.0);
-
-                (head, head_x, note_x, head_width)
-            })
-            .collect::<Vec<_>>();
-
-        let heads_above_min_x = heads_above.iter().map(|(_head, head_x, _note_x, _head_width)| head_x).fold(f32::INFINITY, |a, &b| a.min(b));
-
-        let heads_above_max_x = heads_above.iter().map(|(_head, head_x, _note_x, _head_width)| head_x).fold(f32::NEG_INFINITY, |a, b| a.max(*b)) + head_width;
-
-        dbg!(&heads_above_min_x, &heads_above_max_x);
-
-        // let heads_below = heads.iter().filter(|h| h.level >= 6).collect::<Vec<_>>();
-
-        dbg!(&heads_above);
-
         Ok(rects)
     }
 
-    fn build_rest(_cx: &CoreContext, note: &NoteItem, _part_idx: usize, _position: usize, y_rest_offset: Option<f32>) -> Result<Vec<(Rectangle, GlyphItem)>, Box<dyn std::error::Error>> {
+    fn build_rest(
+        cx: &CoreContext,
+        widths: &GlyphWidths,
+        note: &NoteItem,
+        _part_idx: usize,
+        _position: usize,
+        other: Option<&NoteItem>,
+        cplx_config: ComplexConfiguration,
+    ) -> Result<Vec<(Rectangle, GlyphItem)>, Box<dyn std::error::Error>> {
         let mut rects: Vec<(Rectangle, GlyphItem)> = Vec::new();
 
-        let y_offset = y_rest_offset.unwrap_or(0.0);
+        let rtype = note.duration.get_rest_type();
+        let level = match cx.map_noteid_restlevel.borrow().get(&note.id) {
+            Some(level) => *level,
+            None => match cplx_config {
+                ComplexConfiguration::TwoNotes(direction) => get_rest_level(&rtype, other, direction),
+                _ => 0,
+            },
+        };
 
-        let rect: Rectangle = (0., -SPACE + y_offset, SPACE, SPACE2);
-        let item: GlyphItem = GlyphItem::Rest(note.duration.get_rest_type());
+        let (top, bottom) = rtype.get_extents();
+        let rect: Rectangle = (0., (level + top) as f32 * SPACE_HALF, widths.get_rest_width(&rtype), (bottom - top) as f32 * SPACE_HALF);
+        let item: GlyphItem = GlyphItem::Rest(rtype);
         rects.push((rect, item));
 
         Ok(rects)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn build_head(
         cx: &CoreContext,
+        widths: &GlyphWidths,
         note: &NoteItem,
         head: &HeadItem,
-        _heads: &[HeadItem],
+        heads: &[HeadItem],
         _part_idx: usize,
         _position: usize,
         _cplx_config: ComplexConfiguration,
@@ -346,52 +572,18 @@ impl BuildScore {
 
         //--------------------------------------------
         // The head itself
-        let cx_map_head_position = cx.map_head_position.borrow();
-        let mut head_x: f32 = if !cx_map_head_position.contains_key(&head.id) {
-            0.
-        } else {
-            match cx_map_head_position.get(&head.id).cloned().unwrap_or(StemHeadPosition::Center) {
-                StemHeadPosition::Center => 0.,
-                StemHeadPosition::Left => -get_head_width(&note.duration),
-                StemHeadPosition::Right => get_head_width(&note.duration),
-            }
-        };
-
-        if cx.map_noteid_headoffsetx.borrow().contains_key(&note.id) {
-            head_x += cx.map_noteid_headoffsetx.borrow().get(&note.id).cloned().unwrap_or(0.0);
-        };
+        let head_x: f32 = get_head_x(cx, widths, note, head);
 
         let head_y: f32 = head.level as f32 * SPACE_HALF;
 
-        let head_width = get_head_width(&note.duration);
+        let head_width = widths.get_head_width(&note.duration);
         let rect: Rectangle = (head_x, -SPACE_HALF + head_y, head_width, SPACE);
-        let item: GlyphItem = GlyphItem::Notehead(note.duration.get_head_type(), HeadVariant::Normal);
+        let item: GlyphItem = GlyphItem::Notehead(note.duration.get_head_type(), HeadVariant::Normal).tagged(|tag| tag.head_id = Some(head.id));
         rects.push((rect, item));
 
         //-------------------------------------------
-        // helper lines
-
-        //---------------------------------------
-        // dotted durations
-        if note.duration.is_dotted() {
-            let mut color = Color::Lime;
-            let mut _dot_y = 0.0;
-
-            if let Some(direction) = cx.map_noteid_direction.borrow().get(&note.id).cloned() {
-                match direction {
-                    DirectionUD::Up => {
-                        color = Color::DodgerBlue;
-                    }
-                    DirectionUD::Down => {
-                        color = Color::Tomato;
-                    }
-                }
-            }
-
-            let rect: Rectangle = (head_x + head_width, -SPACE_HALF + head_y + _dot_y, SPACE, SPACE);
-            let item: GlyphItem = GlyphItem::XRect(color);
-            rects.push((rect, item));
-        }
+        // ledger lines
+        rects.extend(create_glyphsrectangles_ledger_lines(cx, widths, note, head, heads));
 
         //---------------------------------------
         // Ties
@@ -435,17 +627,24 @@ impl BuildScore {
         Ok(rects)
     }
 
-    fn build_stem_root(cx: &CoreContext, note: &NoteItem, _part_idx: usize, _position: usize, _cplx_config: ComplexConfiguration) -> Result<Vec<(Rectangle, GlyphItem)>, Box<dyn std::error::Error>> {
+    fn build_stem_root(
+        cx: &CoreContext,
+        widths: &GlyphWidths,
+        note: &NoteItem,
+        _part_idx: usize,
+        _position: usize,
+        _cplx_config: ComplexConfiguration,
+    ) -> Result<Vec<(Rectangle, GlyphItem)>, Box<dyn std::error::Error>> {
         let stemitems = cx.stemitems.borrow();
         let noteid_stemitemid = cx.map_noteid_stemitemid.borrow();
         let stemitemlevels = cx.map_noteid_stemitemlevels.borrow();
 
         let mut rects: Vec<(Rectangle, GlyphItem)> = Vec::new();
 
-        let head_width = get_head_width(&note.duration);
+        let head_width = widths.get_head_width(&note.duration);
         let head_offset_x = cx.map_noteid_headoffsetx.borrow().get(&note.id).cloned().unwrap_or(0.0);
 
-        let stem_width = STEM_WIDTH;
+        let stem_width = widths.stem;
 
         //----------------------------------------------
         // Build stem rectangle
@@ -459,15 +658,17 @@ impl BuildScore {
 
             // dbg!(&stem_x, &stem_length, &stemitem_levels);
 
+            let stemitem_id = noteid_stemitemid.get(&note.id).cloned();
+            let tag_stem = |tag: &mut GlyphTag| tag.stemitem_id = stemitem_id;
             match *direction {
                 DirectionUD::Up => {
                     let rect: Rectangle = (stem_x, stem_y, stem_width, stem_length);
-                    let item: GlyphItem = GlyphItem::XRect(Color::Black);
+                    let item: GlyphItem = GlyphItem::Stem.tagged(tag_stem);
                     rects.push((rect, item));
                 }
                 DirectionUD::Down => {
                     let rect: Rectangle = (stem_x, stem_y, stem_width, stem_length);
-                    let item: GlyphItem = GlyphItem::XRect(Color::Black);
+                    let item: GlyphItem = GlyphItem::Stem.tagged(tag_stem);
                     rects.push((rect, item));
                 }
             }
@@ -478,23 +679,22 @@ impl BuildScore {
                 let stemitem_id = noteid_stemitemid.get(&note.id).expect("Stem item ID not found for note");
                 if let Some(stemitem) = stemitems.get(*stemitem_id) {
                     // dbg!(&stemitem.stype, note.id);
-                    match &stemitem.stype {
-                        StemType::NoteWithStem(_note) => match direction {
+                    if let StemType::NoteWithStem(_note) = &stemitem.stype {
+                        match direction {
                             DirectionUD::Up => {
-                                let rect: Rectangle = (stem_x, stem_y, FLAG_WIDTH, FLAG_HEIGHT);
-                                let item: GlyphItem = GlyphItem::FlagEightDown;
+                                let rect: Rectangle = (stem_x, stem_y, widths.flag, FLAG_HEIGHT);
+                                let item: GlyphItem = GlyphItem::FlagEightDown.tagged(tag_stem);
                                 // let item = GlyphItem::XRect(Color::Orange);
                                 rects.push((rect, item));
                             }
                             DirectionUD::Down => {
-                                let rect: Rectangle = (stem_x, stem_y + stem_length - FLAG_HEIGHT, FLAG_WIDTH, FLAG_HEIGHT);
-                                let item: GlyphItem = GlyphItem::FlagEightUp;
+                                let rect: Rectangle = (stem_x, stem_y + stem_length - FLAG_HEIGHT, widths.flag, FLAG_HEIGHT);
+                                let item: GlyphItem = GlyphItem::FlagEightUp.tagged(tag_stem);
                                 // let item = GlyphItem::XRect(Color::Lime);
 
                                 rects.push((rect, item));
                             }
-                        },
-                        _ => {}
+                        }
                     }
                 }
             }
@@ -506,7 +706,7 @@ impl BuildScore {
     fn build_space(_cx: &CoreContext, _note: &NoteItem, _part_idx: usize, _position: usize) -> Result<Vec<(Rectangle, GlyphItem)>, Box<dyn std::error::Error>> {
         let mut rects: Vec<(Rectangle, GlyphItem)> = Vec::new();
         let rect: Rectangle = (0., -SPACE_HALF, SPACE, SPACE);
-        let item: GlyphItem = GlyphItem::XRect(Color::RGBA(0, 0, 0, 0.1));
+        let item: GlyphItem = GlyphItem::Space;
         rects.push((rect, item));
 
         Ok(rects)
@@ -518,107 +718,278 @@ impl BuildScore {
 //---------------------------------------------------------------------------
 //---------------------------------------------------------------------------
 
-fn get_upper_bottom_level(upper: &NoteItem) -> f32 {
-    let level = match upper.ntype {
-        NoteType::Heads(ref heads) => {
-            let head_level = heads.last().unwrap().level + 5;
-            let level_mod = head_level % 2;
-            let head_level2 = head_level + level_mod;
-            head_level2 as f32
-        }
-        NoteType::Rest => 0.0,
-        _ => 2.0,
-    };
-    level.max(4.0)
+/// Rest level clear of the other voice, in whole spaces so that whole and half rests stay on their lines
+fn get_rest_level(rtype: &RestType, other: Option<&NoteItem>, direction: DirectionUD) -> i8 {
+    let (top, bottom) = rtype.get_extents();
+    match (&direction, other.map(|note| &note.ntype)) {
+        // a head takes a level on each side, and a space is kept to it
+        (DirectionUD::Up, Some(NoteType::Heads(heads))) => snap_rest_level(0.min(heads.first().unwrap().level - 2 - bottom), &direction),
+        (DirectionUD::Down, Some(NoteType::Heads(heads))) => snap_rest_level(0.max(heads.last().unwrap().level + 2 - top), &direction),
+        // two rests share the staff, each keeps to its side of the middle line
+        (DirectionUD::Up, Some(NoteType::Rest)) => snap_rest_level(0.min(-1 - bottom), &direction),
+        (DirectionUD::Down, Some(NoteType::Rest)) => snap_rest_level(0.max(1 - top), &direction),
+        _ => 0,
+    }
 }
 
-fn get_lower_top_level(lower: &NoteItem) -> f32 {
-    let level = match lower.ntype {
-        NoteType::Heads(ref heads) => {
-            let head_level = heads.first().unwrap().level - 6;
-            let level_mod = head_level % 2;
-            let head_level2 = head_level - level_mod;
-            head_level2 as f32
-        }
-        NoteType::Rest => -0.0,
-        _ => -2.0,
-    };
-    level.min(-4.0)
+fn snap_rest_level(level: i8, direction: &DirectionUD) -> i8 {
+    match direction {
+        DirectionUD::Up => level - level.rem_euclid(2),
+        DirectionUD::Down => level + level.rem_euclid(2),
+    }
 }
 
 fn leftmost_x(rs: &[(Rectangle, GlyphItem)]) -> f32 {
     rs.iter().map(|((x, _, _, _), _)| *x).min_by(|a, b| a.partial_cmp(b).unwrap()).unwrap_or(0.0)
 }
 
-fn get_head_width(duration: &NoteDuration) -> f32 {
-    match duration.get_head_type() {
-        HeadType::White => HEAD_WIDTH_WHITE, // Example adjustment for white heads
-        HeadType::Whole => HEAD_WIDTH_WHOLE, // No head
-        _ => HEAD_WIDTH_BLACK,
+/// Head x including its position beside the stem and the offset of the note against the other voice
+fn get_head_x(cx: &CoreContext, widths: &GlyphWidths, note: &NoteItem, head: &HeadItem) -> f32 {
+    let head_x = match cx.map_head_position.borrow().get(&head.id).cloned().unwrap_or(StemHeadPosition::Center) {
+        StemHeadPosition::Center => 0.,
+        StemHeadPosition::Left => -widths.get_head_width(&note.duration),
+        StemHeadPosition::Right => widths.get_head_width(&note.duration),
+    };
+    head_x + cx.map_noteid_headoffsetx.borrow().get(&note.id).cloned().unwrap_or(0.0)
+}
+
+/// Ledger lines between the staff and the outermost head above or below it. Only the outermost head creates them,
+/// each line spanning all heads on or beyond it, so chords and displaced seconds share their ledgers.
+fn create_glyphsrectangles_ledger_lines(cx: &CoreContext, widths: &GlyphWidths, note: &NoteItem, head: &HeadItem, heads: &[HeadItem]) -> Vec<(Rectangle, GlyphItem)> {
+    let is_top = heads.iter().min_by_key(|h| h.level).is_some_and(|h| h.id == head.id);
+    let is_bottom = heads.iter().max_by_key(|h| h.level).is_some_and(|h| h.id == head.id);
+    let levels: Vec<i8> = if head.level <= -6 && is_top {
+        (head.level..=-6).filter(|level| level % 2 == 0).collect()
+    } else if head.level >= 6 && is_bottom {
+        (6..=head.level).filter(|level| level % 2 == 0).collect()
+    } else {
+        return Vec::new();
+    };
+
+    let head_width = widths.get_head_width(&note.duration);
+    let overhang = get_ledger_line_overhang(&note.duration.get_head_type());
+
+    levels
+        .iter()
+        .map(|level| {
+            let (left_x, right_x) = heads
+                .iter()
+                .filter(|h| if *level < 0 { h.level <= *level } else { h.level >= *level })
+                .map(|h| get_head_x(cx, widths, note, h))
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(left_x, right_x), x| (left_x.min(x), right_x.max(x + head_width)));
+            let rect: Rectangle = (
+                left_x - overhang,
+                *level as f32 * SPACE_HALF - widths.ledger_line.half(),
+                right_x - left_x + overhang * 2.0,
+                widths.ledger_line,
+            );
+            (rect, GlyphItem::LedgerLine)
+        })
+        .collect()
+}
+
+fn get_ledger_line_overhang(head_type: &HeadType) -> f32 {
+    match head_type {
+        HeadType::Whole | HeadType::Brevis => LEDGER_LINE_OVERHANG_WHOLE,
+        _ => LEDGER_LINE_OVERHANG,
     }
 }
 
 pub fn sort_accidentals(accidentals: &mut Vec<(i8, Accidental)>) -> &mut Vec<(i8, Accidental)> {
-    accidentals.sort_by(|a, b| a.0.cmp(&b.0));
+    accidentals.sort_by_key(|a| a.0);
     accidentals
 }
 
-pub fn collect_accidentals(_note: &NoteItem) -> Vec<(i8, Accidental)> {
+/// Accidentals to print, as decided by the accidental state of the bar. Falls back to the written ones.
+pub fn collect_accidentals(cx: &CoreContext, _note: &NoteItem) -> Vec<(i8, Accidental)> {
+    let map_headid_accidental = cx.map_headid_accidental.borrow();
     let mut accidentals: Vec<(i8, Accidental)> = Vec::new();
-    match _note.ntype {
-        NoteType::Heads(ref heads) => {
-            for head in heads {
-                match head.accidental {
-                    Accidental::None => {}
-                    _ => {
-                        accidentals.push((head.level, head.accidental.clone()));
-                    }
+    if let NoteType::Heads(ref heads) = _note.ntype {
+        for head in heads {
+            match map_headid_accidental.get(&head.id).unwrap_or(&head.accidental) {
+                Accidental::None => {}
+                accidental => {
+                    accidentals.push((head.level, accidental.clone()));
                 }
             }
         }
-        _ => {}
     }
     accidentals
 }
 
-#[allow(unused_assignments)]
-pub fn create_glyphsrectangles_accidentals(accs: &[(i8, Accidental)], rectangles: &mut Vec<(Rectangle, GlyphItem)>) -> f32 {
-    let mut altidx = 0; // Alternate index for even/odd handling
-    let mut leftmost_x: f32 = 0.0; // Leftmost x position for the accidentals
-    for accidx in 0..accs.len() {
-        if (&accidx % 2) == 0 {
-            altidx = accidx.div_ceil(2);
-            // println!("Even index: {} {}", accidx, altidx);
-        } else {
-            altidx = &accs.len() - accidx.div_ceil(2);
-            // println!("Odd index: {} {}", accidx, altidx);
+/// Places accidentals in columns to the left of the heads, stems and everything else already in `rectangles`,
+/// including heads of the other voice and displaced heads. Returns the leftmost x.
+pub fn create_glyphsrectangles_accidentals(widths: &GlyphWidths, accs: &[(i8, Accidental)], rectangles: &mut Vec<(Rectangle, GlyphItem)>) -> f32 {
+    let obstacles: Vec<Rectangle> = rectangles.iter().map(|(rect, _)| *rect).collect();
+    let mut placed: Vec<Rectangle> = Vec::new();
+    let mut leftmost_x: f32 = 0.0;
+
+    for group in get_accidentals_columns(accs) {
+        let group_rects: Vec<(Rectangle, GlyphItem)> = group
+            .iter()
+            .map(|idx| {
+                let (level, accidental) = &accs[*idx];
+                let width = widths.get_accidental_width(accidental);
+                let level_y: f32 = *level as f32 * SPACE_HALF;
+                let rect: Rectangle = (0.0, (-ACCIDENTAL_HEIGHT.half() + level_y).r2(), width, ACCIDENTAL_HEIGHT);
+                (rect, GlyphItem::Accidental(accidental.clone()))
+            })
+            .collect();
+
+        // right edge of the column: left of anything at the same height...
+        let mut right_x = group_rects
+            .iter()
+            .flat_map(|(rect, _)| obstacles.iter().filter(|obstacle| rectangles_overlap_vertically(rect, obstacle)).map(|obstacle| obstacle.0))
+            .fold(0.0, f32::min);
+
+        // ...and left of accidentals in the columns already placed
+        while let Some(x) = group_rects
+            .iter()
+            .flat_map(|(rect, _)| {
+                placed
+                    .iter()
+                    .filter(move |other| rectangles_overlap_vertically(rect, other) && other.0 < right_x - ACCIDENTAL_GAP && other.0 + other.2 > right_x - ACCIDENTAL_GAP - rect.2)
+                    .map(|other| other.0)
+            })
+            .reduce(f32::min)
+        {
+            right_x = x;
         }
 
-        let (level, accidental) = &accs[altidx];
+        for (mut rect, item) in group_rects {
+            rect.0 = right_x - ACCIDENTAL_GAP - rect.2;
+            leftmost_x = leftmost_x.min(rect.0);
+            placed.push(rect);
+            rectangles.push((rect, item));
+        }
+    }
 
-        let item = match accidental {
-            Accidental::Sharp => GlyphItem::Accidental(accidental.clone()),
-            Accidental::Flat => GlyphItem::Accidental(accidental.clone()),
-            Accidental::Natural => GlyphItem::Accidental(accidental.clone()),
-            _ => continue, // Skip if no accidental
-        };
-        let width = match accidental {
-            Accidental::Sharp => ACCIDENTAL_WIDTH_WIDE, // Natural is wider
-            _ => ACCIDENTAL_WIDTH_NARROW,
+    leftmost_x
+}
+
+/// Groups of accidental indices sharing a column, in the order the columns are placed from right to left.
+/// Chords spanning more than a sixth are stacked zig-zag (top, bottom, second top, second bottom...), narrower ones top to bottom.
+/// Accidentals an octave apart share a column.
+fn get_accidentals_columns(accs: &[(i8, Accidental)]) -> Vec<Vec<usize>> {
+    let mut sorted: Vec<usize> = (0..accs.len()).collect();
+    sorted.sort_by_key(|idx| accs[*idx].0);
+    sorted.dedup_by(|a, b| accs[*a] == accs[*b]);
+
+    let span = match (sorted.first(), sorted.last()) {
+        (Some(top), Some(bottom)) => accs[*bottom].0 - accs[*top].0,
+        _ => 0,
+    };
+    let order: Vec<usize> = if span > 5 {
+        (0..sorted.len()).map(|idx| if idx % 2 == 0 { sorted[idx / 2] } else { sorted[sorted.len() - 1 - idx / 2] }).collect()
+    } else {
+        sorted
+    };
+
+    let mut used = vec![false; accs.len()];
+    let mut columns: Vec<Vec<usize>> = Vec::new();
+    for idx in order.iter() {
+        if used[*idx] {
+            continue;
+        }
+        used[*idx] = true;
+        let mut column = vec![*idx];
+        if let Some(octave_idx) = order.iter().find(|other| !used[**other] && (accs[**other].0 - accs[*idx].0).abs() == 7) {
+            used[*octave_idx] = true;
+            column.push(*octave_idx);
+        }
+        columns.push(column);
+    }
+    columns
+}
+
+/// Augmentation dots of the dotted notes in a complex, aligned in one column right of all heads.
+/// Dots of heads on a line move to the space above, or below for the lower voice, and keep clear of dots already placed.
+fn create_glyphsrectangles_dots(notes: &[(&NoteItem, DirectionUD)], rectangles: &mut Vec<(Rectangle, GlyphItem)>) {
+    let dot_x = rectangles
+        .iter()
+        .filter(|(_, item)| matches!(item.untagged(), GlyphItem::Notehead(_, _)))
+        .map(|(rect, _)| rect.0 + rect.2)
+        .fold(0.0, f32::max)
+        + DOT_GAP;
+
+    let mut dot_levels: Vec<i8> = Vec::new();
+    for (note, direction) in notes.iter() {
+        let NoteType::Heads(heads) = &note.ntype else { continue };
+        if !note.duration.is_dotted() {
+            continue;
+        }
+        let towards: i8 = match direction {
+            DirectionUD::Up => -1,
+            DirectionUD::Down => 1,
         };
 
-        let level_y: f32 = *level as f32 * SPACE_HALF;
-        let mut rect: Rectangle = (0.0, (-ACCIDENTAL_HEIGHT.half() + level_y).r2(), width, ACCIDENTAL_HEIGHT);
+        // heads in spaces first, so that they keep their own space
+        let mut levels: Vec<i8> = heads.iter().map(|head| head.level).collect();
+        levels.sort_by_key(|level| (level % 2 == 0, -(level * towards)));
+        levels.dedup();
+
+        for level in levels {
+            let candidates = if level % 2 == 0 { [level + towards, level - towards] } else { [level, level + towards * 2] };
+            if let Some(dot_level) = candidates.iter().find(|candidate| !dot_levels.contains(candidate)) {
+                dot_levels.push(*dot_level);
+                let rect: Rectangle = (dot_x, *dot_level as f32 * SPACE_HALF - DOT_SIZE.half(), DOT_SIZE, DOT_SIZE);
+                rectangles.push((rect, GlyphItem::Dot.tagged(|tag| tag.note_id = Some(note.id))));
+            }
+        }
+    }
+}
 
-        // let overlap = rectangles.iter().any(|(r, _)| r.overlaps(&rect));
-        let overlap = rectangles_overlap_left(rectangles, &rect);
-        rect.0 = -overlap;
+/// Tag of a note's glyphs in a grid cell
+fn get_cell_note_tag(gcx: &GridContext<GlyphItem>, row_idx: usize, grid_col_idx: usize, note_id: NoteId) -> Option<GlyphTag> {
+    let item_id = *gcx.rows.borrow().get(row_idx)?.item_ids.get(grid_col_idx)?;
+    let GridItemType::Rectangles(ref rects) = gcx.items.borrow()[item_id].gitype else {
+        return None;
+    };
+    rects
+        .iter()
+        .filter_map(|(_, glyph)| glyph.get_tag())
+        .find(|tag| tag.note_id == Some(note_id))
+        .map(|tag| GlyphTag { head_id: None, ..tag.clone() })
+}
 
-        rectangles.push((rect, item));
-        leftmost_x = leftmost_x.min(rect.0);
+/// Adds a glyph to a grid cell after the layout, an empty cell gets its first one
+fn push_to_cell(gcx: &GridContext<GlyphItem>, row_idx: usize, grid_col_idx: usize, rect: GlyphRectangle) {
+    let Some(item_id) = gcx.rows.borrow().get(row_idx).and_then(|row| row.item_ids.get(grid_col_idx).cloned()) else {
+        return;
+    };
+    let gitype = &mut gcx.items.borrow_mut()[item_id].gitype;
+    match gitype {
+        GridItemType::Rectangles(rects) => rects.push(rect),
+        GridItemType::Empty => *gitype = GridItemType::Rectangles(vec![rect]),
     }
+}
 
-    leftmost_x
+fn tag_part_bar(tag: &mut GlyphTag, part_idx: usize, bar_idx: usize) {
+    tag.part_idx = Some(part_idx);
+    tag.bar_idx = Some(bar_idx);
+}
+
+/// Tags accidentals with the head at their level and the colour of the first note that has one
+fn tag_accidentals(cx: &CoreContext, rectangles: &mut [(Rectangle, GlyphItem)], notes: &[&NoteItem]) {
+    for (rect, glyph) in rectangles.iter_mut() {
+        let level = ((rect.1 + rect.3.half()) / SPACE_HALF).round() as i8;
+        let found = notes.iter().find_map(|note| match &note.ntype {
+            NoteType::Heads(heads) => heads.iter().find(|head| head.level == level).map(|head| (note.id, head.id)),
+            _ => None,
+        });
+        if let Some((note_id, head_id)) = found {
+            let color = cx.map_noteid_color.borrow().get(&note_id).cloned();
+            *glyph = glyph.clone().tagged(|tag| {
+                tag.note_id = Some(note_id);
+                tag.head_id = Some(head_id);
+                tag.color = color;
+            });
+        }
+    }
+}
+
+fn rectangles_overlap_vertically(a: &Rectangle, b: &Rectangle) -> bool {
+    a.1 < b.1 + b.3 && b.1 < a.1 + a.3
 }
 
 pub fn rectangles_overlap_left(lefts: &[(Rectangle, GlyphItem)], right: &Rectangle) -> f32 {
@@ -631,8 +1002,156 @@ pub fn rectangles_overlap_left(lefts: &[(Rectangle, GlyphItem)], right: &Rectang
 }
 
 fn create_space_rectangle_for_first_note_in_bar(left_x: f32) -> ((f32, f32, f32, f32), GlyphItem) {
-    (
-        (left_x - SPACE_BEFORE_FIRST_NOTE_IN_BAR, -SPACE, SPACE_BEFORE_FIRST_NOTE_IN_BAR, SPACE2),
-        GlyphItem::XRect(Color::RGBA(0, 0, 0, 0.1)),
-    )
+    ((left_x - SPACE_BEFORE_FIRST_NOTE_IN_BAR, -SPACE, SPACE_BEFORE_FIRST_NOTE_IN_BAR, SPACE2), GlyphItem::Space)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use core::duration::NoteDuration;
+    use parse::parse2::Parse2;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_accidentals_columns() {
+        // a sixth or less: top to bottom
+        let accs = vec![(-2, Accidental::Sharp), (0, Accidental::Flat), (2, Accidental::Sharp)];
+        assert_eq!(get_accidentals_columns(&accs), vec![vec![0], vec![1], vec![2]]);
+
+        // wider chords zig-zag, octaves share a column
+        let accs = vec![(-4, Accidental::Sharp), (-2, Accidental::Sharp), (0, Accidental::Sharp), (3, Accidental::Sharp)];
+        assert_eq!(get_accidentals_columns(&accs), vec![vec![0, 3], vec![1], vec![2]]);
+
+        let mut rects: Vec<(Rectangle, GlyphItem)> = Vec::new();
+        let leftmost = create_glyphsrectangles_accidentals(&GlyphWidths::default(), &accs, &mut rects);
+        assert_eq!(rects.len(), 4);
+        assert_eq!(rects[0].0.0, rects[1].0.0);
+        assert!(rects[2].0.0 < rects[0].0.0);
+        assert_eq!(leftmost, rects.iter().map(|(rect, _)| rect.0).fold(0.0, f32::min));
+
+        // double accidentals stack like the others, with their own widths
+        let widths = GlyphWidths::default();
+        let accs = vec![(-2, Accidental::DoubleSharp), (0, Accidental::DoubleFlat), (2, Accidental::DoubleSharp)];
+        let mut rects: Vec<(Rectangle, GlyphItem)> = Vec::new();
+        create_glyphsrectangles_accidentals(&widths, &accs, &mut rects);
+        assert_eq!(rects.len(), 3);
+        assert!(matches!(rects[0].1, GlyphItem::Accidental(Accidental::DoubleSharp)));
+        assert_eq!(rects[1].0.2, widths.accidental_double_flat);
+        assert!(rects[1].0.0 < rects[0].0.0 && rects[2].0.0 < rects[1].0.0);
+    }
+    #[test]
+    fn test_ledger_lines() {
+        let cx = CoreContext::new();
+        let head = |id: usize, level: i8| HeadItem {
+            id,
+            level,
+            accidental: Accidental::None,
+        };
+        let heads = vec![head(0, -9), head(1, -6), head(2, 7)];
+        let note = NoteItem {
+            id: 0,
+            position: 0,
+            duration: NoteDuration::D4,
+            ntype: NoteType::Heads(heads.clone()),
+        };
+
+        let top = create_glyphsrectangles_ledger_lines(cx, &GlyphWidths::default(), &note, &heads[0], &heads);
+        assert_eq!(top.iter().map(|(rect, _)| (rect.1 + LEDGER_LINE_THICKNESS.half()).round() as i32).collect::<Vec<_>>(), vec![-40, -30]);
+        assert!(create_glyphsrectangles_ledger_lines(cx, &GlyphWidths::default(), &note, &heads[1], &heads).is_empty());
+        assert_eq!(create_glyphsrectangles_ledger_lines(cx, &GlyphWidths::default(), &note, &heads[2], &heads).len(), 1);
+    }
+    #[test]
+    fn test_dots() {
+        let note = |id: usize, levels: &[i8]| NoteItem {
+            id,
+            position: 0,
+            duration: NoteDuration::D4Dot,
+            ntype: NoteType::Heads(
+                levels
+                    .iter()
+                    .map(|level| HeadItem {
+                        id: 0,
+                        level: *level,
+                        accidental: Accidental::None,
+                    })
+                    .collect(),
+            ),
+        };
+        let dot_levels = |rects: &[(Rectangle, GlyphItem)]| {
+            rects
+                .iter()
+                .filter(|(_, item)| matches!(item.untagged(), GlyphItem::Dot))
+                .map(|(rect, _)| ((rect.1 + DOT_SIZE.half()) / SPACE_HALF).round() as i8)
+                .collect::<Vec<_>>()
+        };
+
+        // second in a chord: the space keeps its dot, the line dot moves below
+        let mut rects: Vec<(Rectangle, GlyphItem)> = Vec::new();
+        create_glyphsrectangles_dots(&[(&note(0, &[0, -1]), DirectionUD::Up)], &mut rects);
+        assert_eq!(dot_levels(&rects), vec![-1, 1]);
+
+        // unison in two voices: the upper dot goes above, the lower one below
+        let mut rects: Vec<(Rectangle, GlyphItem)> = Vec::new();
+        create_glyphsrectangles_dots(&[(&note(0, &[-2]), DirectionUD::Up), (&note(1, &[-2]), DirectionUD::Down)], &mut rects);
+        assert_eq!(dot_levels(&rects), vec![-3, -1]);
+
+        // lower voice under a held upper note: the line dot moves below
+        let cx = CoreContext::new();
+        Parse2::sysitemlist2(cx, "d2 -4 % d8 3 d4. 2", false).unwrap();
+        let complexes = cx.complexes.borrow();
+        let lower = complexes.iter().find(|complex| matches!(complex.ctype, ComplexType::Lower(_))).unwrap();
+        let rects = BuildScore::build_complex(cx, &GlyphWidths::default(), lower, 0, lower.position).unwrap();
+        assert_eq!(dot_levels(&rects), vec![3]);
+    }
+
+    #[test]
+    fn test_rest_levels() {
+        let note = |ntype: NoteType| NoteItem {
+            id: 0,
+            position: 0,
+            duration: NoteDuration::D4,
+            ntype,
+        };
+        let heads = |level: i8| {
+            note(NoteType::Heads(vec![HeadItem {
+                id: 0,
+                level,
+                accidental: Accidental::None,
+            }]))
+        };
+
+        // clear of the other voice, in whole spaces
+        assert_eq!(get_rest_level(&RestType::Quarter, Some(&heads(3)), DirectionUD::Up), -2);
+        assert_eq!(get_rest_level(&RestType::Quarter, Some(&heads(-3)), DirectionUD::Down), 2);
+        assert_eq!(get_rest_level(&RestType::Quarter, Some(&heads(8)), DirectionUD::Up), 0);
+        assert_eq!(get_rest_level(&RestType::Quarter, Some(&note(NoteType::Rest)), DirectionUD::Up), -4);
+        assert_eq!(get_rest_level(&RestType::Whole, Some(&note(NoteType::Rest)), DirectionUD::Down), 4);
+        assert_eq!(get_rest_level(&RestType::Half, Some(&note(NoteType::Rest)), DirectionUD::Up), -2);
+        assert_eq!(get_rest_level(&RestType::Quarter, Some(&note(NoteType::Space)), DirectionUD::Up), 0);
+
+        // an explicit level wins over the other voice
+        let cx = CoreContext::new();
+        Parse2::sysitemlist2(cx, "r-6 % r", false).unwrap();
+        let notes = cx.notes.borrow();
+        let rects = BuildScore::build_rest(cx, &GlyphWidths::default(), &notes[0], 0, 0, Some(&notes[1]), ComplexConfiguration::TwoNotes(DirectionUD::Up)).unwrap();
+        assert_eq!(rects[0].0.1, -9.0 * SPACE_HALF);
+        let rects = BuildScore::build_rest(cx, &GlyphWidths::default(), &notes[1], 0, 0, Some(&notes[0]), ComplexConfiguration::TwoNotes(DirectionUD::Down)).unwrap();
+        assert_eq!(rects[0].0.1, SPACE_HALF);
+    }
+
+    #[test]
+    fn test_multirest() {
+        let cx = CoreContext::new();
+        Parse2::sysitemlist2(cx, "0 | bl | bp | bl | bp | bl | bp | bl|. | bp", false).unwrap();
+        let scx = ScoreContext::new();
+        *scx.multimeasure_rests.borrow_mut() = true;
+        BuildScore::build(scx, cx).unwrap();
+
+        // three bars condensed, the final barline stops the run
+        assert_eq!(*scx.map_colidx_multirest.borrow(), BTreeMap::from([(2, 3)]));
+        let map_colidx_gridcolidx = scx.map_colidx_gridcolidx.borrow();
+        assert_eq!(map_colidx_gridcolidx[&3], map_colidx_gridcolidx[&7]);
+        assert_eq!(cx.columns.borrow()[8].position, cx.columns.borrow()[2].position + 3 * NoteDuration::D1 as usize);
+    }
 }
@@ -6,13 +6,11 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use utils::f32_ext::round::F32ExtRound2;
 
+pub type PositionsAllotments = (Vec<usize>, Vec<usize>, Vec<f32>, BTreeMap<(usize, usize), usize>);
+
 pub struct BuildUtils;
 impl BuildUtils {
-    pub fn get_complexes_positions_allotments(
-        cx: &core::context::CoreContext,
-        hparts: &[&HPartItem],
-        duration: usize,
-    ) -> Result<(Vec<usize>, Vec<usize>, Vec<f32>, BTreeMap<(usize, usize), usize>), Box<dyn std::error::Error>> {
+    pub fn get_complexes_positions_allotments(cx: &core::context::CoreContext, hparts: &[&HPartItem], duration: usize) -> Result<PositionsAllotments, Box<dyn std::error::Error>> {
         let cx_complexes = cx.complexes.borrow();
 
         // Collect positions and map them to hpart indices
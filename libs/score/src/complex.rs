@@ -1,7 +1,7 @@
 use core::{
     accidental::Accidental,
     duration::NoteDuration,
-    head::{HeadItem, HeadType, HeadVariant},
+    head::{HeadItem, HeadVariant},
     note::{NoteItem, NoteType},
     stems::stemitems::StemHeadPosition,
 };
@@ -12,33 +12,31 @@ use utils::f32_ext::{half::F32ExtHalf, round::F32ExtRound2};
 use crate::{
     constants::*,
     glyphitem::{GlyphItem, GlyphRectangle},
+    scorecontext::GlyphWidths,
 };
 
 pub fn sort_accidentals(accidentals: &mut Vec<(i8, Accidental)>) -> &mut Vec<(i8, Accidental)> {
-    accidentals.sort_by(|a, b| a.0.cmp(&b.0));
+    accidentals.sort_by_key(|a| a.0);
     accidentals
 }
 
 pub fn collect_accidentals(_note: &NoteItem) -> Vec<(i8, Accidental)> {
     let mut accidentals: Vec<(i8, Accidental)> = Vec::new();
-    match _note.ntype {
-        NoteType::Heads(ref heads) => {
-            for head in heads {
-                match head.accidental {
-                    Accidental::None => {}
-                    _ => {
-                        accidentals.push((head.level, head.accidental.clone()));
-                    }
+    if let NoteType::Heads(ref heads) = _note.ntype {
+        for head in heads {
+            match head.accidental {
+                Accidental::None => {}
+                _ => {
+                    accidentals.push((head.level, head.accidental.clone()));
                 }
             }
         }
-        _ => {}
     }
     accidentals
 }
 
 #[allow(unused_assignments)]
-pub fn create_glyphsrectangles_accidentals(accs: &[(i8, Accidental)], rectangles: &mut Vec<(Rectangle, GlyphItem)>) -> f32 {
+pub fn create_glyphsrectangles_accidentals(widths: &GlyphWidths, accs: &[(i8, Accidental)], rectangles: &mut Vec<(Rectangle, GlyphItem)>) -> f32 {
     // let mut rectangles: ComplexGlyphsRectangles = Vec::new();
 
     let mut altidx = 0; // Alternate index for even/odd handling
@@ -50,22 +48,17 @@ pub fn create_glyphsrectangles_accidentals(accs: &[(i8, Accidental)], rectangles
             altidx = accidx.div_ceil(2);
             // println!("Even index: {} {}", accidx, altidx);
         } else {
-            altidx = &accs.len() - accidx.div_ceil(2);
+            altidx = accs.len() - accidx.div_ceil(2);
             // println!("Odd index: {} {}", accidx, altidx);
         }
 
         let (level, accidental) = &accs[altidx];
 
         let item = match accidental {
-            Accidental::Sharp => GlyphItem::Accidental(accidental.clone()),
-            Accidental::Flat => GlyphItem::Accidental(accidental.clone()),
-            Accidental::Natural => GlyphItem::Accidental(accidental.clone()),
-            _ => continue, // Skip if no accidental
-        };
-        let width = match accidental {
-            Accidental::Sharp => ACCIDENTAL_WIDTH_WIDE, // Natural is wider
-            _ => ACCIDENTAL_WIDTH_NARROW,
+            Accidental::None => continue, // Skip if no accidental
+            _ => GlyphItem::Accidental(accidental.clone()),
         };
+        let width = widths.get_accidental_width(accidental);
 
         let level_y: f32 = *level as f32 * SPACE_HALF;
         let mut rect: Rectangle = (0.0, (-ACCIDENTAL_HEIGHT.half() + level_y).r2(), width, ACCIDENTAL_HEIGHT);
@@ -90,19 +83,19 @@ pub fn rectangles_overlap_left(lefts: &[(Rectangle, GlyphItem)], right: &Rectang
     result
 }
 
-pub fn create_glyphsrectangles_note(_note: &NoteItem, map_head_position: &BTreeMap<usize, StemHeadPosition>, rectangles: &mut Vec<(Rectangle, GlyphItem)>) -> f32 {
+pub fn create_glyphsrectangles_note(widths: &GlyphWidths, _note: &NoteItem, map_head_position: &BTreeMap<usize, StemHeadPosition>, rectangles: &mut Vec<(Rectangle, GlyphItem)>) -> f32 {
     // let mut rectangles: ComplexGlyphsRectangles = Vec::new();
     let mut leftmost_x: f32 = 0.0; // Leftmost x position for the note glyphs
     match _note.ntype {
         NoteType::Heads(ref heads) => {
             for head in heads {
-                let rect = create_glyphrectangle_head(&_note.duration, head, map_head_position);
+                let rect = create_glyphrectangle_head(widths, &_note.duration, head, map_head_position);
                 leftmost_x = leftmost_x.min(rect.0 .0);
                 rectangles.push(rect);
             }
         }
         NoteType::Rest => {
-            rectangles.push(create_glyphrectangle_rest(&_note.duration));
+            rectangles.push(create_glyphrectangle_rest(widths, &_note.duration));
         }
         NoteType::Space => {
             todo!("Note is Space type");
@@ -114,33 +107,25 @@ pub fn create_glyphsrectangles_note(_note: &NoteItem, map_head_position: &BTreeM
     leftmost_x
 }
 
-fn create_glyphrectangle_head(duration: &NoteDuration, head: &HeadItem, map_head_position: &BTreeMap<usize, StemHeadPosition>) -> GlyphRectangle {
+fn create_glyphrectangle_head(widths: &GlyphWidths, duration: &NoteDuration, head: &HeadItem, map_head_position: &BTreeMap<usize, StemHeadPosition>) -> GlyphRectangle {
     let head_x: f32 = if !map_head_position.contains_key(&head.id) {
         0.
     } else {
         match map_head_position.get(&head.id).cloned().unwrap_or(StemHeadPosition::Center) {
             StemHeadPosition::Center => 0.,
-            StemHeadPosition::Left => -get_head_width(duration),
-            StemHeadPosition::Right => get_head_width(duration),
+            StemHeadPosition::Left => -widths.get_head_width(duration),
+            StemHeadPosition::Right => widths.get_head_width(duration),
         }
     };
 
     let level_y: f32 = head.level as f32 * SPACE_HALF;
-    let rect: Rectangle = (head_x, -SPACE_HALF + level_y, get_head_width(duration), SPACE);
+    let rect: Rectangle = (head_x, -SPACE_HALF + level_y, widths.get_head_width(duration), SPACE);
     // dbg!(&rect);
     let item: GlyphItem = GlyphItem::Notehead(duration.get_head_type(), HeadVariant::Normal);
     (rect, item)
 }
 
-fn get_head_width(duration: &NoteDuration) -> f32 {
-    match duration.get_head_type() {
-        HeadType::White => HEAD_WIDTH_WHITE, // Example adjustment for white heads
-        HeadType::Whole => HEAD_WIDTH_WHOLE, // No head
-        _ => HEAD_WIDTH_BLACK,
-    }
-}
-
-fn create_glyphrectangle_rest(duration: &NoteDuration) -> GlyphRectangle {
-    let rect: Rectangle = (0., -SPACE, REST_WIDTH, SPACE2);
+fn create_glyphrectangle_rest(widths: &GlyphWidths, duration: &NoteDuration) -> GlyphRectangle {
+    let rect: Rectangle = (0., -SPACE, widths.get_rest_width(&duration.get_rest_type()), SPACE2);
     (rect, GlyphItem::Rest(duration.get_rest_type()))
 }
@@ -1,5 +1,5 @@
 pub const SPACE: f32 = 10.0; // Default space size in pixels, used for layout and spacing in the score rendering
-                             //
+//
 pub const SPACE2: f32 = SPACE * 2.0;
 pub const SPACE3: f32 = SPACE * 3.0;
 pub const SPACE4: f32 = SPACE * 4.0;
@@ -9,18 +9,39 @@ pub const SPACE_QUARTER: f32 = SPACE / 4.0;
 
 pub const SPACE_BEFORE_FIRST_NOTE_IN_BAR: f32 = SPACE * 1.2;
 
-pub const HEAD_WIDTH_BLACK: f32 = SPACE * 1.3;
-pub const HEAD_WIDTH_WHITE: f32 = SPACE * 1.35;
-pub const HEAD_WIDTH_WHOLE: f32 = SPACE * 2.4;
-pub const REST_WIDTH: f32 = SPACE;
+pub const STAFF_LINE_THICKNESS: f32 = SPACE * 0.1;
+pub const LEDGER_LINE_THICKNESS: f32 = SPACE * 0.16;
+pub const LEDGER_LINE_OVERHANG: f32 = SPACE * 0.35;
+pub const LEDGER_LINE_OVERHANG_WHOLE: f32 = SPACE * 0.45;
+pub const DOT_SIZE: f32 = SPACE * 0.4;
+pub const DOT_GAP: f32 = SPACE * 0.35;
+pub const BAR_REST_WIDTH: f32 = SPACE * 6.0;
+pub const MULTIREST_WIDTH: f32 = SPACE * 10.0;
+pub const MULTIREST_THICK: f32 = SPACE;
 pub const ACCIDENTAL_HEIGHT: f32 = SPACE3;
-pub const ACCIDENTAL_WIDTH_WIDE: f32 = SPACE * 1.25;
-pub const ACCIDENTAL_WIDTH_NARROW: f32 = SPACE * 1.0;
-pub const CLEF_WIDTH: f32 = SPACE3;
+pub const ACCIDENTAL_GAP: f32 = SPACE * 0.15;
+pub const CLEF_CHANGE_SCALE: f32 = 0.75;
 pub const BARLINE_WIDTH: f32 = SPACE * 0.3;
 pub const BARLINE_DOUBLE_WIDTH: f32 = SPACE;
 pub const BARLINE_FINAL_WIDTH: f32 = SPACE * 1.5;
+pub const BARLINE_THIN: f32 = SPACE * 0.16;
+pub const BARLINE_THICK: f32 = SPACE * 0.5;
+pub const BARLINE_DASH: f32 = SPACE * 0.6;
+pub const BARLINE_GAP: f32 = SPACE * 0.3;
+pub const BARLINE_REPEAT_DOT: f32 = SPACE * 0.25;
+pub const VOLTA_HEIGHT: f32 = SPACE * 2.0;
+pub const VOLTA_DISTANCE: f32 = SPACE * 2.5;
+pub const NAVIGATION_DISTANCE: f32 = SPACE * 3.0;
+pub const BRACE_WIDTH: f32 = SPACE * 1.5;
+pub const BRACKET_THICK: f32 = SPACE * 0.5;
+pub const STAFF_GROUP_GAP: f32 = SPACE * 0.6;
+pub const STAFF_DISTANCE: f32 = SPACE * 8.0;
+pub const STAFF_DISTANCE_BRACE: f32 = SPACE * 7.0;
+pub const STAFF_PADDING: f32 = SPACE;
+pub const PART_NAME_SIZE: f32 = SPACE * 1.6;
+pub const PART_NAME_GAP: f32 = SPACE;
 
 pub const STEM_WIDTH: f32 = SPACE / 7.0;
-pub const FLAG_WIDTH: f32 = SPACE * 1.6;
 pub const FLAG_HEIGHT: f32 = SPACE4;
+pub const BEAM_THICKNESS: f32 = SPACE_HALF;
+pub const BEAM_GAP: f32 = SPACE_QUARTER;
@@ -2,8 +2,10 @@ use core::{
     accidental::Accidental,
     barline::BarlineType,
     clef::ClefSignature,
-    head::{HeadType, HeadVariant},
+    head::{HeadId, HeadType, HeadVariant},
+    note::{NoteColor, NoteId},
     rest::RestType,
+    stems::stemitems::StemItemId,
 };
 
 use graphics::{color::Color, rectangle::Rectangle};
@@ -11,9 +13,15 @@ use graphics::{color::Color, rectangle::Rectangle};
 #[derive(Debug, Clone)]
 pub enum GlyphItem {
     XRect(Color),
+    // space taken in the layout with nothing drawn
+    Space,
     //
     Barline(BarlineType),
     Notehead(HeadType, HeadVariant),
+    Stem,
+    Beam,
+    LedgerLine,
+    Dot,
     Rest(RestType),
     BarRest,
     //
@@ -29,8 +37,53 @@ pub enum GlyphItem {
 
     Accidental(Accidental),
     Clef(ClefSignature),
+    ClefChange(ClefSignature),
     // TieFromResolved(i8),
     // TieFromUnresolved(i8),
+    Tagged(GlyphTag, Box<GlyphItem>),
+}
+
+/// The musical objects a glyph is drawn for
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GlyphTag {
+    pub part_idx: Option<usize>,
+    pub bar_idx: Option<usize>,
+    pub note_id: Option<NoteId>,
+    pub head_id: Option<HeadId>,
+    pub stemitem_id: Option<StemItemId>,
+    /// Colour set for the note in the input, instead of the theme's foreground
+    pub color: Option<NoteColor>,
+}
+
+impl GlyphItem {
+    /// The glyph tagged by `update`, on top of the tag it already has
+    pub fn tagged(self, update: impl Fn(&mut GlyphTag)) -> GlyphItem {
+        match self {
+            GlyphItem::Tagged(mut tag, glyph) => {
+                update(&mut tag);
+                GlyphItem::Tagged(tag, glyph)
+            }
+            glyph => {
+                let mut tag = GlyphTag::default();
+                update(&mut tag);
+                GlyphItem::Tagged(tag, Box::new(glyph))
+            }
+        }
+    }
+
+    pub fn untagged(&self) -> &GlyphItem {
+        match self {
+            GlyphItem::Tagged(_, glyph) => glyph.untagged(),
+            glyph => glyph,
+        }
+    }
+
+    pub fn get_tag(&self) -> Option<&GlyphTag> {
+        match self {
+            GlyphItem::Tagged(tag, _) => Some(tag),
+            _ => None,
+        }
+    }
 }
 
 pub type GlyphRectangle = (Rectangle, GlyphItem);
@@ -1,16 +1,205 @@
-use core::stems::stemitems::StemHeadPosition;
+use core::{
+    accidental::Accidental,
+    duration::NoteDuration,
+    group::{StaffGroup, StaffGroupType},
+    head::HeadType,
+    rest::RestType,
+    stems::stemitems::StemHeadPosition,
+};
+use fonts::fontcontext::FontContext;
 use std::{cell::RefCell, collections::BTreeMap};
 
 use grid::griditem::GridItemType;
 
-use crate::glyphitem::GlyphItem;
+use crate::{
+    constants::{
+        BARLINE_GAP, BARLINE_THICK, BARLINE_THIN, BEAM_GAP, BEAM_THICKNESS, LEDGER_LINE_THICKNESS, SPACE, STAFF_DISTANCE, STAFF_DISTANCE_BRACE, STAFF_LINE_THICKNESS, STAFF_PADDING, STEM_WIDTH,
+    },
+    glyphitem::GlyphItem,
+};
+
+/// Smallest distances between the middle lines of neighbouring staves, by the group joining them
+#[derive(Debug, Clone)]
+pub struct StaffSpacing {
+    pub ungrouped: f32,
+    pub bracket: f32,
+    pub brace: f32,
+    /// Free space kept between the lowest item of a staff and the highest item of the staff below
+    pub padding: f32,
+}
+
+impl Default for StaffSpacing {
+    fn default() -> Self {
+        StaffSpacing {
+            ungrouped: STAFF_DISTANCE,
+            bracket: STAFF_DISTANCE,
+            brace: STAFF_DISTANCE_BRACE,
+            padding: STAFF_PADDING,
+        }
+    }
+}
+
+impl StaffSpacing {
+    /// Minimal distance from each staff to the next one, a brace wins over a bracket joining the same staves
+    pub fn get_min_distances(&self, groups: &[StaffGroup], staves_count: usize) -> Vec<f32> {
+        let joined_by = |gtype: StaffGroupType, part_idx: usize| groups.iter().any(|group| group.gtype == gtype && group.from_part_idx <= part_idx && part_idx < group.to_part_idx);
+        (0..staves_count.saturating_sub(1))
+            .map(|part_idx| {
+                if joined_by(StaffGroupType::Brace, part_idx) {
+                    self.brace
+                } else if joined_by(StaffGroupType::Bracket, part_idx) || joined_by(StaffGroupType::SubBracket, part_idx) {
+                    self.bracket
+                } else {
+                    self.ungrouped
+                }
+            })
+            .collect()
+    }
+}
+
+/// Widths of the music glyphs and thicknesses of the lines, measured from the music font
+#[derive(Debug, Clone)]
+pub struct GlyphWidths {
+    pub head_black: f32,
+    pub head_white: f32,
+    pub head_whole: f32,
+    pub accidental_sharp: f32,
+    pub accidental_flat: f32,
+    pub accidental_natural: f32,
+    pub accidental_double_sharp: f32,
+    pub accidental_double_flat: f32,
+    pub clef: f32,
+    pub flag: f32,
+    pub rest_whole: f32,
+    pub rest_half: f32,
+    pub rest_quarter: f32,
+    pub rest_eighth: f32,
+    pub rest_sixteenth: f32,
+    pub staff_line: f32,
+    pub stem: f32,
+    pub ledger_line: f32,
+    pub barline_thin: f32,
+    pub barline_thick: f32,
+    pub barline_gap: f32,
+    pub beam: f32,
+    pub beam_gap: f32,
+}
+
+impl Default for GlyphWidths {
+    // the measures of the built-in music font, for layouts without a font context
+    fn default() -> Self {
+        GlyphWidths {
+            head_black: SPACE * 1.3,
+            head_white: SPACE * 1.365,
+            head_whole: SPACE * 1.664,
+            accidental_sharp: SPACE * 1.097,
+            accidental_flat: SPACE * 0.906,
+            accidental_natural: SPACE * 0.818,
+            accidental_double_sharp: SPACE * 1.249,
+            accidental_double_flat: SPACE * 1.812,
+            clef: SPACE * 2.713,
+            flag: SPACE * 1.201,
+            rest_whole: SPACE * 1.496,
+            rest_half: SPACE * 1.496,
+            rest_quarter: SPACE * 1.033,
+            rest_eighth: SPACE * 1.01,
+            rest_sixteenth: SPACE * 1.3,
+            staff_line: STAFF_LINE_THICKNESS,
+            stem: STEM_WIDTH,
+            ledger_line: LEDGER_LINE_THICKNESS,
+            barline_thin: BARLINE_THIN,
+            barline_thick: BARLINE_THICK,
+            barline_gap: BARLINE_GAP,
+            beam: BEAM_THICKNESS,
+            beam_gap: BEAM_GAP,
+        }
+    }
+}
+
+impl GlyphWidths {
+    pub fn from_font(fcx: &FontContext) -> GlyphWidths {
+        let width = |name: &str| fcx.get_music_glyph_width(name, SPACE).unwrap_or(SPACE);
+        // stems meet the heads at their anchor, when the font has one
+        let head_width = |name: &str| match fcx.get_music_glyph_anchors(name).and_then(|anchors| anchors.stem_up_se) {
+            Some((x, _)) => x * SPACE,
+            None => width(name),
+        };
+        // line thicknesses from the engraving defaults, in staff spaces
+        let defaults = fcx.get_engraving_defaults();
+        let thickness = |value: Option<f32>, fallback: f32| value.map(|value| value * SPACE).unwrap_or(fallback);
+        GlyphWidths {
+            head_black: head_width("noteheadBlack"),
+            head_white: head_width("noteheadHalf"),
+            head_whole: width("noteheadWhole"),
+            accidental_sharp: width("accidentalSharp"),
+            accidental_flat: width("accidentalFlat"),
+            accidental_natural: width("accidentalNatural"),
+            accidental_double_sharp: width("accidentalDoubleSharp"),
+            // fonts without the glyph get two flats
+            accidental_double_flat: fcx.get_music_glyph_width("accidentalDoubleFlat", SPACE).unwrap_or(width("accidentalFlat") * 2.0),
+            clef: width("gClef").max(width("fClef")).max(width("cClef")),
+            flag: width("flag8thUp").max(width("flag8thDown")),
+            rest_whole: width("restWhole"),
+            rest_half: width("restHalf"),
+            rest_quarter: width("restQuarter"),
+            rest_eighth: width("rest8th"),
+            rest_sixteenth: width("rest16th"),
+            staff_line: thickness(defaults.staff_line_thickness, STAFF_LINE_THICKNESS),
+            stem: thickness(defaults.stem_thickness, STEM_WIDTH),
+            ledger_line: thickness(defaults.leger_line_thickness, LEDGER_LINE_THICKNESS),
+            barline_thin: thickness(defaults.thin_barline_thickness, BARLINE_THIN),
+            barline_thick: thickness(defaults.thick_barline_thickness, BARLINE_THICK),
+            barline_gap: thickness(defaults.barline_separation, BARLINE_GAP),
+            beam: thickness(defaults.beam_thickness, BEAM_THICKNESS),
+            beam_gap: thickness(defaults.beam_spacing, BEAM_GAP),
+        }
+    }
+
+    pub fn get_head_width(&self, duration: &NoteDuration) -> f32 {
+        match duration.get_head_type() {
+            HeadType::White => self.head_white,
+            HeadType::Whole | HeadType::Brevis => self.head_whole,
+            HeadType::Black => self.head_black,
+        }
+    }
+
+    pub fn get_accidental_width(&self, accidental: &Accidental) -> f32 {
+        match accidental {
+            Accidental::Flat => self.accidental_flat,
+            Accidental::Natural => self.accidental_natural,
+            Accidental::DoubleSharp => self.accidental_double_sharp,
+            Accidental::DoubleFlat => self.accidental_double_flat,
+            _ => self.accidental_sharp,
+        }
+    }
+
+    pub fn get_rest_width(&self, rtype: &RestType) -> f32 {
+        match rtype {
+            RestType::Brevis | RestType::Whole => self.rest_whole,
+            RestType::Half => self.rest_half,
+            RestType::Quarter => self.rest_quarter,
+            RestType::Eighth => self.rest_eighth,
+            RestType::Sixteenth => self.rest_sixteenth,
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct ScoreContext {
     pub grid_columns: RefCell<Vec<Vec<GridItemType<GlyphItem>>>>,
     pub grid_column_sysitem_ids: RefCell<Vec<usize>>,
     pub grid_column_allotment: RefCell<Vec<f32>>,
+    /// First grid column of each core column, music columns span one grid column per position
+    pub map_colidx_gridcolidx: RefCell<BTreeMap<usize, usize>>,
     pub map_head_position: RefCell<BTreeMap<usize, StemHeadPosition>>,
+    /// Row and grid column each note is drawn in, the row differs from the part for notes moved to a neighbouring staff
+    pub map_noteid_gridcell: RefCell<BTreeMap<usize, (usize, usize)>>,
+    pub staff_spacing: RefCell<StaffSpacing>,
+    /// Condense runs of empty bars into multi-measure rests, for single part extractions
+    pub multimeasure_rests: RefCell<bool>,
+    /// Number of bars of the multi-measure rest starting at a music column
+    pub map_colidx_multirest: RefCell<BTreeMap<usize, usize>>,
+    pub glyph_widths: RefCell<GlyphWidths>,
 }
 
 impl ScoreContext {
@@ -19,10 +208,51 @@ impl ScoreContext {
             grid_columns: RefCell::new(Vec::new()),
             grid_column_sysitem_ids: RefCell::new(Vec::new()),
             grid_column_allotment: RefCell::new(Vec::new()),
+            map_colidx_gridcolidx: RefCell::new(BTreeMap::new()),
             map_head_position: RefCell::new(BTreeMap::new()),
+            map_noteid_gridcell: RefCell::new(BTreeMap::new()),
+            staff_spacing: RefCell::new(StaffSpacing::default()),
+            multimeasure_rests: RefCell::new(false),
+            map_colidx_multirest: RefCell::new(BTreeMap::new()),
+            glyph_widths: RefCell::new(GlyphWidths::default()),
         };
         Box::leak(Box::new(scx))
     }
 
     // Vec<Vec<GridItemType<GlyphItem>>>
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glyph_widths() {
+        // the defaults are the measures of the built-in font
+        let fcx = FontContext::new();
+        let (default, measured) = (GlyphWidths::default(), GlyphWidths::from_font(fcx));
+        for (a, b) in [
+            (default.head_black, measured.head_black),
+            (default.accidental_flat, measured.accidental_flat),
+            (default.clef, measured.clef),
+            (default.rest_whole, measured.rest_whole),
+        ] {
+            assert!((a - b).abs() < 0.01);
+        }
+
+        // from a SMuFL metadata file, with the heads as wide as their stem anchors and the lines from the engraving defaults
+        fcx.load_smufl_font(
+            include_bytes!("../../fonts/assets/MTF-Cadence-Fin.ttf").to_vec(),
+            include_str!("../../fonts/assets/bravura_metadata_excerpt.json"),
+        )
+        .unwrap();
+        let widths = GlyphWidths::from_font(fcx);
+        assert!((widths.head_black - 11.8).abs() < 0.001 && (widths.head_white - 11.8).abs() < 0.001);
+        assert!((widths.head_whole - 16.88).abs() < 0.001 && (widths.rest_whole - 11.28).abs() < 0.001);
+        assert!((widths.staff_line - 1.3).abs() < 0.001 && (widths.stem - 1.2).abs() < 0.001 && (widths.ledger_line - 1.6).abs() < 0.001);
+        assert!((widths.barline_thin - 1.6).abs() < 0.001 && (widths.barline_thick - 5.0).abs() < 0.001 && (widths.barline_gap - 4.0).abs() < 0.001);
+        assert!((widths.beam - 5.0).abs() < 0.001 && (widths.beam_gap - 2.5).abs() < 0.001);
+        // the stand-in font has no glyph at the SMuFL codepoint of the flat, so it gets a staff space
+        assert_eq!(widths.accidental_flat, SPACE);
+    }
+}
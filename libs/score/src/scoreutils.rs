@@ -9,12 +9,12 @@ use core::{
 };
 use std::collections::BTreeMap;
 
-use graphics::{color::Color, rectangle::Rectangle};
+use graphics::rectangle::Rectangle;
 use grid::griditem::GridItemType;
 
 use crate::{
     complex::{collect_accidentals, create_glyphsrectangles_accidentals, create_glyphsrectangles_note, sort_accidentals},
-    constants::{BARLINE_DOUBLE_WIDTH, BARLINE_FINAL_WIDTH, BARLINE_WIDTH, CLEF_WIDTH, SPACE, SPACE2, SPACE4, SPACE_BEFORE_FIRST_NOTE_IN_BAR},
+    constants::{BARLINE_DOUBLE_WIDTH, BARLINE_FINAL_WIDTH, BARLINE_WIDTH, SPACE, SPACE2, SPACE4, SPACE_BEFORE_FIRST_NOTE_IN_BAR},
     glyphitem::{ComplexGlyphsRectangles, GlyphItem, GlyphRectangle, PartGlyphsRectangles, SysitemGlyphsRectangles},
     scorecontext::ScoreContext,
 };
@@ -85,7 +85,7 @@ impl ScoreUtils {
         for clef in _clefs {
             // println!("Clef: {:?}", clef);
             let glyph: GlyphItem = GlyphItem::Clef(clef.clone());
-            let rect = (0.0, -SPACE2, CLEF_WIDTH, SPACE4); // Placeholder rectangle
+            let rect = (0.0, -SPACE2, scx.glyph_widths.borrow().clef, SPACE4);
             column_griditems.push(GridItemType::Rectangles(vec![(rect, glyph.clone())]));
         }
 
@@ -94,7 +94,7 @@ impl ScoreUtils {
             let glyph = match column_griditems.len() {
                 0 => GlyphItem::Clef(ClefSignature::Treble),
                 1 => GlyphItem::Clef(ClefSignature::Bass),
-                _ => GlyphItem::XRect(Color::Gray),
+                _ => GlyphItem::Space,
             };
 
             let rect = (0.0, -SPACE2, 1.0, SPACE4); // Placeholder rectangle
@@ -107,13 +107,14 @@ impl ScoreUtils {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn build_sysitem_parts(
         scx: &ScoreContext,
         complexes: &[Complex],
         sysitem_id: usize,
         _parts_ids: &Vec<PartId>,
         _sum_duration: &SumDuration,
-        complexes_infos: &Vec<BTreeMap<usize, ComplexInfo>>,
+        complexes_infos: &[BTreeMap<usize, ComplexInfo>],
         positions_durations: &BTreeMap<usize, usize>,
         expected_parts_count: usize,
     ) -> Result<(), Box<dyn std::error::Error>> {
@@ -141,17 +142,18 @@ impl ScoreUtils {
 
     pub fn create_glyphsrectangles_complex(scx: &ScoreContext, _partidx: usize, _complex: &Complex) -> ComplexGlyphsRectangles {
         let mut rectangles: ComplexGlyphsRectangles = Vec::new();
+        let widths = scx.glyph_widths.borrow();
 
         match _complex.ctype {
             ComplexType::Upper(ref note) => {
                 // note
                 let mut note_rectangles: Vec<(Rectangle, GlyphItem)> = Vec::new();
 
-                let leftmost_head_x = create_glyphsrectangles_note(note, &scx.map_head_position.borrow(), &mut note_rectangles);
+                let leftmost_head_x = create_glyphsrectangles_note(&widths, note, &scx.map_head_position.borrow(), &mut note_rectangles);
                 // accidentals
                 let mut accidentals = collect_accidentals(note);
                 sort_accidentals(&mut accidentals);
-                let leftmost_accidental_x = create_glyphsrectangles_accidentals(&accidentals, &mut note_rectangles);
+                let leftmost_accidental_x = create_glyphsrectangles_accidentals(&widths, &accidentals, &mut note_rectangles);
 
                 if note.position == 0 {
                     note_rectangles.push(create_space_rectangle_for_first_note_in_bar(leftmost_accidental_x.min(leftmost_head_x)));
@@ -162,12 +164,12 @@ impl ScoreUtils {
             ComplexType::Lower(ref note) => {
                 // note
                 let mut note_rectangles = Vec::new();
-                let leftmost_head_x = create_glyphsrectangles_note(note, &scx.map_head_position.borrow(), &mut note_rectangles);
+                let leftmost_head_x = create_glyphsrectangles_note(&widths, note, &scx.map_head_position.borrow(), &mut note_rectangles);
 
                 // accidentals
                 let mut accidentals = collect_accidentals(note);
                 sort_accidentals(&mut accidentals);
-                let leftmost_accidental_x = create_glyphsrectangles_accidentals(&accidentals, &mut note_rectangles);
+                let leftmost_accidental_x = create_glyphsrectangles_accidentals(&widths, &accidentals, &mut note_rectangles);
 
                 if note.position == 0 {
                     note_rectangles.push(create_space_rectangle_for_first_note_in_bar(leftmost_accidental_x.min(leftmost_head_x)));
@@ -181,16 +183,16 @@ impl ScoreUtils {
                 // note
                 let mut note_rectangles = Vec::new();
 
-                let leftmost_upper_x = create_glyphsrectangles_note(upper, &scx.map_head_position.borrow(), &mut note_rectangles);
+                let leftmost_upper_x = create_glyphsrectangles_note(&widths, upper, &scx.map_head_position.borrow(), &mut note_rectangles);
 
-                let leftmost_lower_x = create_glyphsrectangles_note(lower, &scx.map_head_position.borrow(), &mut note_rectangles);
+                let leftmost_lower_x = create_glyphsrectangles_note(&widths, lower, &scx.map_head_position.borrow(), &mut note_rectangles);
 
                 // accidentals
                 let mut accidentals = collect_accidentals(upper);
                 accidentals.extend(collect_accidentals(lower));
                 sort_accidentals(&mut accidentals);
 
-                let leftmost_accidental_x = create_glyphsrectangles_accidentals(&accidentals, &mut note_rectangles);
+                let leftmost_accidental_x = create_glyphsrectangles_accidentals(&widths, &accidentals, &mut note_rectangles);
 
                 if upper.position == 0 {
                     note_rectangles.push(create_space_rectangle_for_first_note_in_bar(leftmost_accidental_x.min(leftmost_upper_x.min(leftmost_lower_x))));
@@ -206,10 +208,7 @@ impl ScoreUtils {
 }
 
 fn create_space_rectangle_for_first_note_in_bar(left_x: f32) -> ((f32, f32, f32, f32), GlyphItem) {
-    (
-        (left_x - SPACE_BEFORE_FIRST_NOTE_IN_BAR, -SPACE, SPACE_BEFORE_FIRST_NOTE_IN_BAR, SPACE2),
-        GlyphItem::XRect(Color::Purple),
-    )
+    ((left_x - SPACE_BEFORE_FIRST_NOTE_IN_BAR, -SPACE, SPACE_BEFORE_FIRST_NOTE_IN_BAR, SPACE2), GlyphItem::Space)
 }
 
 #[derive(Debug)]
@@ -8,9 +8,10 @@ use std::fs;
 use svg::builder::SvgBuilder;
 
 fn main() {
-    let mut items = GraphicItems::new();
-    items.push(GraphicItem::Rect(0., 0., 20., 20., Stroke::None, Fill::Solid(Color::Tomato), None));
-    items.push(GraphicItem::Line(0., 0., 50., 15., Stroke::Solid(1., Color::DodgerBlue), None));
+    let items: GraphicItems = vec![
+        GraphicItem::Rect(0., 0., 20., 20., Stroke::None, Fill::Solid(Color::Tomato), None),
+        GraphicItem::Line(0., 0., 50., 15., Stroke::Solid(1., Color::DodgerBlue), None),
+    ];
     let mut svg = SvgBuilder::new();
     let svg_string = svg.build(items, None);
 
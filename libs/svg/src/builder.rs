@@ -1,158 +1,308 @@
 use std::collections::HashSet;
 
-use graphics::fill::Fill;
-use graphics::graphicitem::{items_bounding_box, items_move, GraphicItem, GraphicItems, PathCache};
+use fonts::fontcontext::FontContext;
+use graphics::backend::{RenderBackend, render_items};
+use graphics::color::Color;
+use graphics::fill::{Fill, LinearGradient};
+use graphics::graphicitem::{GraphicItems, PathCache, TEXT_SIZE, TextMetrics};
+use graphics::path::{PathSegment, PathUtils};
+use graphics::stroke::{LineCap, LineJoin, Stroke};
+use graphics::transform::Transform;
+use xmlwriter::XmlWriter;
 
-use graphics::path::PathUtils;
-use graphics::stroke::Stroke;
-
-#[derive(Debug)]
 pub struct SvgBuilder {
     path_cache: HashSet<String>,
+    code: Option<String>,
+    svg: XmlWriter,
+    // id of the note whose elements are being written in a group of their own
+    note_group: Option<String>,
+    fonts: Option<&'static FontContext<'static>>,
+    // gradients referenced by fills, written as defs at the end of the document
+    gradients: Vec<LinearGradient>,
+    // painted under the drawing, transparent when none
+    background: Option<Color>,
+}
+pub const SVG_BORDER_MARGIN: f32 = 3.0;
+
+impl Default for SvgBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
-const SVG_BORDER_MARGIN: f32 = 3.0;
 
 impl SvgBuilder {
     pub fn new() -> SvgBuilder {
-        SvgBuilder { path_cache: HashSet::new() }
+        SvgBuilder {
+            path_cache: HashSet::new(),
+            code: None,
+            svg: XmlWriter::new(xmlwriter::Options::default()),
+            note_group: None,
+            fonts: None,
+            gradients: Vec::new(),
+            background: Some(Color::White),
+        }
+    }
+
+    pub fn with_background(mut self, background: Option<Color>) -> SvgBuilder {
+        self.background = background;
+        self
+    }
+
+    pub fn build(&mut self, items: GraphicItems, code: Option<String>) -> String {
+        self.code = code;
+        render_items(self, items, SVG_BORDER_MARGIN)
     }
 
-    pub fn build(&mut self, mut items: GraphicItems, code: Option<String>) -> String {
-        let bbox = items_bounding_box(&items);
-        items = items_move(items, SVG_BORDER_MARGIN + -bbox.0, SVG_BORDER_MARGIN + -bbox.1);
+    /// Consecutive elements of a note go into a `<g>` carrying the note, part and bar of the first of them
+    fn start_element(&mut self, name: &str, id: Option<&str>) {
+        let words: Vec<&str> = id.map(|id| id.split_whitespace().collect()).unwrap_or_default();
+        let note = words.iter().skip(1).find(|word| word.starts_with("note-"));
+        if self.note_group.as_deref() != note.copied() {
+            self.end_note_group();
+            if let Some(note) = note {
+                self.svg.start_element("g");
+                self.svg.write_attribute("id", note);
+                self.svg.write_attribute("class", "note");
+                for (name, value) in words.iter().skip(1).filter_map(|word| word.split_once('-')) {
+                    if ["note", "part", "bar"].contains(&name) {
+                        self.svg.write_attribute(&format!("data-{}", name), value);
+                    }
+                }
+                self.note_group = Some(note.to_string());
+            }
+        }
+        self.svg.start_element(name);
+        self.write_id(&words);
+    }
 
-        let svg_width_value = bbox.2 + (-bbox.0) + (2.0 * SVG_BORDER_MARGIN);
-        let svg_height_value = bbox.3 + (-bbox.1) + (2.0 * SVG_BORDER_MARGIN);
-        let mut svg = xmlwriter::XmlWriter::new(xmlwriter::Options::default());
+    fn end_note_group(&mut self) {
+        if self.note_group.take().is_some() {
+            self.svg.end_element();
+        }
+    }
+
+    // the kind becomes the class, and the id if it has a value, the objects it belongs to become data attributes
+    fn write_id(&mut self, words: &[&str]) {
+        let Some((kind, belongs_to)) = words.split_first() else { return };
+        match kind.split_once('-') {
+            Some((class, _)) => {
+                self.svg.write_attribute("id", kind);
+                self.svg.write_attribute("class", class);
+            }
+            None => self.svg.write_attribute("class", kind),
+        }
+        for (name, value) in belongs_to.iter().filter_map(|word| word.split_once('-')) {
+            self.svg.write_attribute(&format!("data-{}", name), value);
+        }
+    }
+
+    // only the style attributes that differ from the svg defaults
+    fn write_stroke(&mut self, stroke: &Stroke) {
+        let Some((w, color, style)) = stroke.get_style() else { return };
+        self.svg.write_attribute("stroke", &color);
+        self.svg.write_attribute("stroke-width", &w);
+        if !style.dash.is_empty() {
+            let dash: Vec<String> = style.dash.iter().map(|length| length.to_string()).collect();
+            self.svg.write_attribute("stroke-dasharray", dash.join(" ").as_str());
+        }
+        match style.cap {
+            LineCap::Butt => {}
+            LineCap::Round => self.svg.write_attribute("stroke-linecap", "round"),
+            LineCap::Square => self.svg.write_attribute("stroke-linecap", "square"),
+        }
+        match style.join {
+            LineJoin::Miter => {}
+            LineJoin::Round => self.svg.write_attribute("stroke-linejoin", "round"),
+            LineJoin::Bevel => self.svg.write_attribute("stroke-linejoin", "bevel"),
+        }
+        if style.opacity < 1.0 {
+            self.svg.write_attribute("stroke-opacity", &style.opacity);
+        }
+    }
+
+    fn write_fill(&mut self, fill: &Fill) {
+        match fill {
+            Fill::None => self.svg.write_attribute("fill", "none"),
+            Fill::Solid(color) => self.svg.write_attribute("fill", color),
+            Fill::LinearGradient(gradient) => {
+                self.gradients.push(gradient.clone());
+                self.svg.write_attribute_fmt("fill", format_args!("url(#gradient-{})", self.gradients.len()));
+            }
+        }
+    }
+
+    fn write_gradients(&mut self) {
+        let gradients = std::mem::take(&mut self.gradients);
+        if gradients.is_empty() {
+            return;
+        }
+        self.svg.start_element("defs");
+        for (idx, gradient) in gradients.iter().enumerate() {
+            self.svg.start_element("linearGradient");
+            self.svg.write_attribute_fmt("id", format_args!("gradient-{}", idx + 1));
+            self.svg.write_attribute("x1", &gradient.x1);
+            self.svg.write_attribute("y1", &gradient.y1);
+            self.svg.write_attribute("x2", &gradient.x2);
+            self.svg.write_attribute("y2", &gradient.y2);
+            for (offset, color) in &gradient.stops {
+                self.svg.start_element("stop");
+                self.svg.write_attribute("offset", offset);
+                self.svg.write_attribute("stop-color", color);
+                self.svg.end_element();
+            }
+            self.svg.end_element();
+        }
+        self.svg.end_element();
+    }
+}
+
+impl RenderBackend for SvgBuilder {
+    type Output = String;
+
+    fn begin(&mut self, width: f32, height: f32) {
+        let svg = &mut self.svg;
         svg.start_element("svg");
         svg.write_attribute("xmlns", "http://www.w3.org/2000/svg");
         svg.write_attribute("xmlns:xlink", "http://www.w3.org/1999/xlink");
-        svg.write_attribute("width", svg_width_value.to_string().as_str());
-        svg.write_attribute("height", svg_height_value.to_string().as_str());
-        svg.write_attribute_fmt("viewBox", format_args!("{} {} {} {}", 0, 0, svg_width_value, svg_height_value));
-        if let Some(code) = code {
+        svg.write_attribute("width", width.to_string().as_str());
+        svg.write_attribute("height", height.to_string().as_str());
+        svg.write_attribute_fmt("viewBox", format_args!("{} {} {} {}", 0, 0, width, height));
+        if let Some(code) = &self.code {
             svg.write_attribute("code", code.as_str());
         }
-        // white background
-        svg.start_element("rect");
-        svg.write_attribute("x", "0");
-        svg.write_attribute("y", "0");
-        svg.write_attribute("width", svg_width_value.to_string().as_str());
-        svg.write_attribute("height", svg_height_value.to_string().as_str());
-        svg.write_attribute("fill", "white");
-        svg.end_element();
-
-        for item in items.iter() {
-            match item {
-                GraphicItem::Line(x1, y1, x2, y2, stroke, _id) => {
-                    //
-                    svg.start_element("line");
-                    svg.write_attribute("x1", x1);
-                    svg.write_attribute("y1", y1);
-                    svg.write_attribute("x2", x2);
-                    svg.write_attribute("y2", y2);
-                    if let Stroke::Solid(w, color) = stroke {
-                        svg.write_attribute("stroke", color);
-                        svg.write_attribute("stroke-width", w);
-                    }
-                    svg.end_element();
-                }
+        if let Some(background) = &self.background {
+            svg.start_element("rect");
+            svg.write_attribute("x", "0");
+            svg.write_attribute("y", "0");
+            svg.write_attribute("width", width.to_string().as_str());
+            svg.write_attribute("height", height.to_string().as_str());
+            svg.write_attribute("fill", background);
+            svg.end_element();
+        }
+    }
 
-                GraphicItem::Rect(x, y, w, h, stroke, fill, _id) => {
-                    svg.start_element("rect");
-                    svg.write_attribute("x", x);
-                    svg.write_attribute("y", y);
-                    svg.write_attribute("width", w);
-                    svg.write_attribute("height", h);
-                    if let Stroke::Solid(w, color) = stroke {
-                        svg.write_attribute("stroke", color);
-                        svg.write_attribute("stroke-width", w);
-                    }
-                    if let Fill::Solid(color) = fill {
-                        svg.write_attribute("fill", color);
-                    } else {
-                        svg.write_attribute("fill", "none");
-                    }
-                    svg.end_element();
-                }
+    fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, stroke: &Stroke, id: Option<&str>) {
+        self.start_element("line", id);
+        self.svg.write_attribute("x1", &x1);
+        self.svg.write_attribute("y1", &y1);
+        self.svg.write_attribute("x2", &x2);
+        self.svg.write_attribute("y2", &y2);
+        self.write_stroke(stroke);
+        self.svg.end_element();
+    }
 
-                GraphicItem::Path(segments, x, y, stroke, fill, cache) => {
-                    match &cache {
-                        Some(PathCache::UseCache) => {
-                            let s = format!("{:?}", segments);
-                            let md5: String = format!("{:?}", md5::compute(s));
-                            if self.path_cache.contains(&md5) {
-                                // just add a use element
-                                svg.start_element("use");
-                                svg.write_attribute("href", format!("#{}", md5.as_str()).as_str());
-                                svg.write_attribute("x", format!("{}", x).as_str());
-                                svg.write_attribute("y", format!("{}", y).as_str());
-
-                                if let Stroke::Solid(w, color) = stroke {
-                                    svg.write_attribute("stroke", color);
-                                    svg.write_attribute("stroke-width", w);
-                                }
-                                if let Fill::Solid(color) = fill {
-                                    svg.write_attribute("fill", color);
-                                } else {
-                                    svg.write_attribute("fill", "none");
-                                }
-
-                                svg.end_element();
-                            } else {
-                                // store the hashed path in a g wrapper element
-                                self.path_cache.insert(md5.clone());
-
-                                svg.start_element("g");
-                                svg.write_attribute("transform", format!("translate({}, {})", x, y).as_str());
-                                svg.write_attribute("visibility", "hidden".to_string().as_str());
-
-                                svg.start_element("path");
-                                svg.write_attribute("id", md5.as_str());
-                                svg.write_attribute("d", PathUtils::path_to_string(segments.to_vec()).as_str());
-
-                                svg.end_element();
-                                svg.end_element();
-
-                                // Store the use data
-                                svg.start_element("use");
-                                svg.write_attribute("href", format!("#{}", md5.as_str()).as_str());
-                                svg.write_attribute("x", format!("{}", x).as_str());
-                                svg.write_attribute("y", format!("{}", y).as_str());
-                                svg.end_element();
-                            }
-                        }
-
-                        None => {
-                            svg.start_element("path");
-                            svg.write_attribute("d", PathUtils::path_to_string_move(segments.to_vec(), *x, *y).as_str());
-                            if let Stroke::Solid(w, color) = stroke {
-                                svg.write_attribute("stroke", color);
-                                svg.write_attribute("stroke-width", w);
-                            }
-                            if let Fill::Solid(color) = fill {
-                                svg.write_attribute("fill", color);
-                            } else {
-                                svg.write_attribute("fill", "none");
-                            }
-                            svg.end_element();
-                        }
-                    }
-                }
+    fn rect(&mut self, x: f32, y: f32, w: f32, h: f32, stroke: &Stroke, fill: &Fill, id: Option<&str>) {
+        self.start_element("rect", id);
+        self.svg.write_attribute("x", &x);
+        self.svg.write_attribute("y", &y);
+        self.svg.write_attribute("width", &w);
+        self.svg.write_attribute("height", &h);
+        self.write_stroke(stroke);
+        self.write_fill(fill);
+        self.svg.end_element();
+    }
+
+    fn ellipse(&mut self, cx: f32, cy: f32, rx: f32, ry: f32, stroke: &Stroke, fill: &Fill, id: Option<&str>) {
+        self.start_element("ellipse", id);
+        self.svg.write_attribute("cx", &cx);
+        self.svg.write_attribute("cy", &cy);
+        self.svg.write_attribute("rx", &rx);
+        self.svg.write_attribute("ry", &ry);
+        self.write_stroke(stroke);
+        self.write_fill(fill);
+        self.svg.end_element();
+    }
 
-                GraphicItem::Text(x, y, text, _xtra) => {
-                    svg.start_element("text");
-                    svg.write_attribute("x", x);
-                    svg.write_attribute("y", y);
-                    svg.write_text(text);
-                    svg.end_element();
+    fn path(&mut self, segments: &[PathSegment], x: f32, y: f32, stroke: &Stroke, fill: &Fill, cache: Option<&PathCache>, id: Option<&str>) {
+        match cache {
+            Some(PathCache::UseCache) => {
+                let s = format!("{:?}", segments);
+                let md5: String = format!("{:?}", md5::compute(s));
+                if self.path_cache.contains(&md5) {
+                    // just add a use element
+                    self.start_element("use", id);
+                    self.svg.write_attribute("href", format!("#{}", md5.as_str()).as_str());
+                    self.svg.write_attribute("x", format!("{}", x).as_str());
+                    self.svg.write_attribute("y", format!("{}", y).as_str());
+                    self.write_stroke(stroke);
+                    self.write_fill(fill);
+                    self.svg.end_element();
+                } else {
+                    // store the hashed path in a g wrapper element
+                    self.path_cache.insert(md5.clone());
+
+                    self.svg.start_element("g");
+                    self.svg.write_attribute("transform", format!("translate({}, {})", x, y).as_str());
+                    self.svg.write_attribute("visibility", "hidden".to_string().as_str());
+
+                    self.svg.start_element("path");
+                    self.svg.write_attribute("id", md5.as_str());
+                    self.svg.write_attribute("d", PathUtils::path_to_string(segments.to_vec()).as_str());
+
+                    self.svg.end_element();
+                    self.svg.end_element();
+
+                    // Store the use data
+                    self.start_element("use", id);
+                    self.svg.write_attribute("href", format!("#{}", md5.as_str()).as_str());
+                    self.svg.write_attribute("x", format!("{}", x).as_str());
+                    self.svg.write_attribute("y", format!("{}", y).as_str());
+                    self.write_stroke(stroke);
+                    self.write_fill(fill);
+                    self.svg.end_element();
                 }
+            }
 
-                _ => todo!("Unimplemented item type"),
+            None => {
+                self.start_element("path", id);
+                self.svg.write_attribute("d", PathUtils::path_to_string_move(segments.to_vec(), x, y).as_str());
+                self.write_stroke(stroke);
+                self.write_fill(fill);
+                self.svg.end_element();
             }
         }
+    }
+
+    fn text(&mut self, x: f32, y: f32, text: &str, color: &Color, id: Option<&str>) {
+        self.start_element("text", id);
+        self.svg.write_attribute("x", &x);
+        self.svg.write_attribute("y", &y);
+        self.svg.write_attribute("fill", color);
+        // the font the text is measured with
+        self.svg.write_attribute("font-family", "Open Sans, sans-serif");
+        self.svg.write_attribute("font-size", &TEXT_SIZE);
+        self.svg.write_text(text);
+        self.svg.end_element();
+    }
+
+    fn begin_group(&mut self, id: Option<&str>, class: Option<&str>, transform: &Transform) {
+        self.end_note_group();
+        self.svg.start_element("g");
+        if let Some(id) = id {
+            self.svg.write_attribute("id", id);
+        }
+        if let Some(class) = class {
+            self.svg.write_attribute("class", class);
+        }
+        if !transform.is_identity() {
+            let Transform { a, b, c, d, e, f } = transform;
+            self.svg.write_attribute_fmt("transform", format_args!("matrix({} {} {} {} {} {})", a, b, c, d, e, f));
+        }
+    }
+
+    fn end_group(&mut self) {
+        self.end_note_group();
+        self.svg.end_element();
+    }
+
+    fn finish(&mut self) -> String {
+        self.end_note_group();
+        self.write_gradients();
+        self.path_cache.clear();
+        std::mem::replace(&mut self.svg, XmlWriter::new(xmlwriter::Options::default())).end_document()
+    }
 
-        let str = svg.end_document();
-        str
+    fn text_metrics(&mut self) -> &dyn TextMetrics {
+        *self.fonts.get_or_insert_with(FontContext::new)
     }
 }
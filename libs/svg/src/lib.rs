@@ -3,16 +3,19 @@ pub mod builder;
 #[cfg(test)]
 mod tests {
     use graphics::{
+        backend::RenderBackend,
         color::Color,
-        fill::Fill,
+        fill::{Fill, LinearGradient},
         graphicitem::{GraphicItem, GraphicItems},
-        stroke::Stroke,
+        stroke::{LineCap, LineJoin, Stroke, StrokeStyle},
+        transform::Transform,
     };
     use std::fs;
 
     use crate::builder::SvgBuilder;
 
     #[test]
+    #[allow(clippy::vec_init_then_push)]
     fn test_svg() {
         let mut items = GraphicItems::new();
         items.push(GraphicItem::Rect(0., 0., 20., 20., Stroke::None, Fill::Solid(Color::Tomato), None));
@@ -22,4 +25,51 @@ mod tests {
 
         fs::write("out/test.svg", svg_string).unwrap();
     }
+
+    #[test]
+    fn test_group() {
+        let mut svg = SvgBuilder::new();
+        svg.begin(20., 20.);
+        svg.begin_group(Some("note-1"), None, &Transform::translate(2., 3.));
+        svg.rect(0., 0., 5., 5., &Stroke::None, &Fill::Solid(Color::Black), None);
+        svg.end_group();
+        let svg_string = svg.finish();
+        assert!(svg_string.contains(r#"<g id="note-1" transform="matrix(1 0 0 1 2 3)">"#));
+
+        // nested groups, moved along with the other items by the border margin
+        let rect = GraphicItem::Rect(0., 0., 5., 5., Stroke::None, Fill::Solid(Color::Black), None);
+        let inner = GraphicItem::Group(vec![rect.clone()], Transform::scale(-1., 1.), Some("flag".to_string()), None);
+        let items: GraphicItems = vec![rect, GraphicItem::Group(vec![inner], Transform::IDENTITY, None, Some("flags".to_string()))];
+        let svg_string = SvgBuilder::new().build(items, None);
+        assert!(svg_string.contains(r#"<g id="flags" transform="matrix(1 0 0 1 8 3)">"#));
+        assert!(svg_string.contains(r#"<g class="flag" transform="matrix(-1 0 0 1 0 0)">"#));
+        assert!(svg_string.contains(r#"width="16" height="11""#));
+    }
+
+    #[test]
+    fn test_ids() {
+        let rect = |x: f32, id: &str| GraphicItem::Rect(x, 0., 5., 5., Stroke::None, Fill::Solid(Color::Black), Some(id.to_string()));
+        let items: GraphicItems = vec![rect(0., "head-3 note-1 part-0 bar-2"), rect(10., "stem note-1 part-0 bar-2"), rect(20., "barline part-0 bar-2")];
+        let svg_string = SvgBuilder::new().build(items, None);
+        assert!(svg_string.contains(r#"<g id="note-1" class="note" data-note="1" data-part="0" data-bar="2">"#));
+        assert!(svg_string.contains(r#"<rect id="head-3" class="head" data-note="1" data-part="0" data-bar="2""#));
+        // the barline is not part of the note
+        assert!(svg_string.find("</g>") < svg_string.find(r#"<rect class="barline" data-part="0" data-bar="2""#));
+    }
+
+    #[test]
+    fn test_stroke_and_fill_styles() {
+        let mut svg = SvgBuilder::new();
+        svg.begin(20., 20.);
+        let style = StrokeStyle::dashed(3., 2.).with_cap(LineCap::Round).with_join(LineJoin::Bevel).with_opacity(0.5);
+        svg.line(0., 0., 10., 0., &Stroke::Styled(1., Color::Black, style), None);
+        svg.line(0., 5., 10., 5., &Stroke::dotted(1., Color::Black, 2.), None);
+        svg.rect(0., 10., 5., 5., &Stroke::None, &Fill::LinearGradient(LinearGradient::horizontal(Color::Yellow, Color::Orange)), None);
+        let svg_string = svg.finish();
+        assert!(svg_string.contains(r#"stroke-dasharray="3 2" stroke-linecap="round" stroke-linejoin="bevel" stroke-opacity="0.5""#));
+        assert!(svg_string.contains(r#"stroke-dasharray="0 2" stroke-linecap="round"/>"#));
+        assert!(svg_string.contains(r#"fill="url(#gradient-1)""#));
+        assert!(svg_string.contains(r#"<linearGradient id="gradient-1" x1="0" y1="0" x2="1" y2="0">"#));
+        assert!(svg_string.contains(r#"<stop offset="1" stop-color="orange"/>"#));
+    }
 }
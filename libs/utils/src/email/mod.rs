@@ -3,11 +3,7 @@ pub struct EmailAddress(String);
 
 impl EmailAddress {
     pub fn new(email: &str) -> Result<Self, &str> {
-        if email.contains('@') {
-            Ok(EmailAddress(email.into()))
-        } else {
-            Err("Invalid email address")
-        }
+        if email.contains('@') { Ok(EmailAddress(email.into())) } else { Err("Invalid email address") }
     }
 }
 
@@ -12,13 +12,13 @@ mod tests {
 
     #[test]
     fn test_slice_ext() {
-        let v = vec![1, 2, 3, 4, 5];
+        let v = [1, 2, 3, 4, 5];
         assert_eq!(v.fourth(), Some(&4));
     }
 
     #[test]
     fn test_half() {
-        let value: f32 = 3.14159;
+        let value: f32 = 3.45678;
         dbg!(value);
         dbg!(value.r2());
         dbg!(value.half()); // Should print 5.0
@@ -42,7 +42,7 @@ mod tests {
             Ok(123)
         }
 
-        let v = vec![1, 2, 3];
+        let v = [1, 2, 3];
         v.iter()
             .map(|x| {
                 let t = testerror()?;